@@ -0,0 +1,9 @@
+//! Write the economy test vectors to `vectors/economy.json`.
+
+use std::path::Path;
+
+fn main() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("vectors/economy.json");
+    std::fs::write(&path, milkerfun::vectors::economy_vectors_json()).expect("write vectors");
+    eprintln!("Wrote {}", path.display());
+}
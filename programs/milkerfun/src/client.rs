@@ -0,0 +1,161 @@
+//! Off-chain helpers for locating the program's accounts. Enabled with the
+//! `client` feature, which also disables the entrypoint.
+
+use anchor_lang::prelude::*;
+
+pub fn config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &crate::ID)
+}
+
+pub fn pool_authority_address(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pool_authority", config.as_ref()], &crate::ID)
+}
+
+pub fn cow_mint_authority_address(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cow_mint_authority", config.as_ref()], &crate::ID)
+}
+
+pub fn grants_authority_address(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"grants_authority", config.as_ref()], &crate::ID)
+}
+
+pub fn milk_price_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"milk_price"], &crate::ID)
+}
+
+pub fn farm_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"farm", owner.as_ref()], &crate::ID)
+}
+
+pub fn cow_lock_address(owner: &Pubkey, unlock_at: i64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cow_lock", owner.as_ref(), unlock_at.to_le_bytes().as_ref()], &crate::ID)
+}
+
+pub fn rental_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rental", farm.as_ref()], &crate::ID)
+}
+
+pub fn escrow_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow", farm.as_ref()], &crate::ID)
+}
+
+pub fn harvest_log_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"harvest_log", farm.as_ref()], &crate::ID)
+}
+
+pub fn attestation_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"attestation", farm.as_ref()], &crate::ID)
+}
+
+pub fn loan_position_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"loan", farm.as_ref()], &crate::ID)
+}
+
+pub fn grant_proposal_address(id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"grant_proposal", id.to_le_bytes().as_ref()], &crate::ID)
+}
+
+pub fn grant_vote_address(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"grant_vote", proposal.as_ref(), voter.as_ref()], &crate::ID)
+}
+
+/// The program's ProgramData account, holding its upgrade authority
+pub fn program_data_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[crate::ID.as_ref()],
+        &pubkey!("BPFLoaderUpgradeab1e11111111111111111111111"),
+    )
+}
+
+pub fn quote_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"quote"], &crate::ID)
+}
+
+pub fn penalty_grace_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"penalty_grace"], &crate::ID)
+}
+
+pub fn community_goal_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"community_goal"], &crate::ID)
+}
+
+pub fn yield_claim_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"yield_claim", farm.as_ref()], &crate::ID)
+}
+
+pub fn yield_claim_mint_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"yield_claim_mint", farm.as_ref()], &crate::ID)
+}
+
+pub fn marketing_authority_address(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"marketing_authority", config.as_ref()], &crate::ID)
+}
+
+pub fn starter_pack_claim_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"starter_pack", owner.as_ref()], &crate::ID)
+}
+
+pub fn delegation_address(farm: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"delegation", farm.as_ref(), delegate.as_ref()], &crate::ID)
+}
+
+pub fn cow_vault_address(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cow_vault", config.as_ref()], &crate::ID)
+}
+
+pub fn vault_share_mint_address(cow_vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault_shares", cow_vault.as_ref()], &crate::ID)
+}
+
+pub fn referrer_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"referrer", owner.as_ref()], &crate::ID)
+}
+
+pub fn checkpoint_address(farm: &Pubkey, checkpoint_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"checkpoint", farm.as_ref(), &checkpoint_id.to_le_bytes()], &crate::ID)
+}
+
+pub fn portfolio_address(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"portfolio", wallet.as_ref()], &crate::ID)
+}
+
+/// Receipt written by the farm's `sequence`-th withdrawal (its withdrawal_count beforehand)
+pub fn withdrawal_receipt_address(farm: &Pubkey, sequence: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt", farm.as_ref(), sequence.to_le_bytes().as_ref()], &crate::ID)
+}
+
+pub fn gift_campaign_address(sponsor: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"gift_campaign", sponsor.as_ref(), id.to_le_bytes().as_ref()], &crate::ID)
+}
+
+pub fn gift_claim_address(campaign: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"gift_claim", campaign.as_ref(), wallet.as_ref()], &crate::ID)
+}
+
+pub fn vesting_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vesting", farm.as_ref()], &crate::ID)
+}
+
+pub fn profile_address(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"profile", wallet.as_ref()], &crate::ID)
+}
+
+pub fn cow_staking_address(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cow_staking", config.as_ref()], &crate::ID)
+}
+
+pub fn cow_staking_vault_address(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cow_staking_vault", config.as_ref()], &crate::ID)
+}
+
+pub fn cow_stake_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cow_stake", owner.as_ref()], &crate::ID)
+}
+
+pub fn breeding_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"breeding", farm.as_ref()], &crate::ID)
+}
+
+pub fn event_address(id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"event", id.to_le_bytes().as_ref()], &crate::ID)
+}
@@ -0,0 +1,175 @@
+//! Program-wide constants: curve parameters, limits and bounds on admin settings.
+
+pub const SECONDS_PER_DAY: i64 = 86400; // 24 * 60 * 60
+
+pub const SECONDS_PER_YEAR: i64 = 365 * SECONDS_PER_DAY;
+
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000; // Scale of Config::acc_reward_per_cow
+
+pub const COW_BASE_PRICE: u64 = 6_000_000_000; // 6,000 MILK (6 decimals)
+
+pub const PRICE_PIVOT: u64 = 2_500; // C_pivot
+
+pub const PRICE_STEEPNESS_BPS: u64 = 25_000; // α = 2.5
+
+pub const REWARD_BASE: u64 = 25_000_000_000; // 25,000 MILK (6 decimals) - B
+
+pub const REWARD_SENSITIVITY_BPS: u64 = 5_000; // α_reward = 0.5
+
+pub const TVL_NORMALIZATION: u64 = 100_000_000_000; // 100,000 MILK (6 decimals) - S
+
+pub const MIN_REWARD_PER_DAY: u64 = 1_000_000_000; // 1,000 MILK per day (6 decimals) - R_min
+
+pub const GREED_MULTIPLIER_BPS: u64 = 80_000; // β = 8.0
+
+pub const GREED_DECAY_PIVOT: u64 = 1_500; // C₀
+
+// Bounds on curve parameters set through update_config
+pub const MAX_COW_BASE_PRICE: u64 = 1_000_000_000_000; // 1,000,000 MILK
+pub const MAX_CURVE_PIVOT: u64 = 1_000_000; // Cows
+pub const MAX_PRICE_STEEPNESS_BPS: u64 = 50_000; // α ≤ 5
+pub const MAX_REWARD_BASE: u64 = 1_000_000_000_000; // 1,000,000 MILK per cow per day
+pub const MAX_REWARD_SENSITIVITY_BPS: u64 = 100_000; // α_reward ≤ 10
+pub const MIN_TVL_NORMALIZATION: u64 = 1_000_000; // 1 MILK
+pub const MAX_GREED_MULTIPLIER_BPS: u64 = 200_000; // β ≤ 20
+
+pub const INITIAL_TVL: u64 = 100_000_000_000_000; // 100M MILK (6 decimals)
+
+pub const MAX_COWS_PER_TRANSACTION: u64 = 50; // Buy cap at or below the initial pool depth
+
+pub const MAX_BUY_CAP_MULTIPLIER: u64 = 20; // Adaptive buy cap never exceeds 20x the base cap
+
+pub const BUY_VOLUME_CAP_DIVISOR: u64 = 10; // A single buy may match 10% of the last day's volume
+
+pub const MAX_STARTER_PACK_COWS: u64 = 5; // Most free cows a starter pack can grant
+pub const MAX_IMPORT_QUARANTINE: i64 = 7 * SECONDS_PER_DAY; // Upper bound on import quarantine delay
+
+pub const MAX_STREAK_DAYS: u8 = 31; // Compound history is a 32-day bitmask
+
+pub const MAX_STREAK_BONUS_BPS: u16 = 5_000; // Streak bonus capped at +50%
+pub const MAX_FORGIVENESS_PASSES: u8 = 10; // Upper bound on passes a farm may bank
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const SELL_PRICE_BPS: u64 = 9_000; // Cows sell back to the pool at 90% of the curve price
+pub const MAX_EMERGENCY_EXIT_BPS: u16 = SELL_PRICE_BPS as u16; // Emergency exits never pay more than a regular sale
+
+pub const EPOCH_HISTORY_LEN: usize = 8; // Daily epochs of production kept per farm
+pub const HARVEST_LOG_LEN: usize = 16; // Withdrawals kept in a farm's harvest log
+
+pub const MAX_AUTOMATION_PROGRAMS: usize = 4; // Whitelisted auto-compounding programs
+
+// Farm preference bits, set by the owner with set_farm_preferences
+pub const FARM_PREF_KEEPER_POKES: u8 = 1 << 0; // Whitelisted automation may settle the farm
+pub const FARM_PREF_AUTO_REINVEST: u8 = 1 << 1; // Whitelisted automation may compound the farm
+pub const FARM_PREF_BOOST_EVENTS: u8 = 1 << 2; // Farm earns bonus-token campaign rewards
+pub const FARM_PREF_PREMIUM_RENEW: u8 = 1 << 3; // Renew Premium Dairy from rewards when it lapses
+pub const FARM_PREF_ALL: u8 = FARM_PREF_KEEPER_POKES | FARM_PREF_AUTO_REINVEST | FARM_PREF_BOOST_EVENTS | FARM_PREF_PREMIUM_RENEW;
+
+pub const WITHDRAWAL_TAX_TIERS: usize = 3; // Brackets in the progressive withdrawal tax
+pub const MAX_WITHDRAWAL_TAX_BPS: u16 = 2_500; // Top marginal withdrawal tax of 25%
+
+pub const MAX_GENETICS_BPS: i64 = 500; // Genetics nudge yield by at most ±5%
+
+pub const MAX_REFERRAL_BPS: u16 = 1_000; // Referrers earn at most 10% of referred volume
+pub const MAX_DELEGATION_DURATION: i64 = 365 * SECONDS_PER_DAY; // Delegations must be renewed at least yearly
+pub const MAX_RENTAL_SHARE_BPS: u16 = 9_000; // Managers can take at most 90% of harvests
+
+pub const MAX_RENTAL_DURATION: i64 = 365 * SECONDS_PER_DAY;
+
+pub const MAX_PRICE_AGE_LIMIT: i64 = SECONDS_PER_DAY; // Oracle staleness window can't exceed a day
+pub const MAX_PRICE_CONF_BPS: u16 = 1_000; // Pyth confidence interval can't be configured wider than 10% of the price
+
+pub const MAX_BUY_COOLDOWN: i64 = SECONDS_PER_DAY; // Upper bound on per-farm purchase cooldown
+
+pub const MAX_ESCROW_WINDOW: i64 = 7 * SECONDS_PER_DAY; // Longest a large withdrawal can be held
+
+pub const RELAY_MESSAGE_DOMAIN: &[u8] = b"milkerfun-relay-v1"; // Prefix for owner-signed relayed actions
+
+pub const MAX_COW_LOCK_DURATION: i64 = 5 * 365 * SECONDS_PER_DAY; // Longest an exported-cow lock can run
+pub const MAX_PARAM_UPDATE_COOLDOWN: i64 = 30 * SECONDS_PER_DAY; // Longest enforced gap between economic changes
+pub const MAX_UPGRADE_TIMELOCK: i64 = 90 * SECONDS_PER_DAY; // Longest upgrade delay the admin can declare
+pub const MIGRATION_WINDOW: i64 = 30 * SECONDS_PER_DAY; // execute_migration is usable for at most this long
+pub const MIN_MIGRATION_DELAY: i64 = 72 * 3600; // Shortest wait between queueing and executing a migration
+pub const MAX_MIGRATION_DELAY: i64 = 30 * SECONDS_PER_DAY; // Longest migration timelock the admin can set
+pub const MAX_GUARDIANS: usize = 5; // Guardian seats that can veto a queued migration
+pub const CONFIG_LAYOUT_VERSION: u8 = 1; // Bumped whenever migrate_config has new fields to fill in
+pub const FARM_ACCUMULATOR_LEN: usize = 589; // FarmAccount size once reward_debt was added; shorter farms predate the accumulator
+pub const FEE_HOOK_COUNT: usize = 4; // One per FeeHookKind
+pub const MAX_BUY_FEE_HOOK_BPS: u16 = 1_000; // At most 10% of a purchase can bypass the pool
+pub const MIN_FINAL_MILKING_WINDOW: i64 = 30 * SECONDS_PER_DAY; // Shortest claim window for the final distribution
+pub const MAX_FINAL_MILKING_WINDOW: i64 = 365 * SECONDS_PER_DAY;
+pub const MIN_PREMIUM_PERIOD: i64 = SECONDS_PER_DAY; // Shortest Premium Dairy subscription period
+pub const MAX_PREMIUM_PERIOD: i64 = 90 * SECONDS_PER_DAY;
+pub const MIN_STREAM_PERIOD: i64 = SECONDS_PER_DAY; // Shortest vesting period for streamed withdrawals
+pub const MAX_STREAM_PERIOD: i64 = 90 * SECONDS_PER_DAY;
+pub const LOCK_TIERS: usize = 3;
+pub const LOCK_DURATIONS: [i64; LOCK_TIERS] = [7 * SECONDS_PER_DAY, 30 * SECONDS_PER_DAY, 90 * SECONDS_PER_DAY]; // Terms lock_rewards accepts
+pub const MAX_WITHHOLDING_BPS: u16 = 5_000; // A farm can withhold at most half of each withdrawal
+pub const MAX_LOCK_BOOST_BPS: u16 = 5_000; // Locking rewards can at most add 50% to the reward rate
+pub const COW_RARITIES: usize = 3; // One per CowRarity
+pub const MAX_COW_UPGRADE_RATIO: u8 = 10; // Most lower-tier cows an upgrade can consume per cow
+pub const MAX_COW_TIER_BPS: u16 = 50_000; // Tier price and yield multipliers are capped at 5x
+pub const GAME_PHASES: usize = 4; // One per GamePhase
+pub const PHASE_TRANSITION_DELAY: i64 = 72 * 3600; // Notice before a queued phase transition takes effect
+pub const MAX_PHASE_BUY_FEE_BPS: u16 = 1_000; // A phase can surcharge purchases by at most 10%
+
+// Actions a phase can block, in PhaseRules::blocked_actions
+pub const PHASE_BLOCK_BUY: u8 = 1 << 0;
+pub const PHASE_BLOCK_SELL: u8 = 1 << 1;
+pub const PHASE_BLOCK_COMPOUND: u8 = 1 << 2;
+pub const PHASE_BLOCK_EXPORT: u8 = 1 << 3;
+pub const PHASE_BLOCK_IMPORT: u8 = 1 << 4;
+pub const PHASE_BLOCK_ALL: u8 = PHASE_BLOCK_BUY | PHASE_BLOCK_SELL | PHASE_BLOCK_COMPOUND | PHASE_BLOCK_EXPORT | PHASE_BLOCK_IMPORT;
+
+pub const MIN_FEED_INTERVAL: i64 = SECONDS_PER_DAY; // Farms never have to feed more than daily
+pub const MAX_FEED_INTERVAL: i64 = 30 * SECONDS_PER_DAY;
+pub const MIN_FEED_DECAY_BPS: u16 = 100; // Bounds the missed periods settlement walks to 100
+pub const MAX_FEED_COST_PER_COW: u64 = 100_000_000; // 100 MILK per cow per feeding
+
+pub const MAX_EVENT_DURATION: i64 = 30 * SECONDS_PER_DAY;
+pub const MAX_EVENT_REWARD_BPS: u16 = 30_000; // Events at most triple the reward rate
+pub const MAX_EVENT_PRICE_DISCOUNT_BPS: u16 = (BPS_DENOMINATOR - SELL_PRICE_BPS) as u16; // Discounted cows never buy below their sale price
+
+pub const BREEDING_PARENTS: u64 = 2; // Cows consumed by one breeding
+pub const BREEDING_OUTCOMES: usize = 4; // One, two or three calves, or a Rare calf
+pub const BREEDING_TIMEOUT: i64 = SECONDS_PER_DAY; // Unfulfilled breedings can be cancelled after this
+pub const MAX_BREEDING_FEE: u64 = 1_000_000_000; // 1,000 MILK per breeding
+
+pub const MAX_BARN_LEVEL: u8 = 100; // Barn upgrades stop here
+pub const MAX_PREMIUM_BUY_CAP_BPS: u16 = 10_000; // Premium can at most double the per-transaction buy cap
+pub const MAX_PREMIUM_YIELD_BONUS_BPS: u16 = 1_000; // Premium yield bonus is capped at 10%
+pub const MAX_PROJECTION_HORIZON: i64 = 365 * SECONDS_PER_DAY; // Longest reward projection served
+pub const MAX_GIFT_CAMPAIGN_DURATION: i64 = 90 * SECONDS_PER_DAY; // Longest a gift campaign stays claimable
+pub const MAX_GIFT_PROOF_LEN: usize = 20; // Merkle proof depth, enough for a million listed wallets
+pub const MAX_FARMS_PER_BATCH: usize = 24; // Farm summaries that fit in one view's 1024-byte return data
+pub const MAX_BONUS_CAMPAIGN_DURATION: i64 = 90 * SECONDS_PER_DAY; // Longest secondary-token campaign
+pub const MAX_PENALTY_GRACE_DURATION: i64 = 14 * SECONDS_PER_DAY; // Longest program-wide penalty waiver
+pub const MAX_COMMUNITY_GOAL_DURATION: i64 = 30 * SECONDS_PER_DAY; // Longest window to reach a community goal
+pub const MAX_COMMUNITY_BOOST_DURATION: i64 = 14 * SECONDS_PER_DAY; // Longest boost a reached goal unlocks
+pub const MAX_COMMUNITY_BOOST_BPS: u16 = 5_000; // A reached goal boosts rewards by at most 50%
+pub const MAX_YIELD_CLAIM_DURATION: i64 = 90 * SECONDS_PER_DAY; // Longest stretch of yield a farm can tokenize
+pub const YIELD_CLAIM_SUPPLY: u64 = 1_000_000_000; // 1,000 claim tokens (6 decimals) minted per tokenization
+pub const ATTESTATION_TTL: i64 = 10 * 60; // Farm attestations are valid for 10 minutes
+
+pub const MAX_LIQUIDATION_THRESHOLD_BPS: u16 = 9_000; // Loans must liquidate before debt reaches 90% of collateral
+
+pub const MAX_LOAN_INTEREST_BPS: u16 = 10_000; // At most 100% APR
+
+pub const MAX_LIQUIDATION_BONUS_BPS: u16 = 2_000; // Keepers earn at most 20% of cleared debt
+
+pub const MAX_WITHDRAW_MEMO_LEN: usize = 64; // Bytes of accounting tag carried on a withdrawal
+
+pub const MAX_GRANT_VOTING_PERIOD: i64 = 30 * SECONDS_PER_DAY; // Upper bound on a grant vote
+
+pub const MAX_GRANT_DESCRIPTION_LEN: usize = 128; // Bytes of proposal description stored on-chain
+
+pub const MAX_WHALE_TIERS: u32 = 16; // Tiers past this all earn at the last tier's rate
+
+pub const MIN_RECLAIM_IDLE: i64 = 90 * SECONDS_PER_DAY; // Rewards can't be reclaimed from farms idle less than this
+
+pub const MIN_RECLAIM_GRACE: i64 = SECONDS_PER_DAY; // Owners get at least a day's notice before reclamation
+
+pub const MAX_RECLAIM_GRACE: i64 = 90 * SECONDS_PER_DAY;
+
+#[cfg(feature = "devnet")]
+pub const FAUCET_AMOUNT: u64 = 100_000_000_000; // 100,000 test MILK (6 decimals) per claim
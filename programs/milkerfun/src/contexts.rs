@@ -0,0 +1,4051 @@
+//! Account validation for every instruction.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_spl::memo::Memo;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::errors::ErrorCode;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE, // discriminator + Config struct
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(constraint = milk_mint.decimals == 6)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(constraint = cow_mint.decimals == 6)]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"cow_mint_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for COW token mint
+    pub cow_mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Pool token account will be validated during runtime
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BuyCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FarmAccount::INIT_SPACE, // discriminator + FarmAccount struct
+        seeds = [b"farm", user.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount,
+        constraint = pool_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = pool_token_account.owner == pool_authority.key() @ ErrorCode::InvalidOwner
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = slot_hashes::ID)]
+    /// CHECK: SlotHashes sysvar, read raw since it is too large to deserialize
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// The farm of the wallet that referred this one, to credit its share
+    #[account(
+        mut,
+        seeds = [b"farm", referrer_farm.owner.as_ref()],
+        bump
+    )]
+    pub referrer_farm: Option<Account<'info, FarmAccount>>,
+
+    /// The referrer's registration, required to link a new farm to it and
+    /// updated with the referred volume whenever passed
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer_stats.owner.as_ref()],
+        bump
+    )]
+    pub referrer_stats: Option<Account<'info, Referrer>>,
+
+    /// Required in USD pricing mode
+    #[account(
+        seeds = [b"milk_price"],
+        bump
+    )]
+    pub milk_price: Option<Account<'info, MilkPrice>>,
+
+    /// Required in Pyth pricing mode
+    /// CHECK: address pinned to config; owner and layout checked when read
+    #[account(
+        address = config.pyth_price_account @ ErrorCode::InvalidPriceFeed
+    )]
+    pub pyth_price_update: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"quote"],
+        bump
+    )]
+    pub quote: Option<Account<'info, Quote>>,
+
+    /// The running community goal, credited with this purchase
+    #[account(
+        mut,
+        seeds = [b"community_goal"],
+        bump
+    )]
+    pub community_goal: Option<Account<'info, CommunityGoal>>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SellCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"quote"],
+        bump
+    )]
+    pub quote: Option<Account<'info, Quote>>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStarterPack<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FarmAccount::INIT_SPACE,
+        seeds = [b"farm", user.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + StarterPackClaim::INIT_SPACE,
+        seeds = [b"starter_pack", user.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, StarterPackClaim>,
+
+    #[account(
+        mut,
+        constraint = marketing_vault.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = marketing_vault.owner == marketing_authority.key() @ ErrorCode::InvalidOwner
+    )]
+    pub marketing_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"marketing_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for the marketing vault
+    pub marketing_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Required when Config names a starter pack gatekeeper
+    pub gatekeeper: Option<Signer<'info>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"quote"],
+        bump
+    )]
+    pub quote: Option<Account<'info, Quote>>,
+
+    /// The farm of the wallet that referred this one, to credit its share
+    #[account(
+        mut,
+        seeds = [b"farm", referrer_farm.owner.as_ref()],
+        bump
+    )]
+    pub referrer_farm: Option<Account<'info, FarmAccount>>,
+
+    /// The referrer's registration, required to link a new farm to it and
+    /// updated with the referred volume whenever passed
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer_stats.owner.as_ref()],
+        bump
+    )]
+    pub referrer_stats: Option<Account<'info, Referrer>>,
+
+    /// The running community goal, credited with this compound
+    #[account(
+        mut,
+        seeds = [b"community_goal"],
+        bump
+    )]
+    pub community_goal: Option<Account<'info, CommunityGoal>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestAsCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // The remaining accounts are only needed to take the cows as COW tokens
+
+    #[account(
+        mut,
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"cow_mint_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for COW token mint
+    pub cow_mint_authority: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
+        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_cow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawMilk<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount,
+        constraint = pool_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"harvest_log", farm.key().as_ref()],
+        bump
+    )]
+    pub harvest_log: Option<Account<'info, HarvestLog>>,
+
+    /// Pass during a declared grace window to withdraw penalty-free
+    #[account(
+        seeds = [b"penalty_grace"],
+        bump
+    )]
+    pub penalty_grace: Option<Account<'info, PenaltyGrace>>,
+
+    #[account(
+        mut,
+        seeds = [b"quote"],
+        bump
+    )]
+    pub quote: Option<Account<'info, Quote>>,
+
+    /// The farm's designated withholding account, required while
+    /// withholding is on
+    #[account(
+        mut,
+        constraint = withholding_account.key() == farm.withholding_account @ ErrorCode::InvalidWithholdingAccount
+    )]
+    pub withholding_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Keyed by the farm's withdrawal count, so a retried transaction
+    /// can't settle twice under the same receipt
+    #[account(
+        init,
+        payer = user,
+        space = 8 + WithdrawalReceipt::INIT_SPACE,
+        seeds = [b"receipt", farm.key().as_ref(), farm.withdrawal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub receipt: Option<Account<'info, WithdrawalReceipt>>,
+
+    /// Pass both bonus accounts to claim the campaign's secondary token
+    #[account(
+        mut,
+        constraint = bonus_pool.key() == config.bonus_pool @ ErrorCode::InvalidBonusPool
+    )]
+    pub bonus_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_bonus_account.mint == config.bonus_mint @ ErrorCode::InvalidMint,
+        constraint = user_bonus_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_bonus_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    /// Mint of the bonus campaign token, required with `bonus_pool`
+    #[account(address = config.bonus_mint @ ErrorCode::InvalidMint)]
+    pub bonus_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseWithdrawalReceipt<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub receipt: Account<'info, WithdrawalReceipt>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitHarvestLog<'info> {
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + HarvestLog::INIT_SPACE,
+        seeds = [b"harvest_log", farm.key().as_ref()],
+        bump
+    )]
+    pub harvest_log: Account<'info, HarvestLog>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterPortfolio<'info> {
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + Portfolio::INIT_SPACE,
+        seeds = [b"portfolio", wallet.key().as_ref()],
+        bump
+    )]
+    pub portfolio: Account<'info, Portfolio>,
+
+    #[account(
+        seeds = [b"farm", wallet.key().as_ref()],
+        bump
+    )]
+    pub farm: Option<Account<'info, FarmAccount>>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(checkpoint_id: u64)]
+pub struct ExportFarmCheckpoint<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + FarmCheckpoint::INIT_SPACE,
+        seeds = [b"checkpoint", farm.key().as_ref(), checkpoint_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub checkpoint: Account<'info, FarmCheckpoint>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshPortfolio<'info> {
+    #[account(
+        mut,
+        seeds = [b"portfolio", portfolio.wallet.as_ref()],
+        bump
+    )]
+    pub portfolio: Account<'info, Portfolio>,
+
+    #[account(
+        seeds = [b"farm", portfolio.wallet.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        seeds = [b"loan", farm.key().as_ref()],
+        bump
+    )]
+    pub loan_position: Option<Account<'info, LoanPosition>>,
+}
+
+#[derive(Accounts)]
+pub struct InitQuote<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Quote::INIT_SPACE,
+        seeds = [b"quote"],
+        bump
+    )]
+    pub quote: Account<'info, Quote>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshQuote<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"quote"],
+        bump
+    )]
+    pub quote: Account<'info, Quote>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawMilkWithMemo<'info> {
+    pub withdraw: WithdrawMilk<'info>,
+
+    pub memo_program: Option<Program<'info, Memo>>,
+}
+
+#[derive(Accounts)]
+pub struct RelayedCompound<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = instructions_sysvar::ID)]
+    /// CHECK: Instructions sysvar, used to find the Ed25519 verification
+    pub instructions: UncheckedAccount<'info>,
+
+    pub relayer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RelayedWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = owner_token_account.owner == farm.owner @ ErrorCode::InvalidOwner
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = instructions_sysvar::ID)]
+    /// CHECK: Instructions sysvar, used to find the Ed25519 verification
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"harvest_log", farm.key().as_ref()],
+        bump
+    )]
+    pub harvest_log: Option<Account<'info, HarvestLog>>,
+
+    /// Pass during a declared grace window to withdraw penalty-free
+    #[account(
+        seeds = [b"penalty_grace"],
+        bump
+    )]
+    pub penalty_grace: Option<Account<'info, PenaltyGrace>>,
+
+    /// The farm's designated withholding account, required while
+    /// withholding is on
+    #[account(
+        mut,
+        constraint = withholding_account.key() == farm.withholding_account @ ErrorCode::InvalidWithholdingAccount
+    )]
+    pub withholding_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub relayer: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RequestEscrowedWithdrawal<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + WithdrawalEscrow::INIT_SPACE,
+        seeds = [b"escrow", farm.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, WithdrawalEscrow>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"harvest_log", farm.key().as_ref()],
+        bump
+    )]
+    pub harvest_log: Option<Account<'info, HarvestLog>>,
+
+    /// Pass during a declared grace window to withdraw penalty-free
+    #[account(
+        seeds = [b"penalty_grace"],
+        bump
+    )]
+    pub penalty_grace: Option<Account<'info, PenaltyGrace>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.farm.as_ref()],
+        bump,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        has_one = destination @ ErrorCode::InvalidOwner,
+        close = owner
+    )]
+    pub escrow: Account<'info, WithdrawalEscrow>,
+
+    #[account(address = escrow.farm @ ErrorCode::InvalidParameter)]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    /// CHECK: Escrow requester receiving the rent back, checked via has_one
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The farm's designated withholding account, required while
+    /// withholding is on
+    #[account(
+        mut,
+        constraint = withholding_account.key() == farm.withholding_account @ ErrorCode::InvalidWithholdingAccount
+    )]
+    pub withholding_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStreamed<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + VestingStream::INIT_SPACE,
+        seeds = [b"vesting", farm.key().as_ref()],
+        bump
+    )]
+    pub vesting: Account<'info, VestingStream>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", vesting.farm.as_ref()],
+        bump,
+        has_one = owner @ ErrorCode::InvalidOwner
+    )]
+    pub vesting: Account<'info, VestingStream>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == owner.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockCosmetic<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FarmerProfile::INIT_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, FarmerProfile>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FlagEscrow<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump,
+        constraint = config.pauser == pauser.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.farm.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, WithdrawalEscrow>,
+
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", farm.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        has_one = destination @ ErrorCode::InvalidOwner,
+        close = owner
+    )]
+    pub escrow: Account<'info, WithdrawalEscrow>,
+
+    #[account(mut)]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    /// CHECK: Escrow requester receiving the rent back, checked via has_one
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The farm's designated withholding account, required while
+    /// withholding is on
+    #[account(
+        mut,
+        constraint = withholding_account.key() == farm.withholding_account @ ErrorCode::InvalidWithholdingAccount
+    )]
+    pub withholding_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + LoanPosition::INIT_SPACE,
+        seeds = [b"loan", farm.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, LoanPosition>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BorrowMilk<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"loan", position.farm.as_ref()],
+        bump,
+        constraint = position.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub position: Account<'info, LoanPosition>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RepayLoan<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"loan", position.farm.as_ref()],
+        bump
+    )]
+    pub position: Account<'info, LoanPosition>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"loan", farm.key().as_ref()],
+        bump,
+        constraint = position.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub position: Account<'info, LoanPosition>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LiquidatePosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"loan", owner_farm.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, LoanPosition>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", position.owner.as_ref()],
+        bump
+    )]
+    pub owner_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        constraint = liquidator_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = liquidator_token_account.owner == liquidator.key() @ ErrorCode::InvalidOwner
+    )]
+    pub liquidator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub liquidator: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SweepGrantPenalties<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = grants_vault.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = grants_vault.owner == grants_authority.key() @ ErrorCode::InvalidOwner
+    )]
+    pub grants_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"grants_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for the grants vault
+    pub grants_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SyncTvl<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SweepNewFarmerFund<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = marketing_vault.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = marketing_vault.owner == marketing_authority.key() @ ErrorCode::InvalidOwner
+    )]
+    pub marketing_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"marketing_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for the marketing vault
+    pub marketing_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: FeeHookKind)]
+pub struct SweepFeeHook<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.key() == config.fee_hooks[kind as usize].destination @ ErrorCode::InvalidFeeDestination,
+        constraint = destination_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeGrant<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + GrantProposal::INIT_SPACE,
+        seeds = [b"grant_proposal", config.grant_proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, GrantProposal>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoteGrant<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant_proposal", proposal.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, GrantProposal>,
+
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + GrantVote::INIT_SPACE,
+        seeds = [b"grant_vote", proposal.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, GrantVote>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteGrant<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"grant_proposal", proposal.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, GrantProposal>,
+
+    #[account(
+        mut,
+        constraint = grants_vault.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = grants_vault.owner == grants_authority.key() @ ErrorCode::InvalidOwner
+    )]
+    pub grants_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"grants_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for the grants vault
+    pub grants_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_account.key() == proposal.recipient @ ErrorCode::InvalidOwner,
+        constraint = recipient_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ProjectRewards<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct GetPositionHealth<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"loan", position.farm.as_ref()],
+        bump
+    )]
+    pub position: Account<'info, LoanPosition>,
+}
+
+#[derive(Accounts)]
+pub struct AttestFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FarmAttestation::INIT_SPACE,
+        seeds = [b"attestation", farm.key().as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, FarmAttestation>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetGlobalStats<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Optional: views fall back to the balance cached in Config when the
+    /// pool isn't passed
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct GetFarmStats<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    /// Pass during a declared grace window to see the waived penalty
+    #[account(
+        seeds = [b"penalty_grace"],
+        bump
+    )]
+    pub penalty_grace: Option<Account<'info, PenaltyGrace>>,
+}
+
+#[derive(Accounts)]
+pub struct GetBuyQuote<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Prices the pool's share of the payment under a transfer fee
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    /// Required in USD pricing mode
+    #[account(
+        seeds = [b"milk_price"],
+        bump
+    )]
+    pub milk_price: Option<Account<'info, MilkPrice>>,
+
+    /// Required in Pyth pricing mode
+    /// CHECK: address pinned to config; owner and layout checked when read
+    #[account(
+        address = config.pyth_price_account @ ErrorCode::InvalidPriceFeed
+    )]
+    pub pyth_price_update: Option<UncheckedAccount<'info>>,
+}
+
+/// Farms to summarize are passed as remaining accounts
+#[derive(Accounts)]
+pub struct GetFarmsStatsBatch<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct EnforceCowBacking<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"cow_mint_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for COW token mint
+    pub cow_mint_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetBacking<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct ExportCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"cow_mint_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for COW token mint
+    pub cow_mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
+        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_cow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(num_cows: u64, unlock_at: i64)]
+pub struct ExportCowsLocked<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"cow_mint_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as mint and freeze authority for COW tokens
+    pub cow_mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        token::mint = cow_mint,
+        token::authority = user,
+        seeds = [b"cow_lock", user.key().as_ref(), unlock_at.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub locked_cow_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(unlock_at: i64)]
+pub struct UnlockCows<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"cow_mint_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as mint and freeze authority for COW tokens
+    pub cow_mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_lock", owner.key().as_ref(), unlock_at.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub locked_cow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Only used to derive the locked account's address
+    pub owner: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ImportCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FarmAccount::INIT_SPACE, // discriminator + FarmAccount struct
+        seeds = [b"farm", user.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
+        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_cow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCowVault<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + CowVault::INIT_SPACE,
+        seeds = [b"cow_vault", config.key().as_ref()],
+        bump
+    )]
+    pub cow_vault: Account<'info, CowVault>,
+
+    #[account(
+        init,
+        payer = admin,
+        mint::decimals = 6,
+        mint::authority = cow_vault,
+        seeds = [b"vault_shares", cow_vault.key().as_ref()],
+        bump
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FarmAccount::INIT_SPACE,
+        seeds = [b"farm", cow_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCowStaking<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + CowStakingPool::INIT_SPACE,
+        seeds = [b"cow_staking", config.key().as_ref()],
+        bump
+    )]
+    pub staking_pool: Account<'info, CowStakingPool>,
+
+    #[account(
+        init,
+        payer = admin,
+        token::mint = cow_mint,
+        token::authority = staking_pool,
+        token::token_program = token_program,
+        seeds = [b"cow_staking_vault", config.key().as_ref()],
+        bump
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = config.cow_mint @ ErrorCode::InvalidCowMint)]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCowStakingEmission<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_staking", config.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, CowStakingPool>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StakeCow<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_staking", config.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, CowStakingPool>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + CowStake::INIT_SPACE,
+        seeds = [b"cow_stake", user.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, CowStake>,
+
+    #[account(
+        mut,
+        address = staking_pool.token_vault @ ErrorCode::InvalidParameter
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = config.cow_mint @ ErrorCode::InvalidCowMint)]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
+        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_cow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeCow<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_staking", config.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, CowStakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_stake", user.key().as_ref()],
+        bump,
+        constraint = stake.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub stake: Account<'info, CowStake>,
+
+    #[account(
+        mut,
+        address = staking_pool.token_vault @ ErrorCode::InvalidParameter
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = config.cow_mint @ ErrorCode::InvalidCowMint)]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
+        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_cow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakingRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_staking", config.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, CowStakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_stake", user.key().as_ref()],
+        bump,
+        constraint = stake.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub stake: Account<'info, CowStake>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Adapter accounts are fixed: aggregators build these once and CPI with
+/// their vault PDA as `authority`
+#[derive(Accounts)]
+pub struct DepositFor<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + FarmAccount::INIT_SPACE,
+        seeds = [b"farm", authority.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    /// Owner of the position and of the MILK being deposited
+    pub authority: Signer<'info>,
+
+    /// Funds the farm account if it is opened by this deposit
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = source_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = source_token_account.owner == authority.key() @ ErrorCode::InvalidOwner
+    )]
+    pub source_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = slot_hashes::ID)]
+    /// CHECK: SlotHashes sysvar, read raw since it is too large to deserialize
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// Required in USD pricing mode
+    #[account(
+        seeds = [b"milk_price"],
+        bump
+    )]
+    pub milk_price: Option<Account<'info, MilkPrice>>,
+
+    /// Required in Pyth pricing mode
+    /// CHECK: address pinned to config; owner and layout checked when read
+    #[account(
+        address = config.pyth_price_account @ ErrorCode::InvalidPriceFeed
+    )]
+    pub pyth_price_update: Option<UncheckedAccount<'info>>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestFor<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", authority.key().as_ref()],
+        bump,
+        constraint = farm.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub authority: Signer<'info>,
+
+    /// Any MILK account; aggregators usually pass their vault's
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// Pass during a declared grace window to harvest penalty-free
+    #[account(
+        seeds = [b"penalty_grace"],
+        bump
+    )]
+    pub penalty_grace: Option<Account<'info, PenaltyGrace>>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFor<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", authority.key().as_ref()],
+        bump,
+        constraint = farm.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DepositToCowVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_vault", config.key().as_ref()],
+        bump = cow_vault.bump
+    )]
+    pub cow_vault: Account<'info, CowVault>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", cow_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        address = cow_vault.share_mint @ ErrorCode::InvalidMint
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == cow_vault.share_mint @ ErrorCode::InvalidMint
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
+        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_cow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemCowVaultShares<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"cow_vault", config.key().as_ref()],
+        bump = cow_vault.bump
+    )]
+    pub cow_vault: Account<'info, CowVault>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", cow_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        address = cow_vault.share_mint @ ErrorCode::InvalidMint
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == cow_vault.share_mint @ ErrorCode::InvalidMint,
+        constraint = user_share_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
+    )]
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"cow_mint_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for COW token mint
+    pub cow_mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint
+    )]
+    pub user_cow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundCowVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"cow_vault", config.key().as_ref()],
+        bump = cow_vault.bump
+    )]
+    pub cow_vault: Account<'info, CowVault>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", cow_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct QueueMigration<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = destination_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct VetoMigration<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BeginFinalMilking<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFinalMilk<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRetiredFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"farm", owner.key().as_ref()],
+        bump,
+        constraint = farm.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    /// CHECK: receives the farm's rent; pinned by the farm's seeds
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConcludeFinalMilking<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct VetoPhaseTransition<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePhaseTransition<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMigration<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.key() == config.migration_destination @ ErrorCode::InvalidParameter
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AutomatedFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = instructions_sysvar::ID)]
+    /// CHECK: Instructions sysvar, used to identify the invoking program
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CrankFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct EmitHeartbeat<'info> {
+    #[account(
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+}
+
+#[derive(Accounts)]
+pub struct TransferFarmRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", sender.key().as_ref()],
+        bump,
+        constraint = from_farm.owner == sender.key() @ ErrorCode::Unauthorized
+    )]
+    pub from_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", recipient.key().as_ref()],
+        bump,
+        constraint = to_farm.owner == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub to_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub sender: Signer<'info>,
+
+    pub recipient: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", sender.key().as_ref()],
+        bump,
+        constraint = from_farm.owner == sender.key() @ ErrorCode::Unauthorized
+    )]
+    pub from_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + FarmAccount::INIT_SPACE,
+        seeds = [b"farm", recipient.key().as_ref()],
+        bump
+    )]
+    pub to_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    /// The new wallet signs too, so a farm can't be sent to a key nobody holds
+    pub recipient: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GiftCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", sender.key().as_ref()],
+        bump,
+        constraint = from_farm.owner == sender.key() @ ErrorCode::Unauthorized
+    )]
+    pub from_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + FarmAccount::INIT_SPACE,
+        seeds = [b"farm", recipient.key().as_ref()],
+        bump
+    )]
+    pub to_farm: Account<'info, FarmAccount>,
+
+    /// CHECK: any wallet; only used to derive the receiving farm
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateGiftCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", sponsor.key().as_ref()],
+        bump,
+        constraint = farm.owner == sponsor.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        space = 8 + GiftCampaign::INIT_SPACE,
+        seeds = [b"gift_campaign", sponsor.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub campaign: Account<'info, GiftCampaign>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGiftCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"gift_campaign", campaign.sponsor.as_ref(), campaign.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub campaign: Account<'info, GiftCampaign>,
+
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + GiftClaim::INIT_SPACE,
+        seeds = [b"gift_claim", campaign.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, GiftClaim>,
+
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = 8 + FarmAccount::INIT_SPACE,
+        seeds = [b"farm", wallet.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepGiftCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = sponsor,
+        seeds = [b"gift_campaign", campaign.sponsor.as_ref(), campaign.id.to_le_bytes().as_ref()],
+        bump,
+        has_one = sponsor @ ErrorCode::Unauthorized
+    )]
+    pub campaign: Account<'info, GiftCampaign>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", campaign.sponsor.as_ref()],
+        bump
+    )]
+    pub sponsor_farm: Account<'info, FarmAccount>,
+
+    /// CHECK: receives the campaign's rent; must match campaign.sponsor
+    #[account(mut)]
+    pub sponsor: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Anyone can sweep an expired campaign
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ListFarmRental<'info> {
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + FarmRental::INIT_SPACE,
+        seeds = [b"rental", farm.key().as_ref()],
+        bump
+    )]
+    pub rental: Account<'info, FarmRental>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptFarmRental<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"rental", farm.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter
+    )]
+    pub rental: Account<'info, FarmRental>,
+
+    #[account(
+        init_if_needed,
+        payer = manager,
+        space = 8 + FarmAccount::INIT_SPACE, // discriminator + FarmAccount struct
+        seeds = [b"farm", manager.key().as_ref()],
+        bump
+    )]
+    pub manager_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleFarmRental<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        seeds = [b"rental", farm.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        constraint = rental.manager != Pubkey::default() @ ErrorCode::RentalNotAccepted
+    )]
+    pub rental: Account<'info, FarmRental>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", rental.manager.as_ref()],
+        bump
+    )]
+    pub manager_farm: Account<'info, FarmAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct TerminateFarmRental<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"rental", farm.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        close = owner
+    )]
+    pub rental: Account<'info, FarmRental>,
+
+    /// Required once the rental has been accepted
+    #[account(mut)]
+    pub manager_farm: Option<Account<'info, FarmAccount>>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    /// CHECK: Listing owner receiving the rental account rent, checked via has_one
+    pub owner: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManagedCompound<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        seeds = [b"rental", farm.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        has_one = manager @ ErrorCode::Unauthorized
+    )]
+    pub rental: Account<'info, FarmRental>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub manager: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct GrantDelegation<'info> {
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Delegation::INIT_SPACE,
+        seeds = [b"delegation", farm.key().as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"delegation", farm.key().as_ref(), delegation.delegate.as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        close = user
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DelegatedCompound<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"delegation", farm.key().as_ref(), delegate.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        has_one = delegate @ ErrorCode::Unauthorized
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub delegate: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMilkPrice<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump,
+        constraint = config.price_oracle_authority == oracle_authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = oracle_authority,
+        space = 8 + MilkPrice::INIT_SPACE,
+        seeds = [b"milk_price"],
+        bump
+    )]
+    pub milk_price: Account<'info, MilkPrice>,
+
+    #[account(mut)]
+    pub oracle_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(feature = "devnet")]
+#[derive(Accounts)]
+pub struct FaucetMilk<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FaucetClaim::INIT_SPACE,
+        seeds = [b"faucet", user.key().as_ref()],
+        bump
+    )]
+    pub faucet_claim: Account<'info, FaucetClaim>,
+
+    #[account(
+        mut,
+        constraint = faucet_vault.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = faucet_vault.owner == faucet_authority.key() @ ErrorCode::InvalidOwner
+    )]
+    pub faucet_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"faucet_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for the faucet vault
+    pub faucet_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Referrer::INIT_SPACE,
+        seeds = [b"referrer", user.key().as_ref()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    /// Referral credit is paid into this farm, so it must exist
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: may predate the current Config layout, so it can't be
+    /// deserialized; the handler checks the discriminator and admin before
+    /// resizing
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        owner = crate::ID @ ErrorCode::InvalidParameter
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    /// Checked against the Config by the handler
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Checked against the Config by the handler
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    /// Checked against the Config by the handler
+    pub cow_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateFarm<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: may predate the current FarmAccount layout, so it can't be
+    /// deserialized; the handler checks the discriminator before resizing
+    #[account(
+        mut,
+        owner = crate::ID @ ErrorCode::InvalidParameter
+    )]
+    pub farm: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFarmPreferences<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct BreedCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + BreedingRequest::INIT_SPACE,
+        seeds = [b"breeding", farm.key().as_ref()],
+        bump
+    )]
+    pub breeding: Account<'info, BreedingRequest>,
+
+    /// ORAO randomness request for the breeding's seed, requested earlier
+    /// in the same transaction
+    /// CHECK: owner, layout and seed are checked when read
+    pub randomness: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillBreeding<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"breeding", farm.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        close = owner
+    )]
+    pub breeding: Account<'info, BreedingRequest>,
+
+    /// CHECK: owner, layout and seed are checked when read
+    pub randomness: UncheckedAccount<'info>,
+
+    /// CHECK: rent refund destination, matched against the breeding request
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBreeding<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", owner.key().as_ref()],
+        bump,
+        constraint = farm.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"breeding", farm.key().as_ref()],
+        bump,
+        has_one = farm @ ErrorCode::InvalidParameter,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        close = owner
+    )]
+    pub breeding: Account<'info, BreedingRequest>,
+
+    /// CHECK: owner, layout and seed are checked when read
+    pub randomness: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct FeedCows<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeBarn<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithholding<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Where withheld MILK goes; required unless turning withholding off
+    #[account(
+        constraint = withholding_account.mint == config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub withholding_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct RecordUpgradeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::InvalidProgramData
+    )]
+    pub program: Program<'info, crate::program::Milkerfun>,
+
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+pub struct CheckUpgradeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::InvalidProgramData
+    )]
+    pub program: Program<'info, crate::program::Milkerfun>,
+
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+pub struct GetUpgradeAuthority<'info> {
+    #[account(
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::InvalidProgramData
+    )]
+    pub program: Program<'info, crate::program::Milkerfun>,
+
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+pub struct DeclarePenaltyGrace<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + PenaltyGrace::INIT_SPACE,
+        seeds = [b"penalty_grace"],
+        bump
+    )]
+    pub penalty_grace: Account<'info, PenaltyGrace>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenYieldClaim<'info> {
+    #[account(
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + YieldClaim::INIT_SPACE,
+        seeds = [b"yield_claim", farm.key().as_ref()],
+        bump
+    )]
+    pub yield_claim: Account<'info, YieldClaim>,
+
+    #[account(
+        init,
+        payer = user,
+        mint::decimals = 6,
+        mint::authority = yield_claim,
+        seeds = [b"yield_claim_mint", farm.key().as_ref()],
+        bump
+    )]
+    pub claim_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TokenizeYield<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", user.key().as_ref()],
+        bump,
+        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"yield_claim", farm.key().as_ref()],
+        bump = yield_claim.bump
+    )]
+    pub yield_claim: Account<'info, YieldClaim>,
+
+    #[account(
+        mut,
+        address = yield_claim.claim_mint @ ErrorCode::InvalidMint
+    )]
+    pub claim_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_claim_account.mint == yield_claim.claim_mint @ ErrorCode::InvalidMint
+    )]
+    pub user_claim_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SettleYieldClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"yield_claim", farm.key().as_ref()],
+        bump = yield_claim.bump
+    )]
+    pub yield_claim: Account<'info, YieldClaim>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemYieldClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"yield_claim", farm.key().as_ref()],
+        bump = yield_claim.bump
+    )]
+    pub yield_claim: Account<'info, YieldClaim>,
+
+    #[account(
+        mut,
+        address = yield_claim.claim_mint @ ErrorCode::InvalidMint
+    )]
+    pub claim_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_claim_account.mint == yield_claim.claim_mint @ ErrorCode::InvalidMint,
+        constraint = user_claim_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_claim_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    #[account(address = config.milk_mint @ ErrorCode::InvalidMint)]
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct StartCommunityGoal<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + CommunityGoal::INIT_SPACE,
+        seeds = [b"community_goal"],
+        bump
+    )]
+    pub community_goal: Account<'info, CommunityGoal>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ActivateCommunityBoost<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.owner.as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, FarmAccount>,
+
+    #[account(
+        seeds = [b"community_goal"],
+        bump
+    )]
+    pub community_goal: Account<'info, CommunityGoal>,
+
+    #[account(
+        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct StartBonusCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = bonus_pool.owner == pool_authority.key() @ ErrorCode::InvalidOwner,
+        constraint = bonus_pool.mint != config.milk_mint @ ErrorCode::InvalidMint
+    )]
+    pub bonus_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"pool_authority", config.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as authority for token transfers
+    pub pool_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateEvent<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GameEvent::INIT_SPACE,
+        seeds = [b"event", config.event_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub event: Account<'info, GameEvent>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEvent<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.id.to_le_bytes().as_ref()],
+        bump,
+        constraint = event.id + 1 == config.event_count @ ErrorCode::InvalidParameter
+    )]
+    pub event: Account<'info, GameEvent>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"], 
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
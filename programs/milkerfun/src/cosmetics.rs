@@ -0,0 +1,64 @@
+//! Cosmetic unlocks (skins and titles) earned from farm stats.
+//!
+//! Purely progression: unlocking one never touches rewards, the pool or the
+//! herd. The catalog is fixed in the program; a cosmetic's bit in
+//! `FarmerProfile::cosmetics` is its index here, so entries are only ever
+//! appended.
+
+use anchor_lang::prelude::*;
+use crate::constants::SECONDS_PER_DAY;
+use crate::errors::ErrorCode;
+use crate::state::{Config, FarmAccount};
+
+const MILK: u64 = 1_000_000;
+
+#[derive(Clone, Copy)]
+enum CosmeticStat {
+    Cows,
+    CompoundedCows,
+    MilkSpent,
+    MilkWithdrawn,
+    FarmAgeDays,
+}
+
+struct Cosmetic {
+    stat: CosmeticStat,
+    threshold: u64,
+    /// Only unlockable while this is the current season (0 = any time)
+    season: u16,
+}
+
+const CATALOG: [Cosmetic; 10] = [
+    Cosmetic { stat: CosmeticStat::Cows, threshold: 10, season: 0 },              // Skin: spotted barn
+    Cosmetic { stat: CosmeticStat::Cows, threshold: 100, season: 0 },             // Title: Rancher
+    Cosmetic { stat: CosmeticStat::Cows, threshold: 1_000, season: 0 },           // Title: Cattle Baron
+    Cosmetic { stat: CosmeticStat::CompoundedCows, threshold: 50, season: 0 },    // Skin: golden silo
+    Cosmetic { stat: CosmeticStat::CompoundedCows, threshold: 500, season: 0 },   // Title: Compounder
+    Cosmetic { stat: CosmeticStat::MilkWithdrawn, threshold: 1_000_000 * MILK, season: 0 }, // Title: Milk Tycoon
+    Cosmetic { stat: CosmeticStat::FarmAgeDays, threshold: 365, season: 0 },      // Title: Old MacDonald
+    Cosmetic { stat: CosmeticStat::MilkSpent, threshold: 100_000 * MILK, season: 1 }, // Skin: harvest moon (season 1)
+    Cosmetic { stat: CosmeticStat::CompoundedCows, threshold: 25, season: 2 },    // Skin: winter pasture (season 2)
+    Cosmetic { stat: CosmeticStat::Cows, threshold: 250, season: 3 },             // Skin: spring bloom (season 3)
+];
+
+/// Check that `farm` meets cosmetic `id`'s threshold in the current season
+pub(crate) fn check_cosmetic_unlock(id: u8, farm: &FarmAccount, config: &Config, current_time: i64) -> Result<()> {
+    let cosmetic = CATALOG.get(id as usize).ok_or(ErrorCode::UnknownCosmetic)?;
+    require!(
+        cosmetic.season == 0 || cosmetic.season == config.cosmetic_season,
+        ErrorCode::CosmeticOutOfSeason
+    );
+
+    let value = match cosmetic.stat {
+        CosmeticStat::Cows => farm.cows,
+        CosmeticStat::CompoundedCows => farm.lifetime_compounded_cows,
+        CosmeticStat::MilkSpent => farm.total_milk_spent,
+        CosmeticStat::MilkWithdrawn => farm.total_milk_withdrawn,
+        CosmeticStat::FarmAgeDays if farm.farm_created_at > 0 => {
+            (current_time.saturating_sub(farm.farm_created_at) / SECONDS_PER_DAY) as u64
+        }
+        CosmeticStat::FarmAgeDays => 0,
+    };
+    require!(value >= cosmetic.threshold, ErrorCode::CosmeticLocked);
+    Ok(())
+}
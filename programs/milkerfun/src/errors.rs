@@ -0,0 +1,289 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Insufficient rewards to buy cows")]
+    InsufficientRewards,
+    #[msg("No rewards available for withdrawal")]
+    NoRewardsAvailable,
+    #[msg("Invalid amount - must be greater than 0")]
+    InvalidAmount,
+    #[msg("Invalid mint address")]
+    InvalidMint,
+    #[msg("Invalid token account owner")]
+    InvalidOwner,
+    #[msg("Invalid pool token account")]
+    InvalidPoolAccount,
+    #[msg("No funds available for migration")]
+    NoFundsToMigrate,
+    #[msg("Purchase exceeds the per-transaction cow cap")]
+    ExceedsMaxCowsPerTransaction,
+    #[msg("Insufficient cows to export")]
+    InsufficientCows,
+    #[msg("Invalid COW mint address")]
+    InvalidCowMint,
+    #[msg("Parameter out of allowed range")]
+    InvalidParameter,
+    #[msg("Caller is not a whitelisted automation program")]
+    AutomationNotWhitelisted,
+    #[msg("Farm has not opted in to automation")]
+    AutomationNotEnabled,
+    #[msg("Automation program registry is full")]
+    AutomationRegistryFull,
+    #[msg("SlotHashes sysvar data is unavailable")]
+    InvalidSlotHashes,
+    #[msg("Rental has already been accepted")]
+    RentalAlreadyAccepted,
+    #[msg("Rental has not been accepted")]
+    RentalNotAccepted,
+    #[msg("Rental period has ended")]
+    RentalExpired,
+    #[msg("MILK price feed account is required in USD pricing mode")]
+    MissingPriceFeed,
+    #[msg("MILK price feed is stale")]
+    StalePriceFeed,
+    #[msg("Purchase cooldown has not elapsed")]
+    BuyCooldownActive,
+    #[msg("Withdrawal exceeds the escrow threshold - use request_escrowed_withdrawal")]
+    WithdrawalRequiresEscrow,
+    #[msg("Withdrawal escrow is not enabled")]
+    EscrowDisabled,
+    #[msg("Escrowed withdrawal is flagged for review")]
+    EscrowFlagged,
+    #[msg("Escrowed withdrawal is not flagged")]
+    EscrowNotFlagged,
+    #[msg("Escrow dispute window is still open")]
+    EscrowWindowOpen,
+    #[msg("Escrow dispute window has closed")]
+    EscrowWindowClosed,
+    #[msg("Missing or invalid owner signature for relayed action")]
+    InvalidRelaySignature,
+    #[msg("Relayed action nonce does not match")]
+    InvalidRelayNonce,
+    #[msg("Relayed action authorization has expired")]
+    RelayAuthorizationExpired,
+    #[msg("Faucet can only be claimed once per day")]
+    FaucetCooldownActive,
+    #[msg("Faucet vault is empty")]
+    FaucetEmpty,
+    #[msg("Lending is disabled")]
+    LendingDisabled,
+    #[msg("Debt would exceed the allowed loan-to-value")]
+    ExceedsLoanToValue,
+    #[msg("Position has no outstanding debt")]
+    NoOutstandingDebt,
+    #[msg("Position is above the liquidation threshold")]
+    PositionHealthy,
+    #[msg("Withdrawal memo must be 1-64 bytes")]
+    InvalidMemo,
+    #[msg("Community grants are not enabled")]
+    GrantsDisabled,
+    #[msg("Voting on this grant has closed")]
+    GrantVotingClosed,
+    #[msg("Voting on this grant is still open")]
+    GrantVotingOpen,
+    #[msg("Grant did not pass its vote")]
+    GrantNotApproved,
+    #[msg("Grant has already been paid out")]
+    GrantAlreadyExecuted,
+    #[msg("Grants vault cannot cover this grant")]
+    GrantsVaultUnderfunded,
+    #[msg("Idle reward reclamation is disabled")]
+    ReclamationDisabled,
+    #[msg("Farm has not been idle long enough")]
+    FarmNotIdle,
+    #[msg("Farm has already been warned")]
+    ReclamationAlreadyWarned,
+    #[msg("Farm has no outstanding idle warning")]
+    ReclamationNotWarned,
+    #[msg("Reclamation grace window has not passed")]
+    ReclamationGraceActive,
+    #[msg("Unlock date must be in the future and within the maximum lock duration")]
+    InvalidLockDuration,
+    #[msg("COW mint freeze authority has not been given to the program")]
+    FreezeAuthorityNotDelegated,
+    #[msg("Locked cows cannot be released before their unlock date")]
+    CowsStillLocked,
+    #[msg("Farm has no forgiveness pass to redeem")]
+    NoForgivenessPass,
+    #[msg("Economic parameters were changed too recently")]
+    ParamUpdateCooldown,
+    #[msg("Parameter change exceeds the maximum step size")]
+    ParamStepTooLarge,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Timelock duration out of range")]
+    InvalidTimelock,
+    #[msg("Program data account does not belong to this program")]
+    InvalidProgramData,
+    #[msg("Starter packs are disabled")]
+    StarterPackDisabled,
+    #[msg("Starter pack claim was not approved by the gatekeeper")]
+    StarterPackNotApproved,
+    #[msg("Marketing budget cannot cover a starter pack")]
+    MarketingBudgetExhausted,
+    #[msg("Reward accrual is paused until the pool is recapitalized")]
+    AccrualPaused,
+    #[msg("No catch-up rate has been set")]
+    CatchUpDisabled,
+    #[msg("Farm has no paused production to catch up")]
+    NothingToCatchUp,
+    #[msg("Referrer farm does not match the farm's referrer")]
+    InvalidReferrer,
+    #[msg("COW supply would exceed the cows backing it")]
+    CowSupplyExceedsBacking,
+    #[msg("COW mint authority has not been handed to the program")]
+    MintAuthorityNotDelegated,
+    #[msg("COW backing is already enforced")]
+    CowBackingAlreadyEnforced,
+    #[msg("The migration window has closed")]
+    MigrationWindowClosed,
+    #[msg("Delegation has expired")]
+    DelegationExpired,
+    #[msg("Compound exceeds the delegate's daily allowance")]
+    DelegationAllowanceExceeded,
+    #[msg("A bonus campaign is still running")]
+    BonusCampaignActive,
+    #[msg("Bonus pool account does not match the campaign")]
+    InvalidBonusPool,
+    #[msg("Pool balance too low to buy back cows")]
+    InsufficientPoolBalance,
+    #[msg("Farm owner has not opted in to this action")]
+    PreferenceNotSet,
+    #[msg("Unknown preference bits")]
+    InvalidPreferences,
+    #[msg("Cow price exceeds the buyer's limit")]
+    SlippageExceeded,
+    #[msg("Farm tag is already set")]
+    FarmTagAlreadySet,
+    #[msg("A migration is already queued")]
+    MigrationAlreadyQueued,
+    #[msg("No migration is queued")]
+    MigrationNotQueued,
+    #[msg("The migration timelock has not elapsed")]
+    MigrationTimelocked,
+    #[msg("Referrer is not registered")]
+    ReferrerNotRegistered,
+    #[msg("MILK mint authorities do not match the declared expectation")]
+    MilkMintAuthorityMismatch,
+    #[msg("The community goal or its boost is still running")]
+    CommunityGoalActive,
+    #[msg("The community goal has not been reached")]
+    CommunityGoalNotReached,
+    #[msg("Farm did not contribute enough to the community goal")]
+    InsufficientGoalContribution,
+    #[msg("The community goal boost has ended")]
+    CommunityBoostExpired,
+    #[msg("The farm's yield is already tokenized")]
+    YieldAlreadyTokenized,
+    #[msg("The yield claim period has not ended")]
+    YieldClaimNotExpired,
+    #[msg("Cows can't leave a farm while its yield is tokenized")]
+    FarmYieldTokenized,
+    #[msg("Signer is not a guardian")]
+    NotGuardian,
+    #[msg("Guardian already vetoed the queued migration")]
+    AlreadyVetoed,
+    #[msg("Guardians can't change while a migration is queued")]
+    MigrationPending,
+    #[msg("Price feed account is not the configured Pyth MILK/USD update")]
+    InvalidPriceFeed,
+    #[msg("Pyth price confidence interval is too wide")]
+    PriceFeedUncertain,
+    #[msg("Too many farms for one batch")]
+    TooManyFarms,
+    #[msg("Farm already has the current layout")]
+    FarmAlreadyMigrated,
+    #[msg("Farm is rented out or its yield is tokenized")]
+    FarmEncumbered,
+    #[msg("The game is winding down")]
+    FinalMilkingActive,
+    #[msg("The game is not winding down")]
+    FinalMilkingNotActive,
+    #[msg("The final milking claim window is still open")]
+    FinalMilkingClaimWindowOpen,
+    #[msg("The final milking claim window has ended")]
+    FinalMilkingClaimWindowClosed,
+    #[msg("Premium Dairy is not offered")]
+    PremiumDisabled,
+    #[msg("Farm still holds cows or rewards")]
+    FarmNotEmpty,
+    #[msg("Farm belongs to a different owner")]
+    FarmOwnerMismatch,
+    #[msg("Gift campaign has expired")]
+    GiftCampaignExpired,
+    #[msg("Gift campaign is still open")]
+    GiftCampaignActive,
+    #[msg("Wallet is not on the gift campaign's allowlist")]
+    NotOnAllowlist,
+    #[msg("Emergency exit is disabled")]
+    EmergencyExitDisabled,
+    #[msg("Token account is not the fee hook's destination")]
+    InvalidFeeDestination,
+    #[msg("Streamed withdrawals are disabled")]
+    StreamingDisabled,
+    #[msg("Nothing has vested yet")]
+    NothingVested,
+    #[msg("Unknown cosmetic")]
+    UnknownCosmetic,
+    #[msg("Cosmetic is not available this season")]
+    CosmeticOutOfSeason,
+    #[msg("Farm stats don't meet the cosmetic's threshold")]
+    CosmeticLocked,
+    #[msg("Cosmetic already unlocked")]
+    CosmeticAlreadyUnlocked,
+    #[msg("Rewards are still locked")]
+    RewardsLocked,
+    #[msg("Farm has no locked rewards")]
+    NoLockedRewards,
+    #[msg("No boost is offered for this lock duration")]
+    LockBoostDisabled,
+    #[msg("Not enough staked COW")]
+    InsufficientStake,
+    #[msg("No staking rewards to claim")]
+    NoStakingRewards,
+    #[msg("This farm withholds part of each withdrawal; pass its withholding account")]
+    WithholdingAccountRequired,
+    #[msg("Not the farm's designated withholding account")]
+    InvalidWithholdingAccount,
+    #[msg("Not enough cows of that rarity")]
+    InsufficientTierCows,
+    #[msg("Cow upgrades are disabled")]
+    CowUpgradesDisabled,
+    #[msg("The farm's barn is full; upgrade it to hold more cows")]
+    BarnFull,
+    #[msg("The barn is at its highest level")]
+    BarnMaxLevel,
+    #[msg("Not allowed in the current game phase")]
+    BlockedInPhase,
+    #[msg("The game is already in its final phase")]
+    FinalPhase,
+    #[msg("A phase transition is already queued")]
+    PhaseTransitionQueued,
+    #[msg("No phase transition is queued")]
+    PhaseTransitionNotQueued,
+    #[msg("The phase transition timelock has not elapsed")]
+    PhaseTransitionTimelocked,
+    #[msg("Rules of the current or a past phase can't be changed")]
+    PhaseRulesFrozen,
+    #[msg("Feeding is disabled")]
+    FeedingDisabled,
+    #[msg("Breeding is disabled")]
+    BreedingDisabled,
+    #[msg("Invalid VRF randomness account")]
+    InvalidRandomness,
+    #[msg("VRF randomness has not been fulfilled yet")]
+    RandomnessNotFulfilled,
+    #[msg("Breeding can't be cancelled before its timeout")]
+    BreedingPending,
+    #[msg("Another event is scheduled or running")]
+    EventOverlap,
+    #[msg("Event has already ended")]
+    EventEnded,
+    #[msg("Config is already on the current layout")]
+    ConfigAlreadyMigrated,
+}
@@ -0,0 +1,578 @@
+//! Events emitted for indexers.
+
+use anchor_lang::prelude::*;
+use crate::state::{BreedingOutcome, CowRarity, FeeHookKind, GamePhase};
+use crate::types::{ConfigParam, ParamValue};
+
+#[event]
+pub struct ParamChanged {
+    pub sequence: u64,
+    pub param: ConfigParam,
+    pub old_value: ParamValue,
+    pub new_value: ParamValue,
+    pub changed_by: Pubkey,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct BuyEvent {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub num_cows: u64,
+    pub price_per_cow: u64,
+    pub total_cost: u64,
+    pub global_cows: u64,
+    pub reward_rate: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowsSold {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub num_cows: u64,
+    pub payout: u64,
+    pub global_cows: u64,
+    pub reward_rate: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawn {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub num_cows: u64,
+    pub payout: u64,
+    pub forfeited_rewards: u64,
+    pub global_cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeHookSwept {
+    pub kind: FeeHookKind,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompoundEvent {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub num_cows: u64,
+    pub price_per_cow: u64,
+    pub total_cost: u64,
+    pub global_cows: u64,
+    pub reward_rate: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub amount: u64,
+    pub penalty: u64,
+    pub reward_rate: u64,
+    pub memo: Option<String>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilkStreamed {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub amount: u64,
+    pub tax: u64,
+    pub unvested: u64,
+    pub ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedMilkClaimed {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CosmeticUnlocked {
+    pub wallet: Pubkey,
+    pub cosmetic_id: u8,
+    pub cosmetics: u64,
+    pub season: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsLocked {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub amount: u64,
+    pub boost_bps: u16,
+    pub locked_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsUnlocked {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithholdingSet {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub account: Pubkey,
+    pub bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilkWithheld {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub account: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowsUpgraded {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub rarity: CowRarity,
+    pub num_cows: u64,
+    pub cows_consumed: u64,
+    pub cows: u64,
+    pub global_cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BarnUpgraded {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub barn_level: u8,
+    pub capacity: u64,
+    pub milk_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PhaseTransitionQueued {
+    pub from: GamePhase,
+    pub to: GamePhase,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PhaseTransitionVetoed {
+    pub to: GamePhase,
+    pub guardian: Pubkey,
+    pub vetoes: u8,
+    pub threshold: u8,
+    pub cancelled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PhaseChanged {
+    pub from: GamePhase,
+    pub to: GamePhase,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventScheduled {
+    pub id: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub reward_bps: u16,
+    pub price_discount_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventCancelled {
+    pub id: u64,
+    pub ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BreedingRequested {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub seed: [u8; 32],
+    pub fee: u64,
+    pub cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowsBred {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub outcome: BreedingOutcome,
+    pub calves: u64,
+    pub cows: u64,
+    pub global_cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BreedingCancelled {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowsFed {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub cows: u64,
+    pub cost: u64,
+    pub next_feed_due: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExportEvent {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub num_cows: u64,
+    pub price_per_cow: u64,
+    pub export_fee: u64,
+    pub farm_cows: u64,
+    pub tokenized_cows: u64,
+    pub global_cows: u64,
+    pub reward_rate: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ImportEvent {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub num_cows: u64,
+    pub price_per_cow: u64,
+    pub producing_from: i64,
+    pub global_cows: u64,
+    pub reward_rate: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowStaked {
+    pub owner: Pubkey,
+    pub num_cows: u64,
+    pub staked_cows: u64,
+    pub total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowUnstaked {
+    pub owner: Pubkey,
+    pub num_cows: u64,
+    pub staked_cows: u64,
+    pub total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakingRewardsClaimed {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdapterDeposited {
+    pub farm: Pubkey,
+    pub authority: Pubkey,
+    pub num_cows: u64,
+    pub total_cost: u64,
+    pub cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdapterHarvested {
+    pub farm: Pubkey,
+    pub authority: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdapterWithdrawn {
+    pub farm: Pubkey,
+    pub authority: Pubkey,
+    pub recipient: Pubkey,
+    pub num_cows: u64,
+    pub payout: u64,
+    pub cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowVaultDeposited {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub num_cows: u64,
+    pub shares: u64,
+    pub vault_cows: u64,
+    pub total_shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowVaultRedeemed {
+    pub vault: Pubkey,
+    pub redeemer: Pubkey,
+    pub num_cows: u64,
+    pub shares: u64,
+    pub vault_cows: u64,
+    pub total_shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FarmRewardsTransferred {
+    pub from_farm: Pubkey,
+    pub to_farm: Pubkey,
+    pub from_owner: Pubkey,
+    pub to_owner: Pubkey,
+    pub from_tag: [u8; 32],
+    pub to_tag: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FarmTransferred {
+    pub from_farm: Pubkey,
+    pub to_farm: Pubkey,
+    pub from_owner: Pubkey,
+    pub to_owner: Pubkey,
+    pub cows: u64,
+    pub rewards: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowsGifted {
+    pub from_farm: Pubkey,
+    pub to_farm: Pubkey,
+    pub from_owner: Pubkey,
+    pub to_owner: Pubkey,
+    pub from_tag: [u8; 32],
+    pub to_tag: [u8; 32],
+    pub num_cows: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GiftCampaignCreated {
+    pub campaign: Pubkey,
+    pub sponsor: Pubkey,
+    pub id: u64,
+    pub num_cows: u64,
+    pub cows_per_claim: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GiftCowsClaimed {
+    pub campaign: Pubkey,
+    pub wallet: Pubkey,
+    pub farm: Pubkey,
+    pub tag: [u8; 32],
+    pub num_cows: u64,
+    pub cows_remaining: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GiftCampaignSwept {
+    pub campaign: Pubkey,
+    pub sponsor: Pubkey,
+    pub returned_cows: u64,
+    pub claims: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CowsLocked {
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub token_account: Pubkey,
+    pub cows: u64,
+    pub unlock_at: i64,
+}
+
+#[event]
+pub struct IdleFarmWarned {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub unclaimed_rewards: u64,
+    pub idle_since: i64,
+    pub reclaimable_at: i64,
+}
+
+#[event]
+pub struct IdleRewardsReclaimed {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeAuthorityMismatch {
+    pub recorded_authority: Pubkey,
+    pub current_authority: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrationQueued {
+    pub destination: Pubkey,
+    pub pool_balance: u64,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrationCancelled {
+    pub destination: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrationVetoed {
+    pub destination: Pubkey,
+    pub guardian: Pubkey,
+    pub vetoes: u8,
+    pub threshold: u8,
+    pub cancelled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FinalMilkingStarted {
+    pub pool: u64,
+    pub cows: u64,
+    pub claim_ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FinalMilkClaimed {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub cows: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FinalMilkingConcluded {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrationExecuted {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccrualPauseChanged {
+    pub paused: bool,
+    pub pool_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PausedRewardsCaughtUp {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub cow_seconds: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FarmHeartbeat {
+    pub farm: Pubkey,
+    pub tag: [u8; 32],
+    pub cows: u64,
+    pub rewards_bucket: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FarmCheckpointed {
+    pub farm: Pubkey,
+    pub tag: [u8; 32],
+    pub checkpoint: Pubkey,
+    pub checkpoint_id: u64,
+    pub cows: u64,
+    pub accumulated_rewards: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionLiquidated {
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub tag: [u8; 32],
+    pub liquidator: Pubkey,
+    pub seized_cows: u64,
+    pub proceeds: u64,
+    pub debt_cleared: u64,
+    pub liquidator_bonus: u64,
+    pub owner_surplus: u64,
+    pub timestamp: i64,
+}
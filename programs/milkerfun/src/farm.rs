@@ -0,0 +1,2052 @@
+//! Farm accounting: reward accrual, compounding and herd bookkeeping.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::*;
+use crate::errors::ErrorCode;
+use crate::helpers::route_fee;
+use crate::phase::{phase_buy_cap, phase_penalty_waiver, require_phase_allows};
+use crate::events::{AccrualPauseChanged, CompoundEvent, ExportEvent, ImportEvent, FarmHeartbeat};
+use crate::math::{apply_bonus_bps, apply_signed_bps, calculate_cow_cost, calculate_cow_price_with, max_affordable_cows, calculate_reward_rate_with, calculate_withdrawal_tax, economic_params, event_weighted_bps_seconds};
+use crate::rounding::{self, USER_CREDIT, USER_DEBIT};
+use crate::state::*;
+
+/// Remove cows from a farm's producing herd. The cows leaving take an average
+/// share of the farm's genetics and cost basis with them.
+pub(crate) fn take_cows_from_herd(farm: &mut FarmAccount, num_cows: u64) -> Result<()> {
+    require!(farm.cows >= num_cows, ErrorCode::InsufficientCows);
+    require!(tier_cows(farm, CowRarity::Common) >= num_cows, ErrorCode::InsufficientTierCows);
+    require!(
+        farm.yield_claim_until <= Clock::get()?.unix_timestamp,
+        ErrorCode::FarmYieldTokenized
+    );
+    if num_cows == 0 {
+        return Ok(());
+    }
+
+    // The leaving cows' shares round down, towards negative for genetics,
+    // so the remainder stays with the herd
+    let genetics_rounding = if farm.genetics_bps_sum < 0 { USER_DEBIT } else { USER_CREDIT };
+    let genetics_share = rounding::mul_div(farm.genetics_bps_sum.unsigned_abs(), num_cows, farm.cows, genetics_rounding)?;
+    farm.genetics_bps_sum -= genetics_share as i64 * farm.genetics_bps_sum.signum();
+    farm.cost_basis -= rounding::mul_div(farm.cost_basis, num_cows, farm.cows, USER_CREDIT)?;
+    farm.cows -= num_cows;
+    Ok(())
+}
+
+/// Cows of `rarity` in the farm's producing herd. Common cows are whatever
+/// isn't Rare or Epic.
+pub(crate) fn tier_cows(farm: &FarmAccount, rarity: CowRarity) -> u64 {
+    match rarity {
+        CowRarity::Common => farm.cows.saturating_sub(farm.rare_cows).saturating_sub(farm.epic_cows),
+        CowRarity::Rare => farm.rare_cows,
+        CowRarity::Epic => farm.epic_cows,
+    }
+}
+
+fn tier_count_mut(farm: &mut FarmAccount, rarity: CowRarity) -> Option<&mut u64> {
+    match rarity {
+        CowRarity::Common => None,
+        CowRarity::Rare => Some(&mut farm.rare_cows),
+        CowRarity::Epic => Some(&mut farm.epic_cows),
+    }
+}
+
+/// Remove `num_cows` of `rarity` from a settled farm's herd
+pub(crate) fn take_tier_cows(farm: &mut FarmAccount, rarity: CowRarity, num_cows: u64) -> Result<()> {
+    require!(tier_cows(farm, rarity) >= num_cows, ErrorCode::InsufficientTierCows);
+    if let Some(count) = tier_count_mut(farm, rarity) {
+        // Demote first so they leave the herd as commons
+        *count -= num_cows;
+    }
+    take_cows_from_herd(farm, num_cows)
+}
+
+/// Combine `num_cows * ratio` cows of the tier below `rarity` into
+/// `num_cows` cows of `rarity`. Returns how many cows left the herd.
+pub(crate) fn upgrade_tier_cows(farm: &mut FarmAccount, rarity: CowRarity, num_cows: u64, ratio: u8) -> Result<u64> {
+    let lower = match rarity {
+        CowRarity::Common => return err!(ErrorCode::InvalidParameter),
+        CowRarity::Rare => CowRarity::Common,
+        CowRarity::Epic => CowRarity::Rare,
+    };
+    let consumed = num_cows
+        .checked_mul(ratio as u64)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(tier_cows(farm, lower) >= consumed, ErrorCode::InsufficientTierCows);
+    let removed = consumed - num_cows;
+
+    if let Some(count) = tier_count_mut(farm, lower) {
+        *count -= consumed;
+    }
+    // The upgraded cows stay in the herd, so only the rest leave it
+    take_cows_from_herd(farm, removed)?;
+    if let Some(count) = tier_count_mut(farm, rarity) {
+        *count += num_cows;
+    }
+    Ok(removed)
+}
+
+/// Most cows the farm's barn holds, producing and quarantined together.
+/// Unlimited while the admin hasn't set a base capacity.
+pub(crate) fn barn_capacity(farm: &FarmAccount, config: &Config) -> u64 {
+    if config.barn_base_capacity == 0 {
+        return u64::MAX;
+    }
+    config.barn_base_capacity
+        .saturating_add(config.barn_capacity_per_level.saturating_mul(farm.barn_level as u64))
+}
+
+/// Check the farm's barn has room for `num_cows` more
+pub(crate) fn check_barn_capacity(farm: &FarmAccount, config: &Config, num_cows: u64) -> Result<()> {
+    let herd = farm.cows
+        .saturating_add(farm.pending_cows)
+        .saturating_add(num_cows);
+    require!(herd <= barn_capacity(farm, config), ErrorCode::BarnFull);
+    Ok(())
+}
+
+/// MILK burned to raise the farm's barn to the next level
+pub(crate) fn barn_upgrade_cost(farm: &FarmAccount, config: &Config) -> Result<u64> {
+    config.barn_upgrade_cost
+        .checked_mul(farm.barn_level as u64 + 1)
+        .ok_or(error!(ErrorCode::MathOverflow))
+}
+
+/// Take a breeding pair out of a settled farm's herd, returning the
+/// genetics and cost basis they carry
+pub(crate) fn take_breeding_parents(farm: &mut FarmAccount) -> Result<(i64, u64)> {
+    let (genetics, cost_basis) = (farm.genetics_bps_sum, farm.cost_basis);
+    take_cows_from_herd(farm, BREEDING_PARENTS)?;
+    Ok((genetics - farm.genetics_bps_sum, cost_basis - farm.cost_basis))
+}
+
+/// Pick a breeding outcome from VRF randomness and the configured odds
+pub(crate) fn roll_breeding(config: &Config, randomness: &[u8; 64]) -> BreedingOutcome {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&randomness[..8]);
+    let roll = u64::from_le_bytes(word) % BPS_DENOMINATOR;
+
+    let mut cumulative = 0u64;
+    for outcome in [
+        BreedingOutcome::OneCalf,
+        BreedingOutcome::TwoCalves,
+        BreedingOutcome::ThreeCalves,
+        BreedingOutcome::RareCalf,
+    ] {
+        cumulative += config.breeding_odds[outcome as usize] as u64;
+        if roll < cumulative {
+            return outcome;
+        }
+    }
+    // Unreachable while the odds sum to 100%
+    BreedingOutcome::OneCalf
+}
+
+/// Calves an outcome brings into the farm, as many as its barn has room
+/// for but never fewer than one
+pub(crate) fn breeding_calves(farm: &FarmAccount, config: &Config, outcome: BreedingOutcome) -> u64 {
+    let born = match outcome {
+        BreedingOutcome::OneCalf | BreedingOutcome::RareCalf => 1,
+        BreedingOutcome::TwoCalves => 2,
+        BreedingOutcome::ThreeCalves => 3,
+    };
+    let herd = farm.cows.saturating_add(farm.pending_cows);
+    born.min(barn_capacity(farm, config).saturating_sub(herd).max(1))
+}
+
+/// Add calves to a settled farm. They share the parents' average genetics
+/// and take over their cost basis.
+pub(crate) fn add_calves(
+    farm: &mut FarmAccount,
+    config: &mut Config,
+    request: &BreedingRequest,
+    calves: u64,
+    rare: bool,
+) -> Result<()> {
+    config.global_cows_count = config.global_cows_count
+        .checked_add(calves)
+        .ok_or(ErrorCode::MathOverflow)?;
+    config.farming_cows_count = config.farming_cows_count
+        .checked_add(calves)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    farm.cows = farm.cows
+        .checked_add(calves)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if rare {
+        farm.rare_cows += calves;
+    }
+    farm.genetics_bps_sum = farm.genetics_bps_sum
+        .checked_add(request.genetics_bps_sum * calves as i64 / BREEDING_PARENTS as i64)
+        .ok_or(ErrorCode::MathOverflow)?;
+    farm.cost_basis = farm.cost_basis
+        .checked_add(request.cost_basis)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Average yield modifier of the farm's Rare and Epic cows across its herd
+pub(crate) fn farm_rarity_bps(farm: &FarmAccount, config: &Config) -> i64 {
+    if farm.cows == 0 {
+        return 0;
+    }
+    let bonus = |rarity: CowRarity| {
+        let reward_bps = config.cow_tiers[rarity as usize].reward_bps as i128;
+        (reward_bps - BPS_DENOMINATOR as i128).max(0) * tier_cows(farm, rarity) as i128
+    };
+    ((bonus(CowRarity::Rare) + bonus(CowRarity::Epic)) / farm.cows as i128) as i64
+}
+
+/// Move producing cows between two settled farms. The recipient takes the
+/// same average share of genetics and cost basis the sender gives up.
+pub(crate) fn gift_cows_between(from: &mut FarmAccount, to: &mut FarmAccount, num_cows: u64) -> Result<()> {
+    let genetics_before = from.genetics_bps_sum;
+    let cost_basis_before = from.cost_basis;
+    take_cows_from_herd(from, num_cows)?;
+
+    to.cows = to.cows.checked_add(num_cows).ok_or(ErrorCode::MathOverflow)?;
+    to.genetics_bps_sum = to.genetics_bps_sum
+        .checked_add(genetics_before - from.genetics_bps_sum)
+        .ok_or(ErrorCode::MathOverflow)?;
+    to.cost_basis = to.cost_basis.saturating_add(cost_basis_before - from.cost_basis);
+    Ok(())
+}
+
+/// Whether `proof` leads from `leaf` to `root`, hashing each pair of
+/// siblings in sorted order
+pub(crate) fn verify_allowlist_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
+
+/// Add cows that came back from COW tokens to a farm, through quarantine if
+/// it is on. The caller settles the farm and burns the tokens first.
+pub(crate) fn import_to_farm(
+    farm: &mut Account<FarmAccount>,
+    config: &mut Config,
+    current_time: i64,
+    num_cows: u64,
+) -> Result<()> {
+    require_phase_allows(config, PHASE_BLOCK_IMPORT)?;
+
+    // COW tokens minted before tokenized supply was tracked can't be told
+    // apart, so never let the counter underflow
+    config.tokenized_cows_count = config.tokenized_cows_count.saturating_sub(num_cows);
+    config.total_cows_imported = config.total_cows_imported.saturating_add(num_cows);
+    // Curve value of the cows coming back, as export_from_farm prices them
+    let price_per_cow = calculate_cow_price_with(&economic_params(config), config.global_cows_count)?;
+
+    if config.import_quarantine_seconds > 0 {
+        // Quarantined cows sit in the pending bucket and only join the herd
+        // (and the global count) once the delay has elapsed. Topping up the
+        // bucket restarts the delay for every cow in it.
+        farm.pending_cows = farm.pending_cows
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        farm.pending_cows_ready_at = current_time
+            .checked_add(config.import_quarantine_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(ImportEvent {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            num_cows,
+            price_per_cow,
+            producing_from: farm.pending_cows_ready_at,
+            global_cows: config.global_cows_count,
+            reward_rate: config.reward_rate,
+            timestamp: current_time,
+        });
+
+        msg!("Successfully imported {} COW tokens into quarantine. Pending cows: {}, producing from: {}", 
+             num_cows, farm.pending_cows, farm.pending_cows_ready_at);
+        return Ok(());
+    }
+
+    // Add cows to farm
+    farm.cows = farm.cows
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // Update global cow count
+    config.global_cows_count = config.global_cows_count
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.farming_cows_count = config.farming_cows_count
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // Calculate new reward rate
+    let new_reward_rate = sync_reward_accumulator(config, current_time)?;
+
+    emit!(ImportEvent {
+        farm: farm.key(),
+        owner: farm.owner,
+        tag: farm.tag,
+        num_cows,
+        price_per_cow,
+        producing_from: current_time,
+        global_cows: config.global_cows_count,
+        reward_rate: new_reward_rate,
+        timestamp: current_time,
+    });
+
+    msg!("Successfully imported {} COW tokens to cows. User total cows: {}, Global total: {}", 
+         num_cows, farm.cows, config.global_cows_count);
+    Ok(())
+}
+
+/// Settle a vault's farm and reinvest its rewards in as many whole cows as
+/// they buy, so shares are always priced against a compounded herd.
+/// Returns the number of cows added.
+pub(crate) fn compound_vault_farm(
+    farm: &mut Account<FarmAccount>,
+    config: &mut Config,
+    current_time: i64,
+) -> Result<u64> {
+    update_farm_rewards(farm, config, current_time)?;
+    farm.last_interaction_time = current_time;
+
+    let num_cows = max_affordable_cows(config, config.global_cows_count, farm.accumulated_rewards, current_time)?
+        .min(max_cows_per_transaction(config, config.tvl, current_time)?);
+    // A phase that blocks compounding holds the vault's rewards as MILK
+    if num_cows > 0 && require_phase_allows(config, PHASE_BLOCK_COMPOUND).is_ok() {
+        compound_farm(farm, config, current_time, num_cows)?;
+    }
+    Ok(num_cows)
+}
+
+/// Settle a farm and take cows out of it to be minted as COW tokens. The
+/// owner keeps the rewards accrued up to now.
+pub(crate) fn export_from_farm(
+    farm: &mut Account<FarmAccount>,
+    config: &mut Config,
+    current_time: i64,
+    num_cows: u64,
+) -> Result<()> {
+    require!(!config.paused, ErrorCode::ProgramPaused);
+    require_phase_allows(config, PHASE_BLOCK_EXPORT)?;
+    update_farm_rewards(farm, config, current_time)?;
+    farm.last_interaction_time = current_time;
+
+    // Priced on the curve before the cows leave, like any other sale of
+    // the whole lot, so event discounts don't cheapen the fee
+    let price_per_cow = calculate_cow_price_with(&economic_params(config), config.global_cows_count)?;
+    let export_value = price_per_cow
+        .checked_mul(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let export_fee = route_fee(config, FeeHookKind::ExportFee, export_value)?;
+    if export_fee > 0 {
+        require!(farm.accumulated_rewards >= export_fee, ErrorCode::InsufficientRewards);
+        farm.accumulated_rewards -= export_fee;
+        msg!("Export fee: {} MILK from rewards", export_fee / 1_000_000);
+    }
+
+    take_cows_from_herd(farm, num_cows)?;
+
+    // Exported cows leave the productive herd and circulate as COW tokens
+    config.farming_cows_count = config.farming_cows_count.saturating_sub(num_cows);
+    config.tokenized_cows_count = config.tokenized_cows_count
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+    config.total_cows_exported = config.total_cows_exported.saturating_add(num_cows);
+
+    emit!(ExportEvent {
+        farm: farm.key(),
+        owner: farm.owner,
+        tag: farm.tag,
+        num_cows,
+        price_per_cow,
+        export_fee,
+        farm_cows: farm.cows,
+        tokenized_cows: config.tokenized_cows_count,
+        global_cows: config.global_cows_count,
+        reward_rate: config.reward_rate,
+        timestamp: current_time,
+    });
+    Ok(())
+}
+
+/// Append a withdrawal to a farm's harvest log, overwriting the oldest entry
+pub(crate) fn record_harvest(log: &mut HarvestLog, amount: u64, penalty: u64, timestamp: i64) {
+    let slot = (log.total_harvests % HARVEST_LOG_LEN as u64) as usize;
+    log.entries[slot] = HarvestEntry {
+        amount,
+        penalty,
+        timestamp,
+    };
+    log.total_harvests += 1;
+}
+
+/// Settle a farm for withdrawal: accrue rewards, apply the early-withdrawal
+/// penalty to `amount` (all rewards if `None`) and take it off the farm's
+/// balance. The rest keeps accruing. The payout is bounded by the booked
+/// TVL and `pool_balance`, the pool's reloaded balance. Returns (payout,
+/// penalty); the caller is responsible for moving the payout out of the pool.
+pub(crate) fn settle_withdrawal(
+    farm: &mut Account<FarmAccount>,
+    config: &mut Config,
+    penalty_grace: Option<&PenaltyGrace>,
+    current_time: i64,
+    amount: Option<u64>,
+    pool_balance: u64,
+) -> Result<(u64, u64)> {
+    require!(!config.paused, ErrorCode::ProgramPaused);
+    update_farm_rewards(farm, config, current_time)?;
+    take_withdrawal(farm, config, penalty_grace, current_time, amount, pool_balance)
+}
+
+/// Take `amount` (all rewards if `None`) off an already settled farm's
+/// balance, applying the early-withdrawal penalty and the withdrawal tax.
+/// Returns (payout, penalty).
+pub(crate) fn take_withdrawal(
+    farm: &mut FarmAccount,
+    config: &mut Config,
+    penalty_grace: Option<&PenaltyGrace>,
+    current_time: i64,
+    amount: Option<u64>,
+    pool_balance: u64,
+) -> Result<(u64, u64)> {
+    require!(farm.accumulated_rewards > 0, ErrorCode::NoRewardsAvailable);
+
+    let total_rewards = match amount {
+        Some(amount) => {
+            require!(amount > 0, ErrorCode::InvalidAmount);
+            require!(amount <= farm.accumulated_rewards, ErrorCode::InsufficientRewards);
+            amount
+        }
+        None => farm.accumulated_rewards,
+    };
+    
+    let hours_since_last_withdraw = if farm.last_withdraw_time == 0 {
+        25 // First withdrawal - no penalty
+    } else {
+        (current_time - farm.last_withdraw_time) / 3600 // Convert to hours
+    };
+    
+    let (withdrawal_amount, penalty_amount) = if hours_since_last_withdraw >= 24 {
+        msg!("Penalty-free withdrawal: {} MILK tokens", total_rewards / 1_000_000);
+        (total_rewards, 0)
+    } else if penalty_grace.is_some_and(|grace| (grace.starts_at..grace.ends_at).contains(&current_time)) {
+        msg!("Penalty waived during grace window: withdrawing {} MILK (last withdraw: {} hours ago)", 
+             total_rewards / 1_000_000, hours_since_last_withdraw);
+        (total_rewards, 0)
+    } else if is_penalty_exempt(farm, config) {
+        msg!("Small farm exempt from penalty: withdrawing {} MILK (last withdraw: {} hours ago)", 
+             total_rewards / 1_000_000, hours_since_last_withdraw);
+        (total_rewards, 0)
+    } else if farm.forgiveness_pass_armed {
+        farm.forgiveness_pass_armed = false;
+        farm.forgiveness_passes_redeemed = farm.forgiveness_passes_redeemed.saturating_add(1);
+        msg!("Forgiveness pass redeemed: withdrawing {} MILK penalty-free (last withdraw: {} hours ago)", 
+             total_rewards / 1_000_000, hours_since_last_withdraw);
+        (total_rewards, 0)
+    } else {
+        let mut withdrawal = rounding::mul_div(total_rewards, 1, 2, USER_CREDIT)?;
+        if is_premium(farm, current_time) {
+            let waived = rounding::mul_div(
+                total_rewards - withdrawal,
+                config.premium_penalty_discount_bps as u64,
+                BPS_DENOMINATOR,
+                USER_CREDIT,
+            )?;
+            withdrawal += waived;
+        }
+        withdrawal += phase_penalty_waiver(config, total_rewards - withdrawal)?;
+        let penalty = total_rewards - withdrawal;
+        msg!("Withdrawal with 50% penalty: withdrawing {} MILK, {} MILK penalty stays in pool (last withdraw: {} hours ago)", 
+             withdrawal / 1_000_000, penalty / 1_000_000, hours_since_last_withdraw);
+        (withdrawal, penalty)
+    };
+
+    let withdrawal_amount = withdrawal_amount.min(config.tvl).min(pool_balance);
+
+    let withdrawal_amount = withdrawal_amount - charge_withdrawal_tax(config, farm, withdrawal_amount, current_time)?;
+
+    if penalty_amount > 0 {
+        if config.penalty_destination == PenaltyDestination::GrantsVault {
+            config.grants_pending = config.grants_pending
+                .checked_add(penalty_amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            route_fee(config, FeeHookKind::WithdrawalPenalty, penalty_amount)?;
+        }
+    }
+
+    farm.accumulated_rewards -= total_rewards;
+    farm.last_withdraw_time = current_time;
+    farm.withdrawal_count = farm.withdrawal_count.saturating_add(1);
+    farm.total_milk_withdrawn = farm.total_milk_withdrawn.saturating_add(withdrawal_amount);
+    farm.total_penalties_paid = farm.total_penalties_paid.saturating_add(penalty_amount);
+
+    Ok((withdrawal_amount, penalty_amount))
+}
+
+/// Undo the settlement of a rejected escrowed withdrawal: return the gross
+/// rewards to the farm, release the penalty and tax set aside for it and
+/// restore the withdrawal clock and any forgiveness pass it used. Earmarks
+/// already swept can't be recovered and only come off what is left.
+pub(crate) fn unsettle_escrowed_withdrawal(farm: &mut FarmAccount, config: &mut Config, escrow: &WithdrawalEscrow) -> Result<()> {
+    farm.accumulated_rewards = farm.accumulated_rewards
+        .checked_add(escrow.gross_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    // A later withdrawal keeps its own clock
+    if farm.last_withdraw_time == escrow.requested_at {
+        farm.last_withdraw_time = escrow.prior_withdraw_time;
+    }
+    if escrow.pass_redeemed {
+        farm.forgiveness_pass_armed = true;
+        farm.forgiveness_passes_redeemed = farm.forgiveness_passes_redeemed.saturating_sub(1);
+    }
+    farm.total_milk_withdrawn = farm.total_milk_withdrawn.saturating_sub(escrow.amount);
+    farm.total_penalties_paid = farm.total_penalties_paid.saturating_sub(escrow.penalty);
+    farm.tax_window_withdrawn = farm.tax_window_withdrawn.saturating_sub(escrow.amount + escrow.tax);
+
+    config.grants_pending = config.grants_pending.saturating_sub(escrow.grants_earmarked);
+    let fee_pending = &mut config.fee_hook_pending[FeeHookKind::WithdrawalPenalty as usize];
+    *fee_pending = fee_pending.saturating_sub(escrow.fee_earmarked);
+    config.new_farmer_fund_pending = config.new_farmer_fund_pending.saturating_sub(escrow.tax);
+    config.total_withdrawal_tax = config.total_withdrawal_tax.saturating_sub(escrow.tax);
+    Ok(())
+}
+
+/// Take the large-withdrawal tax on `amount`. The brackets apply to
+/// everything the farm withdrew in its current 24h tax window, so splitting
+/// a withdrawal doesn't drop it into lower brackets. The tax stays in the
+/// pool, earmarked for the new farmer fund until swept. Returns the tax.
+pub(crate) fn charge_withdrawal_tax(config: &mut Config, farm: &mut FarmAccount, amount: u64, current_time: i64) -> Result<u64> {
+    if current_time >= farm.tax_window_started_at.saturating_add(SECONDS_PER_DAY) {
+        farm.tax_window_started_at = current_time;
+        farm.tax_window_withdrawn = 0;
+    }
+    let withdrawn = farm.tax_window_withdrawn.saturating_add(amount);
+    let tax = calculate_withdrawal_tax(config, withdrawn)?
+        .saturating_sub(calculate_withdrawal_tax(config, farm.tax_window_withdrawn)?)
+        .min(amount);
+    farm.tax_window_withdrawn = withdrawn;
+    if tax > 0 {
+        config.new_farmer_fund_pending = config.new_farmer_fund_pending
+            .checked_add(tax)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.total_withdrawal_tax = config.total_withdrawal_tax.saturating_add(tax);
+        msg!("Large withdrawal tax: {} MILK to the new farmer fund", tax / 1_000_000);
+    }
+    Ok(tax)
+}
+
+/// MILK of the current schedule vested by `current_time`, rounded down
+pub(crate) fn vested_in_schedule(stream: &VestingStream, current_time: i64) -> Result<u64> {
+    if current_time >= stream.ends_at {
+        return Ok(stream.amount);
+    }
+    if current_time <= stream.starts_at {
+        return Ok(0);
+    }
+    rounding::mul_div(
+        stream.amount,
+        (current_time - stream.starts_at) as u64,
+        (stream.ends_at - stream.starts_at) as u64,
+        USER_CREDIT,
+    )
+}
+
+/// Vested MILK the owner can claim now
+pub(crate) fn claimable_vested(stream: &VestingStream, current_time: i64) -> Result<u64> {
+    Ok(stream.carried + vested_in_schedule(stream, current_time)?.saturating_sub(stream.claimed))
+}
+
+/// Add `amount` to a stream, restarting the schedule at `current_time` for
+/// it and everything still unvested
+pub(crate) fn top_up_stream(stream: &mut VestingStream, amount: u64, current_time: i64, period: i64) -> Result<()> {
+    let vested = vested_in_schedule(stream, current_time)?;
+    stream.carried = stream.carried
+        .checked_add(vested.saturating_sub(stream.claimed))
+        .ok_or(ErrorCode::MathOverflow)?;
+    stream.amount = (stream.amount - vested)
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stream.claimed = 0;
+    stream.starts_at = current_time;
+    stream.ends_at = current_time.checked_add(period).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Move a settled farm's herd, rewards and lifetime stats onto another
+/// settled farm and zero the source. Global counts don't change since the
+/// cows stay in play. Per-farm settings (preferences, tag, referrer,
+/// streak) carry over only to a freshly created destination. Rental and
+/// yield claim balances owed from the source stay with it.
+pub(crate) fn move_farm(from: &mut FarmAccount, to: &mut FarmAccount, fresh: bool, current_time: i64) -> Result<()> {
+    require!(
+        from.rental_share_bps == 0 && to.rental_share_bps == 0,
+        ErrorCode::FarmEncumbered
+    );
+    require!(
+        from.yield_claim_until <= current_time && to.yield_claim_until <= current_time,
+        ErrorCode::FarmEncumbered
+    );
+    require!(
+        from.locked_rewards == 0 && to.locked_rewards == 0,
+        ErrorCode::FarmEncumbered
+    );
+
+    if fresh {
+        to.compound_day_mask = from.compound_day_mask;
+        to.last_compound_day = from.last_compound_day;
+        to.automation_opt_in = from.automation_opt_in;
+        to.preferences = from.preferences;
+        to.tag = from.tag;
+        to.referrer = from.referrer;
+        to.community_goal_id = from.community_goal_id;
+        to.community_goal_contribution = from.community_goal_contribution;
+        to.community_boost_bps = from.community_boost_bps;
+        to.community_boost_until = from.community_boost_until;
+        to.farm_created_at = from.farm_created_at;
+        to.paused_clock = from.paused_clock;
+        to.bonus_campaign_id = from.bonus_campaign_id;
+    } else if from.community_goal_id == to.community_goal_id {
+        to.community_goal_contribution = to.community_goal_contribution
+            .saturating_add(from.community_goal_contribution);
+    }
+
+    to.cows = to.cows.checked_add(from.cows).ok_or(ErrorCode::MathOverflow)?;
+    to.rare_cows = to.rare_cows.saturating_add(from.rare_cows);
+    to.epic_cows = to.epic_cows.saturating_add(from.epic_cows);
+    to.genetics_bps_sum = to.genetics_bps_sum
+        .checked_add(from.genetics_bps_sum)
+        .ok_or(ErrorCode::MathOverflow)?;
+    to.cost_basis = to.cost_basis.saturating_add(from.cost_basis);
+    to.accumulated_rewards = to.accumulated_rewards
+        .checked_add(from.accumulated_rewards)
+        .ok_or(ErrorCode::MathOverflow)?;
+    to.bonus_rewards = to.bonus_rewards.saturating_add(from.bonus_rewards);
+    to.paused_cow_seconds = to.paused_cow_seconds.saturating_add(from.paused_cow_seconds);
+    if from.pending_cows > 0 {
+        to.pending_cows = to.pending_cows
+            .checked_add(from.pending_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        to.pending_cows_ready_at = to.pending_cows_ready_at.max(from.pending_cows_ready_at);
+    }
+    // The later withdrawal clock wins, as with reward transfers
+    to.last_withdraw_time = to.last_withdraw_time.max(from.last_withdraw_time);
+    to.tax_window_started_at = to.tax_window_started_at.max(from.tax_window_started_at);
+    to.tax_window_withdrawn = to.tax_window_withdrawn.saturating_add(from.tax_window_withdrawn);
+    to.premium_until = to.premium_until.max(from.premium_until);
+    to.forgiveness_passes = to.forgiveness_passes.saturating_add(from.forgiveness_passes);
+    to.forgiveness_pass_armed |= from.forgiveness_pass_armed;
+    to.forgiveness_passes_redeemed = to.forgiveness_passes_redeemed.saturating_add(from.forgiveness_passes_redeemed);
+    to.lifetime_compounded_cows = to.lifetime_compounded_cows.saturating_add(from.lifetime_compounded_cows);
+    to.referral_earnings = to.referral_earnings.saturating_add(from.referral_earnings);
+    to.withdrawal_count = to.withdrawal_count.saturating_add(from.withdrawal_count);
+    to.total_milk_spent = to.total_milk_spent.saturating_add(from.total_milk_spent);
+    to.total_milk_withdrawn = to.total_milk_withdrawn.saturating_add(from.total_milk_withdrawn);
+    to.total_penalties_paid = to.total_penalties_paid.saturating_add(from.total_penalties_paid);
+    if from.farm_created_at > 0 && (to.farm_created_at == 0 || from.farm_created_at < to.farm_created_at) {
+        to.farm_created_at = from.farm_created_at;
+    }
+
+    from.cows = 0;
+    from.rare_cows = 0;
+    from.epic_cows = 0;
+    from.genetics_bps_sum = 0;
+    from.cost_basis = 0;
+    from.accumulated_rewards = 0;
+    from.bonus_rewards = 0;
+    from.paused_cow_seconds = 0;
+    from.pending_cows = 0;
+    from.pending_cows_ready_at = 0;
+    from.compound_day_mask = 0;
+    from.forgiveness_passes = 0;
+    from.forgiveness_pass_armed = false;
+    from.forgiveness_passes_redeemed = 0;
+    from.lifetime_compounded_cows = 0;
+    from.referral_earnings = 0;
+    from.withdrawal_count = 0;
+    from.total_milk_spent = 0;
+    from.total_milk_withdrawn = 0;
+    from.total_penalties_paid = 0;
+    from.community_goal_contribution = 0;
+    from.community_boost_bps = 0;
+    from.community_boost_until = 0;
+    from.premium_until = 0;
+    Ok(())
+}
+
+/// Credit a referrer's farm with its share of a referred farm's volume.
+/// Returns the amount credited.
+pub(crate) fn credit_referral(referrer_farm: &mut FarmAccount, volume: u64, share_bps: u16) -> Result<u64> {
+    let share = rounding::mul_div(volume, share_bps as u64, BPS_DENOMINATOR, USER_CREDIT)?;
+    if share == 0 {
+        return Ok(0);
+    }
+
+    referrer_farm.accumulated_rewards = referrer_farm.accumulated_rewards
+        .checked_add(share)
+        .ok_or(ErrorCode::MathOverflow)?;
+    referrer_farm.referral_earnings = referrer_farm.referral_earnings.saturating_add(share);
+    Ok(share)
+}
+
+/// Add a referred farm's volume and the credit it earned to its referrer's
+/// stats
+pub(crate) fn record_referral(referrer_stats: &mut Referrer, farm: &FarmAccount, volume: u64, share: u64) -> Result<()> {
+    require!(referrer_stats.owner == farm.referrer, ErrorCode::InvalidReferrer);
+    referrer_stats.referred_volume = referrer_stats.referred_volume.saturating_add(volume);
+    referrer_stats.earnings = referrer_stats.earnings.saturating_add(share);
+    Ok(())
+}
+
+/// Whether a farm is small enough, by herd size or reward balance, to skip
+/// the early-withdrawal penalty. The balance is checked rather than the
+/// amount withdrawn, so partial withdrawals can't slip under the threshold.
+fn is_penalty_exempt(farm: &FarmAccount, config: &Config) -> bool {
+    (config.penalty_exempt_below_cows > 0 && farm.cows < config.penalty_exempt_below_cows)
+        || (config.penalty_exempt_below_rewards > 0 && farm.accumulated_rewards < config.penalty_exempt_below_rewards)
+}
+
+/// Advance the global reward accumulator to `current_time` at the rate in
+/// force since its last advance, then re-price the rate from the current
+/// herd and pool. Call after anything that moves the global cow count or
+/// the TVL, so the new rate applies from that moment on. Returns the new rate.
+pub(crate) fn sync_reward_accumulator(config: &mut Config, current_time: i64) -> Result<u64> {
+    if config.reward_rate_updated_at > 0 && current_time > config.reward_rate_updated_at {
+        // Seconds inside an event window earn at its multiplier
+        let elapsed = event_weighted_bps_seconds(config, config.reward_rate_updated_at, current_time);
+        let growth = (config.reward_rate as u128)
+            .checked_mul(elapsed)
+            .and_then(|v| v.checked_mul(ACC_REWARD_PRECISION))
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.acc_reward_per_cow = config.acc_reward_per_cow
+            .checked_add(rounding::div_wide(growth, SECONDS_PER_DAY as u128 * BPS_DENOMINATOR as u128, USER_CREDIT)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    config.reward_rate_updated_at = config.reward_rate_updated_at.max(current_time);
+    config.reward_rate = calculate_reward_rate_with(&economic_params(config), config.global_cows_count, config.tvl)?;
+    Ok(config.reward_rate)
+}
+
+/// Start a new farm's reward clock at the current accumulator
+pub(crate) fn start_reward_clock(farm: &mut FarmAccount, config: &mut Config, current_time: i64) -> Result<()> {
+    sync_reward_accumulator(config, current_time)?;
+    farm.last_update_time = current_time;
+    farm.reward_debt = config.acc_reward_per_cow;
+    Ok(())
+}
+
+/// Move a farm created before the reward accumulator onto it: credit what
+/// its herd earned since its last update at the rate it snapshotted (the
+/// live rate if it never took one), then start its clock at the current
+/// accumulator. Returns the rewards credited.
+pub(crate) fn adopt_reward_accumulator(farm: &mut FarmAccount, config: &mut Config, current_time: i64) -> Result<u64> {
+    sync_reward_accumulator(config, current_time)?;
+    let rate = if farm.last_reward_rate == 0 { config.reward_rate } else { farm.last_reward_rate };
+    let elapsed = (current_time - farm.last_update_time).max(0) as u128;
+    let earned = rounding::div(
+        (farm.cows as u128)
+            .checked_mul(rate as u128)
+            .and_then(|v| v.checked_mul(elapsed))
+            .ok_or(ErrorCode::MathOverflow)?,
+        SECONDS_PER_DAY as u128,
+        USER_CREDIT,
+    )?;
+    farm.accumulated_rewards = farm.accumulated_rewards
+        .checked_add(earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    farm.last_reward_rate = 0;
+    start_reward_clock(farm, config, current_time)?;
+    Ok(earned)
+}
+
+/// Take ownership of a farm account created by `init_if_needed`, or check
+/// that an existing one belongs to `owner`, so a farm is never silently
+/// adopted by a second signer. Returns whether the farm was just opened.
+pub(crate) fn open_farm(farm: &mut FarmAccount, owner: Pubkey, config: &mut Config, current_time: i64) -> Result<bool> {
+    if farm.owner != Pubkey::default() {
+        require_keys_eq!(farm.owner, owner, ErrorCode::FarmOwnerMismatch);
+        return Ok(false);
+    }
+
+    farm.owner = owner;
+    farm.cows = 0;
+    farm.accumulated_rewards = 0;
+    farm.last_fed_at = current_time;
+    start_reward_clock(farm, config, current_time)?;
+    farm.farm_created_at = current_time;
+    Ok(true)
+}
+
+/// Update farm rewards from the global accumulator. Every farm earns the
+/// accumulator's growth since its own last update, so all farms earn at the
+/// same live rate however rarely they touch the chain.
+/// Quarantined imports earn from the moment their delay elapses and are
+/// promoted into the herd (and the global count) on the first update after that
+pub(crate) fn update_farm_rewards(
+    farm: &mut Account<FarmAccount>, 
+    config: &mut Config, 
+    current_time: i64,
+) -> Result<()> {
+    let current_tvl = config.tvl;
+    sync_accrual_pause(config, current_time, current_tvl);
+    sync_reward_accumulator(config, current_time)?;
+    accrue_bonus_rewards(farm, config, current_time)?;
+    // Once per farm per second, so nested settlements don't repeat it
+    let heartbeat = current_time > farm.last_update_time;
+    let pending_matured = farm.pending_cows > 0 && current_time >= farm.pending_cows_ready_at;
+    let paused_clock = paused_clock_at(config, current_time);
+
+    if current_time > farm.last_update_time {
+        let time_elapsed = (current_time - farm.last_update_time) as u64;
+        let pending_elapsed = if pending_matured {
+            (current_time - farm.pending_cows_ready_at.max(farm.last_update_time)) as u64
+        } else {
+            0
+        };
+
+        let cow_seconds = farm.cows
+            .checked_mul(time_elapsed)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_add(
+                farm.pending_cows
+                    .checked_mul(pending_elapsed)
+                    .ok_or(ErrorCode::MathOverflow)?
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Production during accrual pauses is set aside for catch-up
+        let paused = paused_clock.saturating_sub(farm.paused_clock).min(time_elapsed);
+        let paused_cow_seconds = ((cow_seconds as u128) * (paused as u128) / (time_elapsed as u128)) as u64;
+        farm.paused_cow_seconds = farm.paused_cow_seconds.saturating_add(paused_cow_seconds);
+        let cow_seconds = cow_seconds - paused_cow_seconds;
+
+        let herd = farm.cows.saturating_add(if pending_matured { farm.pending_cows } else { 0 });
+        let cow_seconds = whale_adjusted_cow_seconds(config, herd, cow_seconds)?;
+
+        if cow_seconds > 0 {
+            // Per-cow accrual over the interval, spread over the farm's
+            // cow-seconds so partial herds (quarantine, pauses, taper) earn pro rata
+            let accrued_per_cow = config.acc_reward_per_cow.saturating_sub(farm.reward_debt);
+            let mut new_rewards = rounding::div(
+                (cow_seconds as u128)
+                    .checked_mul(accrued_per_cow)
+                    .ok_or(ErrorCode::MathOverflow)?,
+                (time_elapsed as u128) * ACC_REWARD_PRECISION,
+                USER_CREDIT,
+            )?;
+
+            let genetics = farm_genetics_bps(farm);
+            if genetics != 0 {
+                new_rewards = apply_signed_bps(new_rewards, genetics)?;
+            }
+
+            let rarity = farm_rarity_bps(farm, config);
+            if rarity != 0 {
+                new_rewards = apply_signed_bps(new_rewards, rarity)?;
+                msg!("Cow rarity bonus applied: +{} bps", rarity);
+            }
+
+            let efficiency = average_efficiency_bps(farm, config, current_time);
+            if efficiency < BPS_DENOMINATOR {
+                new_rewards = apply_signed_bps(new_rewards, efficiency as i64 - BPS_DENOMINATOR as i64)?;
+                msg!("Idle farm milking efficiency: {} bps", efficiency);
+            }
+
+            let feed = average_feed_bps(farm, config, current_time);
+            if feed < BPS_DENOMINATOR {
+                new_rewards = apply_signed_bps(new_rewards, feed as i64 - BPS_DENOMINATOR as i64)?;
+                msg!("Hungry herd yield: {} bps", feed);
+            }
+
+            if has_compound_streak(farm, config, current_time) {
+                new_rewards = apply_bonus_bps(new_rewards, config.streak_bonus_bps)?;
+                msg!("Compound streak bonus applied: +{} bps", config.streak_bonus_bps);
+            }
+
+            let community_bonus = community_boost(farm, current_time, time_elapsed, new_rewards)?;
+            if community_bonus > 0 {
+                new_rewards = new_rewards
+                    .checked_add(community_bonus)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                msg!("Community goal boost applied: +{} bps", farm.community_boost_bps);
+            }
+
+            let premium = premium_bonus(farm, config, current_time, time_elapsed, new_rewards)?;
+            if premium > 0 {
+                new_rewards = new_rewards
+                    .checked_add(premium)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                msg!("Premium Dairy bonus applied: +{} bps", config.premium_yield_bonus_bps);
+            }
+
+            let lock_bonus = lock_boost(farm, current_time, time_elapsed, new_rewards)?;
+            if lock_bonus > 0 {
+                new_rewards = new_rewards
+                    .checked_add(lock_bonus)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                msg!("Reward lock boost applied: +{} bps", farm.lock_boost_bps);
+            }
+
+            if new_rewards > 0 {
+                let manager_cut = rental_cut(farm, current_time, time_elapsed, new_rewards)?;
+                if manager_cut > 0 {
+                    farm.rental_owed = farm.rental_owed
+                        .checked_add(manager_cut)
+                        .ok_or(ErrorCode::MathOverflow)?;
+                }
+
+                let owner_rewards = new_rewards - manager_cut;
+                let claim_cut = yield_claim_cut(farm, current_time, time_elapsed, owner_rewards)?;
+                if claim_cut > 0 {
+                    farm.yield_claim_owed = farm.yield_claim_owed
+                        .checked_add(claim_cut)
+                        .ok_or(ErrorCode::MathOverflow)?;
+                }
+
+                farm.accumulated_rewards = farm.accumulated_rewards
+                    .checked_add(owner_rewards - claim_cut)
+                    .ok_or(ErrorCode::MathOverflow)?;
+
+                let accrued_from = farm.last_update_time;
+                record_epoch_production(farm, config, accrued_from, current_time, new_rewards)?;
+                
+                msg!("Updated rewards: +{} (rate: {} MILK/cow/day, time: {}s, manager share: {}), Total: {}", 
+                     new_rewards, config.reward_rate / 1_000_000, time_elapsed, manager_cut, farm.accumulated_rewards);
+            }
+        }
+    }
+
+    if pending_matured {
+        farm.cows = farm.cows
+            .checked_add(farm.pending_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.global_cows_count = config.global_cows_count
+            .checked_add(farm.pending_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.farming_cows_count = config.farming_cows_count
+            .checked_add(farm.pending_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        msg!("Released {} cows from import quarantine. User total: {}, Global total: {}", 
+             farm.pending_cows, farm.cows, config.global_cows_count);
+        sync_reward_accumulator(config, current_time)?;
+
+        farm.pending_cows = 0;
+        farm.pending_cows_ready_at = 0;
+    }
+    
+    farm.last_update_time = current_time;
+    farm.reward_debt = config.acc_reward_per_cow;
+    farm.paused_clock = paused_clock;
+    renew_premium(farm, config, current_time)?;
+
+    if heartbeat {
+        emit_heartbeat(farm, current_time);
+    }
+    Ok(())
+}
+
+/// Emit a compact liveness record of a farm for dashboards
+pub(crate) fn emit_heartbeat(farm: &Account<FarmAccount>, current_time: i64) {
+    emit!(FarmHeartbeat {
+        farm: farm.key(),
+        tag: farm.tag,
+        cows: farm.cows,
+        rewards_bucket: rewards_bucket(farm.accumulated_rewards),
+        timestamp: current_time,
+    });
+}
+
+/// Order of magnitude of a MILK amount: the bit length of its whole-MILK
+/// value, so 0 is under 1 MILK, 1 is 1 MILK, 2 is 2-3 MILK, and so on
+pub(crate) fn rewards_bucket(amount: u64) -> u8 {
+    (u64::BITS - (amount / 1_000_000).leading_zeros()) as u8
+}
+
+/// Accrue the current campaign's secondary token for the part of
+/// [last_update_time, current_time) that falls inside the campaign, for farms
+/// opted in to boost events. Bonus left unclaimed from an earlier campaign is
+/// dropped, since it was denominated in that campaign's token.
+fn accrue_bonus_rewards(farm: &mut FarmAccount, config: &Config, current_time: i64) -> Result<()> {
+    if farm.bonus_campaign_id != config.bonus_campaign_id {
+        if farm.bonus_rewards > 0 {
+            msg!("Dropped {} unclaimed bonus from campaign {}", farm.bonus_rewards, farm.bonus_campaign_id);
+        }
+        farm.bonus_campaign_id = config.bonus_campaign_id;
+        farm.bonus_rewards = 0;
+    }
+
+    if farm.preferences & FARM_PREF_BOOST_EVENTS == 0 {
+        return Ok(());
+    }
+
+    let from = farm.last_update_time.max(config.bonus_starts_at);
+    let to = current_time.min(config.bonus_ends_at);
+    if config.bonus_rate_per_day == 0 || to <= from {
+        return Ok(());
+    }
+
+    let bonus = (farm.cows as u128)
+        .checked_mul(config.bonus_rate_per_day as u128)
+        .and_then(|v| v.checked_mul((to - from) as u128))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let bonus = rounding::div(bonus, SECONDS_PER_DAY as u128, USER_CREDIT).unwrap_or(u64::MAX);
+    farm.bonus_rewards = farm.bonus_rewards.saturating_add(bonus);
+    Ok(())
+}
+
+/// Pause reward accrual when the pool drops below the solvency threshold
+/// and resume it once the pool is back above
+pub(crate) fn sync_accrual_pause(config: &mut Config, current_time: i64, pool_balance: u64) {
+    let insolvent = config.solvency_threshold > 0 && pool_balance < config.solvency_threshold;
+    let paused = config.accrual_paused_since > 0;
+    if insolvent == paused {
+        return;
+    }
+
+    if insolvent {
+        config.accrual_paused_since = current_time;
+    } else {
+        config.accrual_paused_seconds = config.accrual_paused_seconds
+            .saturating_add((current_time - config.accrual_paused_since).max(0) as u64);
+        config.accrual_paused_since = 0;
+    }
+
+    emit!(AccrualPauseChanged {
+        paused: insolvent,
+        pool_balance,
+        timestamp: current_time,
+    });
+    msg!("Reward accrual {} at pool balance {}", if insolvent { "paused" } else { "resumed" }, pool_balance);
+}
+
+/// Total seconds accrual has spent paused up to `current_time`
+pub(crate) fn paused_clock_at(config: &Config, current_time: i64) -> u64 {
+    let ongoing = if config.accrual_paused_since > 0 {
+        (current_time - config.accrual_paused_since).max(0) as u64
+    } else {
+        0
+    };
+    config.accrual_paused_seconds.saturating_add(ongoing)
+}
+
+/// Settle a farm and convert accumulated rewards into new cows at the current price
+pub(crate) fn compound_farm(
+    farm: &mut Account<FarmAccount>,
+    config: &mut Config,
+    current_time: i64,
+    num_cows: u64
+) -> Result<()> {
+    require!(!config.paused, ErrorCode::ProgramPaused);
+    require_phase_allows(config, PHASE_BLOCK_COMPOUND)?;
+    update_farm_rewards(farm, config, current_time)?;
+
+    let today = day_index(config, current_time);
+    farm.compound_day_mask = compound_mask_at(farm, today) | 1;
+    farm.last_compound_day = today;
+
+    let total_cost = calculate_cow_cost(config, config.global_cows_count, num_cows, current_time)?;
+    let cow_price = total_cost / num_cows.max(1);
+
+    require!(
+        farm.accumulated_rewards >= total_cost,
+        ErrorCode::InsufficientRewards
+    );
+
+    msg!("Compounding {} cows using {} rewards (global count: {})", 
+         num_cows, total_cost, config.global_cows_count);
+
+    farm.accumulated_rewards = farm.accumulated_rewards
+        .checked_sub(total_cost)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    farm.cost_basis = farm.cost_basis
+        .checked_add(total_cost)
+        .ok_or(ErrorCode::MathOverflow)?;
+    farm.total_milk_spent = farm.total_milk_spent.saturating_add(total_cost);
+
+    config.global_cows_count = config.global_cows_count
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.farming_cows_count = config.farming_cows_count
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    farm.cows = farm.cows
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    award_forgiveness_passes(farm, config, num_cows);
+
+    let new_reward_rate = sync_reward_accumulator(config, current_time)?;
+
+    emit!(CompoundEvent {
+        farm: farm.key(),
+        owner: farm.owner,
+        tag: farm.tag,
+        num_cows,
+        price_per_cow: cow_price,
+        total_cost,
+        global_cows: config.global_cows_count,
+        reward_rate: new_reward_rate,
+        timestamp: current_time,
+    });
+
+    msg!("Successfully compounded {} cows. User total: {}. Global total: {}. New rate: {} MILK/cow/day", 
+         num_cows, farm.cows, config.global_cows_count, new_reward_rate / 1_000_000);
+    Ok(())
+}
+
+/// Credit forgiveness passes for every compound milestone crossed, up to the
+/// configured number a farm may hold
+fn award_forgiveness_passes(farm: &mut FarmAccount, config: &Config, num_cows: u64) {
+    let previous = farm.lifetime_compounded_cows;
+    farm.lifetime_compounded_cows = previous.saturating_add(num_cows);
+
+    let milestone = config.forgiveness_milestone_cows;
+    if milestone == 0 {
+        return;
+    }
+
+    let earned = farm.lifetime_compounded_cows / milestone - previous / milestone;
+    if earned > 0 {
+        let held = (farm.forgiveness_passes as u64 + earned).min(config.max_forgiveness_passes as u64);
+        farm.forgiveness_passes = held as u8;
+        msg!("Compound milestone reached: {} forgiveness passes held", farm.forgiveness_passes);
+    }
+}
+
+/// Spread production accrued over [from, to) across the daily epochs it was
+/// earned in, pro rata by time. Epochs older than the farm's history window
+/// are dropped rather than folded into newer ones.
+pub(crate) fn record_epoch_production(
+    farm: &mut FarmAccount,
+    config: &Config,
+    from: i64,
+    to: i64,
+    amount: u64
+) -> Result<()> {
+    if to <= from || amount == 0 {
+        return Ok(());
+    }
+
+    let total_seconds = (to - from) as u128;
+    let first_epoch = day_index(config, from);
+    let last_epoch = day_index(config, to - 1);
+    let first_kept = first_epoch.max(last_epoch - (EPOCH_HISTORY_LEN as i64 - 1));
+
+    let share_of = |start: i64, end: i64| -> u64 {
+        ((amount as u128) * ((end - start) as u128) / total_seconds) as u64
+    };
+
+    let epoch_start = |epoch: i64| config.start_time + epoch * SECONDS_PER_DAY;
+    let mut allocated = if first_kept > first_epoch {
+        share_of(from, epoch_start(first_kept))
+    } else {
+        0
+    };
+
+    for epoch in first_kept..=last_epoch {
+        let share = if epoch == last_epoch {
+            amount.saturating_sub(allocated)
+        } else {
+            share_of(from.max(epoch_start(epoch)), to.min(epoch_start(epoch + 1)))
+        };
+        allocated = allocated.saturating_add(share);
+
+        let entry = &mut farm.epoch_production[epoch as usize % EPOCH_HISTORY_LEN];
+        if entry.epoch != epoch as u32 {
+            *entry = EpochProduction { epoch: epoch as u32, produced: 0 };
+        }
+        entry.produced = entry.produced
+            .checked_add(share)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(())
+}
+
+/// Portion of rewards accrued since the last update that belongs to the farm's
+/// rental manager, pro rata for the part of the interval the rental was live
+pub(crate) fn rental_cut(farm: &FarmAccount, current_time: i64, time_elapsed: u64, new_rewards: u64) -> Result<u64> {
+    if farm.rental_share_bps == 0 || farm.last_update_time >= farm.rental_expires_at {
+        return Ok(0);
+    }
+
+    let rented_seconds = (current_time.min(farm.rental_expires_at) - farm.last_update_time) as u128;
+    let cut = (new_rewards as u128)
+        .checked_mul(farm.rental_share_bps as u128)
+        .and_then(|v| v.checked_mul(rented_seconds))
+        .ok_or(ErrorCode::MathOverflow)?;
+    rounding::div(cut, BPS_DENOMINATOR as u128 * time_elapsed as u128, USER_CREDIT)
+}
+
+/// Extra rewards from an unlocked community goal boost, pro rata for the
+/// part of the interval the boost was live
+pub(crate) fn community_boost(farm: &FarmAccount, current_time: i64, time_elapsed: u64, new_rewards: u64) -> Result<u64> {
+    timed_bonus(farm, farm.community_boost_bps, farm.community_boost_until, current_time, time_elapsed, new_rewards)
+}
+
+/// Extra rewards from Premium Dairy, pro rata for the part of the interval
+/// the subscription was paid for
+pub(crate) fn premium_bonus(farm: &FarmAccount, config: &Config, current_time: i64, time_elapsed: u64, new_rewards: u64) -> Result<u64> {
+    timed_bonus(farm, config.premium_yield_bonus_bps, farm.premium_until, current_time, time_elapsed, new_rewards)
+}
+
+/// Extra rewards from a reward lock, pro rata for the part of the interval
+/// the lock was running
+pub(crate) fn lock_boost(farm: &FarmAccount, current_time: i64, time_elapsed: u64, new_rewards: u64) -> Result<u64> {
+    timed_bonus(farm, farm.lock_boost_bps, farm.rewards_locked_until, current_time, time_elapsed, new_rewards)
+}
+
+/// `bonus_bps` of the interval's rewards for the seconds before `until`
+fn timed_bonus(farm: &FarmAccount, bonus_bps: u16, until: i64, current_time: i64, time_elapsed: u64, new_rewards: u64) -> Result<u64> {
+    if bonus_bps == 0 || farm.last_update_time >= until {
+        return Ok(0);
+    }
+
+    let boosted_seconds = (current_time.min(until) - farm.last_update_time) as u128;
+    let bonus = (new_rewards as u128)
+        .checked_mul(bonus_bps as u128)
+        .and_then(|v| v.checked_mul(boosted_seconds))
+        .ok_or(ErrorCode::MathOverflow)?;
+    rounding::div(bonus, BPS_DENOMINATOR as u128 * time_elapsed as u128, USER_CREDIT)
+}
+
+/// Whether the farm has a paid-up Premium Dairy subscription
+pub(crate) fn is_premium(farm: &FarmAccount, current_time: i64) -> bool {
+    farm.premium_until > current_time
+}
+
+/// Charge one Premium Dairy period to the farm's rewards and extend the
+/// subscription, from now if it had lapsed. The fee stays in the pool.
+pub(crate) fn charge_premium(farm: &mut FarmAccount, config: &mut Config, current_time: i64) -> Result<()> {
+    require!(config.premium_fee > 0, ErrorCode::PremiumDisabled);
+    require!(farm.accumulated_rewards >= config.premium_fee, ErrorCode::InsufficientRewards);
+
+    farm.accumulated_rewards -= config.premium_fee;
+    farm.premium_until = farm.premium_until
+        .max(current_time)
+        .checked_add(config.premium_period)
+        .ok_or(ErrorCode::MathOverflow)?;
+    config.total_premium_fees = config.total_premium_fees.saturating_add(config.premium_fee);
+    route_fee(config, FeeHookKind::Treasury, config.premium_fee)?;
+    Ok(())
+}
+
+/// Renew a lapsed subscription on the farm's next interaction if the owner
+/// opted in and the rewards cover the fee
+fn renew_premium(farm: &mut FarmAccount, config: &mut Config, current_time: i64) -> Result<()> {
+    if farm.preferences & FARM_PREF_PREMIUM_RENEW == 0
+        || farm.premium_until == 0
+        || is_premium(farm, current_time)
+        || config.premium_fee == 0
+    {
+        return Ok(());
+    }
+
+    if farm.accumulated_rewards < config.premium_fee {
+        msg!("Premium Dairy lapsed: {} MILK needed to renew", config.premium_fee / 1_000_000);
+        return Ok(());
+    }
+    charge_premium(farm, config, current_time)?;
+    msg!("Premium Dairy renewed until {}", farm.premium_until);
+    Ok(())
+}
+
+/// Count MILK a farm spent on cows towards the running community goal
+pub(crate) fn record_goal_contribution(goal: &mut CommunityGoal, farm: &mut FarmAccount, amount: u64, current_time: i64) {
+    if amount == 0 || current_time < goal.starts_at || current_time >= goal.ends_at {
+        return;
+    }
+
+    if farm.community_goal_id != goal.id {
+        farm.community_goal_id = goal.id;
+        farm.community_goal_contribution = 0;
+    }
+    farm.community_goal_contribution = farm.community_goal_contribution.saturating_add(amount);
+    goal.progress = goal.progress.saturating_add(amount);
+
+    if goal.reached_at == 0 && goal.progress >= goal.target {
+        goal.reached_at = current_time;
+        msg!("Community goal {} reached: {} MILK. +{} bps boost for {} seconds unlocked",
+             goal.id, goal.progress / 1_000_000, goal.boost_bps, goal.boost_duration);
+    }
+}
+
+/// Portion of the owner's newly accrued rewards that belongs to the farm's
+/// yield claim holders, pro rata for the part of the interval before the
+/// claim expires
+pub(crate) fn yield_claim_cut(farm: &FarmAccount, current_time: i64, time_elapsed: u64, owner_rewards: u64) -> Result<u64> {
+    if farm.last_update_time >= farm.yield_claim_until {
+        return Ok(0);
+    }
+
+    let claimed_seconds = (current_time.min(farm.yield_claim_until) - farm.last_update_time) as u64;
+    rounding::mul_div(owner_rewards, claimed_seconds, time_elapsed, USER_CREDIT)
+}
+
+/// Move the holders' accrued share of a tokenized farm into its yield claim
+pub(crate) fn settle_yield_owed(farm: &mut FarmAccount, yield_claim: &mut YieldClaim) -> Result<u64> {
+    let owed = farm.yield_claim_owed;
+    if owed > 0 {
+        yield_claim.settled_yield = yield_claim.settled_yield
+            .checked_add(owed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        yield_claim.total_yield = yield_claim.total_yield.saturating_add(owed);
+        farm.yield_claim_owed = 0;
+    }
+    Ok(owed)
+}
+
+/// Move the manager's accrued share of a rented farm into the manager's farm
+pub(crate) fn settle_rental(farm: &mut FarmAccount, manager_farm: &mut FarmAccount) -> Result<u64> {
+    let owed = farm.rental_owed;
+    if owed > 0 {
+        manager_farm.accumulated_rewards = manager_farm.accumulated_rewards
+            .checked_add(owed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        farm.rental_owed = 0;
+    }
+    Ok(owed)
+}
+
+/// Average milking efficiency (bps) over [last_update_time, current_time].
+/// Efficiency is full until `efficiency_grace_seconds` after the owner's last
+/// interaction, then falls linearly by `efficiency_decay_bps_per_day` until it
+/// reaches `efficiency_floor_bps`. The piecewise-linear curve is integrated
+/// exactly so settling often or rarely yields the same rewards.
+pub(crate) fn average_efficiency_bps(farm: &FarmAccount, config: &Config, current_time: i64) -> u64 {
+    let from = farm.last_update_time;
+    if config.efficiency_decay_bps_per_day == 0 || farm.last_interaction_time == 0 || current_time <= from {
+        return BPS_DENOMINATOR;
+    }
+
+    let full = BPS_DENOMINATOR as i128;
+    let floor = config.efficiency_floor_bps as i128;
+    let decay = config.efficiency_decay_bps_per_day as i128;
+    let day = SECONDS_PER_DAY as i128;
+
+    let (a, b) = (from as i128, current_time as i128);
+    let decay_start = farm.last_interaction_time as i128 + config.efficiency_grace_seconds as i128;
+    let decay_end = decay_start + (full - floor) * day / decay;
+    let efficiency_at = |t: i128| (full - (t - decay_start).max(0) * decay / day).max(floor);
+
+    let mut integral = 0i128;
+    // Full efficiency before decay starts
+    integral += (b.min(decay_start) - a).max(0) * full;
+    // Linear decay: average of the endpoints
+    let (la, lb) = (a.max(decay_start), b.min(decay_end));
+    if lb > la {
+        integral += (lb - la) * (efficiency_at(la) + efficiency_at(lb)) / 2;
+    }
+    // Flat at the floor once fully decayed
+    integral += (b - a.max(decay_end)).max(0) * floor;
+
+    (integral / (b - a)) as u64
+}
+
+/// Average feed level in bps over the interval since the farm's last
+/// settlement. A farm produces in full for `feed_interval` after it is fed;
+/// every period missed after that costs `feed_decay_bps` more, down to
+/// nothing. The steps are integrated exactly, like milking efficiency.
+pub(crate) fn average_feed_bps(farm: &FarmAccount, config: &Config, current_time: i64) -> u64 {
+    let from = farm.last_update_time;
+    if config.feed_interval <= 0 || config.feed_decay_bps == 0 || current_time <= from {
+        return BPS_DENOMINATOR;
+    }
+
+    let full = BPS_DENOMINATOR as i128;
+    let decay = config.feed_decay_bps as i128;
+    let interval = config.feed_interval as i128;
+    let (a, b) = (from as i128, current_time as i128);
+    let hungry_from = farm.last_fed_at.max(config.feed_started_at) as i128 + interval;
+
+    let mut integral = (b.min(hungry_from) - a).max(0) * full;
+    // Missed period k covers [hungry_from + (k - 1) * interval, hungry_from + k * interval)
+    let first = ((a - hungry_from).max(0) / interval) + 1;
+    let last = if b > hungry_from { (b - hungry_from - 1) / interval + 1 } else { 0 };
+    let starved = (full + decay - 1) / decay;
+    for k in first..=last.min(starved) {
+        let start = hungry_from + (k - 1) * interval;
+        let overlap = (b.min(start + interval) - a.max(start)).max(0);
+        integral += overlap * (full - k * decay).max(0);
+    }
+
+    (integral / (b - a)) as u64
+}
+
+/// Derive a pseudo-random genetics byte for a purchase from the most recent
+/// slot hash, the buyer and the global herd size. Not suitable for anything of
+/// value beyond a small yield nudge: a validator can bias it.
+pub(crate) fn roll_genetics(slot_hashes: &AccountInfo, buyer: &Pubkey, global_cows: u64) -> Result<u8> {
+    let data = slot_hashes.try_borrow_data()?;
+    // Layout: u64 entry count followed by (slot: u64, hash: [u8; 32]) entries, newest first
+    require!(data.len() >= 8 + 8 + 32, ErrorCode::InvalidSlotHashes);
+    let recent_hash = &data[16..48];
+
+    let digest = hashv(&[recent_hash, buyer.as_ref(), &global_cows.to_le_bytes()]);
+    Ok(digest.to_bytes()[0])
+}
+
+/// Map a genetics byte onto [-MAX_GENETICS_BPS, +MAX_GENETICS_BPS]
+pub(crate) fn genetics_bps(genetics: u8) -> i64 {
+    (genetics as i64) * (2 * MAX_GENETICS_BPS) / (u8::MAX as i64) - MAX_GENETICS_BPS
+}
+
+/// Average genetics modifier across the farm's herd. Cows that didn't come
+/// from a purchase (compounds, imports) are neutral and dilute the average.
+pub(crate) fn farm_genetics_bps(farm: &FarmAccount) -> i64 {
+    if farm.cows == 0 {
+        return 0;
+    }
+    (farm.genetics_bps_sum / farm.cows as i64).clamp(-MAX_GENETICS_BPS, MAX_GENETICS_BPS)
+}
+
+/// Rewards a farm's herd would earn over `horizon` seconds at `reward_rate`,
+/// with its genetics applied
+pub(crate) fn project_farm_rewards(farm: &FarmAccount, reward_rate: u64, horizon: i64) -> Result<u64> {
+    let projected = (farm.cows as u128)
+        .checked_mul(reward_rate as u128)
+        .and_then(|v| v.checked_mul(horizon.max(0) as u128))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let projected = rounding::div(projected, SECONDS_PER_DAY as u128, USER_CREDIT)?;
+    apply_signed_bps(projected, farm_genetics_bps(farm))
+}
+
+/// Whole days elapsed since the game started
+pub(crate) fn day_index(config: &Config, current_time: i64) -> i64 {
+    (current_time - config.start_time).max(0) / SECONDS_PER_DAY
+}
+
+/// Cows bought over roughly the last day: the current day's bucket plus the
+/// unexpired share of the previous one
+pub(crate) fn recent_buy_volume(config: &Config, current_time: i64) -> Result<u64> {
+    let day = day_index(config, current_time);
+    let (current, previous) = match day - config.buy_volume_day {
+        0 => (config.buy_volume_current, config.buy_volume_previous),
+        1 => (0, config.buy_volume_current),
+        _ => (0, 0),
+    };
+
+    let elapsed_today = (current_time - config.start_time).max(0) % SECONDS_PER_DAY;
+    let previous_share = rounding::mul_div(previous, (SECONDS_PER_DAY - elapsed_today) as u64, SECONDS_PER_DAY as u64, USER_CREDIT)?;
+    Ok(current.saturating_add(previous_share))
+}
+
+pub(crate) fn record_buy_volume(config: &mut Config, current_time: i64, num_cows: u64) {
+    let day = day_index(config, current_time);
+    match day - config.buy_volume_day {
+        0 => {}
+        1 => {
+            config.buy_volume_previous = config.buy_volume_current;
+            config.buy_volume_current = 0;
+        }
+        _ => {
+            config.buy_volume_previous = 0;
+            config.buy_volume_current = 0;
+        }
+    }
+    config.buy_volume_day = day;
+    config.buy_volume_current = config.buy_volume_current.saturating_add(num_cows);
+}
+
+/// Per-transaction buy cap. Grows with the square root of pool depth past
+/// the initial TVL, and with recent buy volume, up to a fixed ceiling.
+pub(crate) fn max_cows_per_transaction(config: &Config, tvl: u64, current_time: i64) -> Result<u64> {
+    // MAX_COWS_PER_TRANSACTION * sqrt(tvl / INITIAL_TVL), in integers
+    let depth_cap = ((MAX_COWS_PER_TRANSACTION as u128).pow(2) * tvl as u128 / INITIAL_TVL as u128).isqrt() as u64;
+    let volume_cap = rounding::mul_div(recent_buy_volume(config, current_time)?, 1, BUY_VOLUME_CAP_DIVISOR, USER_CREDIT)?;
+
+    Ok(depth_cap
+        .max(volume_cap)
+        .clamp(MAX_COWS_PER_TRANSACTION, MAX_COWS_PER_TRANSACTION * MAX_BUY_CAP_MULTIPLIER))
+}
+
+/// Enforce the per-transaction buy cap and the farm's purchase cooldown,
+/// then stamp the farm's last buy time
+pub(crate) fn check_buy_limits(farm: &mut FarmAccount, config: &Config, num_cows: u64, current_time: i64) -> Result<()> {
+    require_phase_allows(config, PHASE_BLOCK_BUY)?;
+    let mut max_cows = max_cows_per_transaction(config, config.tvl, current_time)?;
+    if is_premium(farm, current_time) {
+        max_cows = apply_bonus_bps(max_cows, config.premium_buy_cap_bps)?;
+    }
+    let max_cows = phase_buy_cap(config, max_cows);
+    require!(num_cows <= max_cows, ErrorCode::ExceedsMaxCowsPerTransaction);
+
+    if config.buy_cooldown_seconds > 0 && farm.last_buy_time != 0 {
+        require!(
+            current_time - farm.last_buy_time >= config.buy_cooldown_seconds,
+            ErrorCode::BuyCooldownActive
+        );
+    }
+    farm.last_buy_time = current_time;
+    Ok(())
+}
+
+/// Book `num_cows` paid for with `total_cost` MILK into a settled farm: the
+/// herd, global counts, buy volume, genetics and cost basis
+pub(crate) fn add_bought_cows(
+    farm: &mut FarmAccount,
+    config: &mut Config,
+    num_cows: u64,
+    total_cost: u64,
+    genetics: u8,
+    current_time: i64,
+) -> Result<()> {
+    check_barn_capacity(farm, config, num_cows)?;
+
+    config.global_cows_count = config.global_cows_count
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.farming_cows_count = config.farming_cows_count
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    record_buy_volume(config, current_time, num_cows);
+
+    farm.cows = farm.cows
+        .checked_add(num_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    farm.genetics_bps_sum = farm.genetics_bps_sum
+        .checked_add(genetics_bps(genetics) * num_cows as i64)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    farm.cost_basis = farm.cost_basis
+        .checked_add(total_cost)
+        .ok_or(ErrorCode::MathOverflow)?;
+    farm.total_milk_spent = farm.total_milk_spent.saturating_add(total_cost);
+    Ok(())
+}
+
+/// Producing cows a herd counts as once the whale taper is applied. The
+/// first `whale_threshold_cows` earn in full; each further tier of the same
+/// size earns `whale_marginal_bps` of the tier before it.
+pub(crate) fn whale_effective_cows(config: &Config, herd: u64) -> Result<u64> {
+    let threshold = config.whale_threshold_cows;
+    if threshold == 0 || herd <= threshold {
+        return Ok(herd);
+    }
+
+    let mut effective = threshold as u128 * BPS_DENOMINATOR as u128;
+    let mut remaining = herd - threshold;
+    let mut rate = BPS_DENOMINATOR as u128;
+    let mut tier = 0;
+    while remaining > 0 {
+        tier += 1;
+        rate = rounding::div_wide(rate * config.whale_marginal_bps as u128, BPS_DENOMINATOR as u128, USER_CREDIT)?;
+        let tier_cows = if tier == MAX_WHALE_TIERS { remaining } else { remaining.min(threshold) };
+        effective += tier_cows as u128 * rate;
+        remaining -= tier_cows;
+    }
+
+    rounding::div(effective, BPS_DENOMINATOR as u128, USER_CREDIT)
+}
+
+/// Scale a farm's cow-seconds by its whale taper
+pub(crate) fn whale_adjusted_cow_seconds(config: &Config, herd: u64, cow_seconds: u64) -> Result<u64> {
+    let effective = whale_effective_cows(config, herd)?;
+    if effective == herd {
+        return Ok(cow_seconds);
+    }
+
+    msg!("Whale taper: {} cows produce as {}", herd, effective);
+    rounding::mul_div(cow_seconds, effective, herd, USER_CREDIT)
+}
+
+/// Farm compound history shifted so that bit 0 is `day`
+pub(crate) fn compound_mask_at(farm: &FarmAccount, day: i64) -> u32 {
+    let shift = (day - farm.last_compound_day).max(0);
+    if shift >= 32 {
+        0
+    } else {
+        farm.compound_day_mask << shift
+    }
+}
+
+/// A farm is on a streak if it compounded in each of the last K days, counting
+/// either today or yesterday as the most recent day so the bonus doesn't lapse
+/// before the owner has had a chance to compound today
+pub(crate) fn has_compound_streak(farm: &FarmAccount, config: &Config, current_time: i64) -> bool {
+    let days = config.streak_days_required;
+    if days == 0 || config.streak_bonus_bps == 0 {
+        return false;
+    }
+
+    let required = (1u32 << days) - 1;
+    let mask = compound_mask_at(farm, day_index(config, current_time));
+    mask & required == required || (mask >> 1) & required == required
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{calculate_cow_price, calculate_reward_rate};
+
+    const T0: i64 = 1_700_000_000;
+
+    fn config(global_cows: u64, tvl: u64) -> Config {
+        let zeroed = vec![0u8; Config::INIT_SPACE];
+        let mut config = Config::deserialize(&mut &zeroed[..]).unwrap();
+        config.global_cows_count = global_cows;
+        config.tvl = tvl;
+        sync_reward_accumulator(&mut config, T0).unwrap();
+        config
+    }
+
+    fn earned_per_cow(config: &Config, reward_debt: u128) -> u128 {
+        (config.acc_reward_per_cow - reward_debt) / ACC_REWARD_PRECISION
+    }
+
+    #[test]
+    fn untouched_farm_earns_the_live_rate_after_pool_changes() {
+        let mut config = config(1_000, 50_000_000_000_000);
+        let idle_debt = config.acc_reward_per_cow;
+        let rich_rate = config.reward_rate;
+
+        // A withdrawal drains the pool a day later; only the withdrawing
+        // farm and the pool are touched
+        config.tvl = 5_000_000_000_000;
+        sync_reward_accumulator(&mut config, T0 + SECONDS_PER_DAY).unwrap();
+        let drained_rate = config.reward_rate;
+        assert_ne!(drained_rate, rich_rate);
+        assert_eq!(drained_rate, calculate_reward_rate(&config, 1_000, config.tvl, T0).unwrap());
+
+        let settled_debt = config.acc_reward_per_cow;
+        sync_reward_accumulator(&mut config, T0 + 2 * SECONDS_PER_DAY).unwrap();
+
+        // The idle farm got the old rate for day one and the new rate for
+        // day two, the same as a farm that settled in between
+        assert_eq!(earned_per_cow(&config, settled_debt), drained_rate as u128);
+        assert_eq!(
+            earned_per_cow(&config, idle_debt),
+            rich_rate as u128 + drained_rate as u128
+        );
+    }
+
+    #[test]
+    fn rate_tracks_global_state_at_every_sync() {
+        let mut config = config(100, 1_000_000_000_000);
+        for (day, (cows, tvl)) in [(5_000u64, 1_000_000_000_000u64), (5_000, 0), (20, 900_000_000_000_000)]
+            .into_iter()
+            .enumerate()
+        {
+            config.global_cows_count = cows;
+            config.tvl = tvl;
+            let rate = sync_reward_accumulator(&mut config, T0 + (day as i64 + 1) * SECONDS_PER_DAY).unwrap();
+            assert_eq!(rate, calculate_reward_rate(&config, cows, tvl, T0).unwrap());
+            assert_eq!(config.reward_rate, rate);
+        }
+    }
+
+    fn empty_farm() -> FarmAccount {
+        let zeroed = vec![0u8; FarmAccount::INIT_SPACE];
+        FarmAccount::deserialize(&mut &zeroed[..]).unwrap()
+    }
+
+    #[test]
+    fn open_farm_adopts_only_the_first_owner() {
+        let mut config = config(100, 1_000_000_000_000);
+        let mut farm = empty_farm();
+        let owner = Pubkey::new_unique();
+
+        assert!(open_farm(&mut farm, owner, &mut config, T0).unwrap());
+        assert_eq!(farm.owner, owner);
+        assert_eq!(farm.last_update_time, T0);
+        assert_eq!(farm.reward_debt, config.acc_reward_per_cow);
+        assert_eq!(farm.farm_created_at, T0);
+
+        farm.cows = 7;
+        assert!(!open_farm(&mut farm, owner, &mut config, T0 + 60).unwrap());
+        assert_eq!(farm.cows, 7);
+        assert_eq!(farm.farm_created_at, T0);
+    }
+
+    #[test]
+    fn open_farm_rejects_a_second_signer() {
+        let mut config = config(100, 1_000_000_000_000);
+        let mut farm = empty_farm();
+        let owner = Pubkey::new_unique();
+        open_farm(&mut farm, owner, &mut config, T0).unwrap();
+
+        let err = open_farm(&mut farm, Pubkey::new_unique(), &mut config, T0).unwrap_err();
+        assert_eq!(err, ErrorCode::FarmOwnerMismatch.into());
+        assert_eq!(farm.owner, owner);
+    }
+
+    #[test]
+    fn rarity_bonus_averages_tier_yields_over_the_herd() {
+        let mut config = config(100, 1_000_000_000_000);
+        config.cow_tiers[CowRarity::Rare as usize].reward_bps = 15_000;
+        config.cow_tiers[CowRarity::Epic as usize].reward_bps = 30_000;
+        let mut farm = empty_farm();
+        assert_eq!(farm_rarity_bps(&farm, &config), 0);
+
+        // 6 commons, 3 Rare at +50% and 1 Epic at +200%
+        farm.cows = 10;
+        farm.rare_cows = 3;
+        farm.epic_cows = 1;
+        assert_eq!(tier_cows(&farm, CowRarity::Common), 6);
+        assert_eq!(farm_rarity_bps(&farm, &config), (3 * 5_000 + 20_000) / 10);
+
+        // Unset tiers (0 bps) never reduce yield
+        config.cow_tiers[CowRarity::Epic as usize].reward_bps = 0;
+        assert_eq!(farm_rarity_bps(&farm, &config), 1_500);
+    }
+
+    #[test]
+    fn barn_caps_producing_and_quarantined_cows() {
+        let mut config = config(100, 1_000_000_000_000);
+        let mut farm = empty_farm();
+        farm.cows = 40;
+        farm.pending_cows = 10;
+        assert!(check_barn_capacity(&farm, &config, 1_000_000).is_ok());
+
+        config.barn_base_capacity = 50;
+        config.barn_capacity_per_level = 25;
+        config.barn_upgrade_cost = 1_000;
+        assert_eq!(check_barn_capacity(&farm, &config, 1).unwrap_err(), ErrorCode::BarnFull.into());
+
+        farm.barn_level = 2;
+        assert_eq!(barn_capacity(&farm, &config), 100);
+        assert!(check_barn_capacity(&farm, &config, 50).is_ok());
+        assert_eq!(barn_upgrade_cost(&farm, &config).unwrap(), 3_000);
+    }
+
+    #[test]
+    fn phase_rules_apply_only_in_their_phase() {
+        let mut config = config(100, 1_000_000_000_000);
+        config.phase_rules[GamePhase::Sunset as usize] = PhaseRules {
+            blocked_actions: PHASE_BLOCK_BUY | PHASE_BLOCK_COMPOUND,
+            buy_cap_bps: 5_000,
+            buy_fee_bps: 0,
+            penalty_waiver_bps: 10_000,
+        };
+        assert!(require_phase_allows(&config, PHASE_BLOCK_BUY).is_ok());
+        assert_eq!(phase_buy_cap(&config, 50), 50);
+
+        config.phase = GamePhase::Sunset;
+        assert_eq!(
+            require_phase_allows(&config, PHASE_BLOCK_BUY).unwrap_err(),
+            ErrorCode::BlockedInPhase.into()
+        );
+        assert!(require_phase_allows(&config, PHASE_BLOCK_SELL).is_ok());
+        assert_eq!(phase_buy_cap(&config, 50), 25);
+        assert_eq!(phase_penalty_waiver(&config, 1_000).unwrap(), 1_000);
+        assert_eq!(crate::phase::next_phase(config.phase), None);
+    }
+
+    #[test]
+    fn sync_never_moves_the_clock_backwards() {
+        let mut config = config(100, 1_000_000_000_000);
+        let acc = config.acc_reward_per_cow;
+        sync_reward_accumulator(&mut config, T0 - 60).unwrap();
+        assert_eq!(config.acc_reward_per_cow, acc);
+        assert_eq!(config.reward_rate_updated_at, T0);
+    }
+
+    #[test]
+    fn streams_vest_linearly_and_carry_over_on_top_up() {
+        let zeroed = [0u8; VestingStream::INIT_SPACE];
+        let mut stream = VestingStream::deserialize(&mut &zeroed[..]).unwrap();
+        let day = SECONDS_PER_DAY;
+
+        top_up_stream(&mut stream, 1_000, T0, 10 * day).unwrap();
+        assert_eq!(claimable_vested(&stream, T0).unwrap(), 0);
+        assert_eq!(claimable_vested(&stream, T0 + 3 * day).unwrap(), 300);
+
+        // Claim 300, then top up halfway through: 200 vested since the claim
+        // carry over and the unvested 500 restart with the new 500
+        stream.claimed += 300;
+        top_up_stream(&mut stream, 500, T0 + 5 * day, 10 * day).unwrap();
+        assert_eq!(stream.carried, 200);
+        assert_eq!(stream.amount, 1_000);
+        assert_eq!(claimable_vested(&stream, T0 + 5 * day).unwrap(), 200);
+        assert_eq!(claimable_vested(&stream, T0 + 10 * day).unwrap(), 700);
+        assert_eq!(claimable_vested(&stream, T0 + 30 * day).unwrap(), 1_200);
+    }
+
+    #[test]
+    fn allowlist_proof_accepts_listed_wallets_only() {
+        let campaign = Pubkey::new_unique();
+        let wallets: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = wallets.iter().map(|w| GiftCampaign::leaf(&campaign, w)).collect();
+        let pair = |a: [u8; 32], b: [u8; 32]| {
+            if a <= b { hashv(&[&a, &b]).to_bytes() } else { hashv(&[&b, &a]).to_bytes() }
+        };
+        let left = pair(leaves[0], leaves[1]);
+        let root = pair(left, leaves[2]);
+
+        assert!(verify_allowlist_proof(leaves[0], &[leaves[1], leaves[2]], &root));
+        assert!(verify_allowlist_proof(leaves[1], &[leaves[0], leaves[2]], &root));
+        assert!(verify_allowlist_proof(leaves[2], &[left], &root));
+
+        let outsider = GiftCampaign::leaf(&campaign, &Pubkey::new_unique());
+        assert!(!verify_allowlist_proof(outsider, &[leaves[1], leaves[2]], &root));
+        let other_campaign = GiftCampaign::leaf(&Pubkey::new_unique(), &wallets[2]);
+        assert!(!verify_allowlist_proof(other_campaign, &[left], &root));
+    }
+
+    #[test]
+    fn hungry_farms_decay_per_missed_period() {
+        let day = SECONDS_PER_DAY;
+        let mut cfg = config(0, 0);
+        cfg.feed_interval = 2 * day;
+        cfg.feed_decay_bps = 4_000;
+        let mut farm = empty_farm();
+        farm.last_update_time = T0;
+        farm.last_fed_at = T0;
+
+        // Fed, then hungry: full for 2 days, 60% for 2, 20% for 2, then nothing
+        assert_eq!(average_feed_bps(&farm, &cfg, T0 + 2 * day), 10_000);
+        assert_eq!(average_feed_bps(&farm, &cfg, T0 + 4 * day), 8_000);
+        assert_eq!(average_feed_bps(&farm, &cfg, T0 + 8 * day), 4_500);
+
+        // Settling mid-period only counts the tail of the window
+        farm.last_update_time = T0 + 3 * day;
+        assert_eq!(average_feed_bps(&farm, &cfg, T0 + 5 * day), 4_000);
+
+        // Feeding off, or farms counted as fed when it was turned on
+        cfg.feed_started_at = T0 + 3 * day;
+        assert_eq!(average_feed_bps(&farm, &cfg, T0 + 5 * day), 10_000);
+        cfg.feed_interval = 0;
+        assert_eq!(average_feed_bps(&farm, &cfg, T0 + 50 * day), 10_000);
+    }
+
+
+    #[test]
+    fn breeding_rolls_against_odds_and_barn_room() {
+        let mut cfg = config(0, 0);
+        cfg.breeding_odds = [5_000, 3_000, 1_500, 500];
+        let roll = |value: u64| {
+            let mut randomness = [0xffu8; 64];
+            randomness[..8].copy_from_slice(&value.to_le_bytes());
+            roll_breeding(&cfg, &randomness)
+        };
+        assert_eq!(roll(4_999), BreedingOutcome::OneCalf);
+        assert_eq!(roll(5_000), BreedingOutcome::TwoCalves);
+        assert_eq!(roll(19_499), BreedingOutcome::ThreeCalves);
+        assert_eq!(roll(9_999), BreedingOutcome::RareCalf);
+
+        let mut farm = empty_farm();
+        farm.cows = 8;
+        assert_eq!(breeding_calves(&farm, &cfg, BreedingOutcome::ThreeCalves), 3);
+        cfg.barn_base_capacity = 10;
+        assert_eq!(breeding_calves(&farm, &cfg, BreedingOutcome::ThreeCalves), 2);
+        farm.cows = 12;
+        assert_eq!(breeding_calves(&farm, &cfg, BreedingOutcome::TwoCalves), 1);
+    }
+
+
+    #[test]
+    fn event_multiplier_applies_inside_its_window_only() {
+        let day = SECONDS_PER_DAY;
+        let mut config = config(1_000, 1_000_000_000_000);
+        config.event_starts_at = T0 + day;
+        config.event_ends_at = T0 + 2 * day;
+        config.event_reward_bps = 20_000;
+        config.event_price_discount_bps = 500;
+
+        sync_reward_accumulator(&mut config, T0).unwrap();
+        let rate = config.reward_rate;
+        let debt = config.acc_reward_per_cow;
+
+        // One sync spanning the whole event: one plain day, one doubled, one plain
+        sync_reward_accumulator(&mut config, T0 + 3 * day).unwrap();
+        assert_eq!(config.reward_rate, rate);
+        assert_eq!((config.acc_reward_per_cow - debt) / ACC_REWARD_PRECISION, 4 * rate as u128);
+
+        assert_eq!(calculate_reward_rate(&config, 1_000, config.tvl, T0 + day).unwrap(), 2 * rate);
+        assert_eq!(calculate_reward_rate(&config, 1_000, config.tvl, T0 + 2 * day).unwrap(), rate);
+
+        let price = calculate_cow_price(&config, 1_000, T0).unwrap();
+        assert_eq!(calculate_cow_price(&config, 1_000, T0 + day).unwrap(), price - price / 20);
+    }
+
+
+    #[test]
+    fn partial_withdrawals_cannot_dodge_the_penalty() {
+        let mut config = config(1_000, 1_000_000_000_000);
+        config.penalty_exempt_below_rewards = 300_000_000;
+        let mut farm = empty_farm();
+        farm.cows = 100;
+        farm.accumulated_rewards = 1_000_000_000;
+        farm.last_withdraw_time = T0 - 3_600;
+
+        // Each chunk is under the exemption threshold, the balance isn't
+        let first = take_withdrawal(&mut farm, &mut config, None, T0, Some(200_000_000), u64::MAX).unwrap();
+        assert_eq!(first, (100_000_000, 100_000_000));
+        let second = take_withdrawal(&mut farm, &mut config, None, T0 + 60, Some(200_000_000), u64::MAX).unwrap();
+        assert_eq!(second, (100_000_000, 100_000_000));
+        assert_eq!(farm.accumulated_rewards, 600_000_000);
+
+        // Once the balance itself is small the farm is exempt
+        farm.accumulated_rewards = 250_000_000;
+        let small = take_withdrawal(&mut farm, &mut config, None, T0 + 120, None, u64::MAX).unwrap();
+        assert_eq!(small, (250_000_000, 0));
+    }
+
+    #[test]
+    fn withdrawal_tax_brackets_span_the_day() {
+        let mut config = config(1_000, 1_000_000_000_000);
+        config.withdrawal_tax_thresholds = [100_000_000, u64::MAX, u64::MAX];
+        config.withdrawal_tax_bps[0] = 1_000;
+        let mut farm = empty_farm();
+
+        assert_eq!(charge_withdrawal_tax(&mut config, &mut farm, 60_000_000, T0).unwrap(), 0);
+        // The second chunk crosses the bracket the pair reaches together
+        assert_eq!(charge_withdrawal_tax(&mut config, &mut farm, 60_000_000, T0 + 3_600).unwrap(), 2_000_000);
+        assert_eq!(charge_withdrawal_tax(&mut config, &mut farm, 10_000_000, T0 + 7_200).unwrap(), 1_000_000);
+
+        // A new window starts from zero
+        assert_eq!(charge_withdrawal_tax(&mut config, &mut farm, 60_000_000, T0 + SECONDS_PER_DAY).unwrap(), 0);
+        assert_eq!(farm.tax_window_started_at, T0 + SECONDS_PER_DAY);
+        assert_eq!(config.new_farmer_fund_pending, 3_000_000);
+    }
+
+    #[test]
+    fn launch_farm_decodes_and_joins_the_accumulator_after_growing() {
+        let owner = Pubkey::new_unique();
+        let rate = 2_000_000u64;
+
+        // The launch layout: owner, cows, last update, rewards, rate snapshot,
+        // last withdrawal
+        let mut data = FarmAccount::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&10u64.to_le_bytes());
+        data.extend_from_slice(&(T0 - SECONDS_PER_DAY).to_le_bytes());
+        data.extend_from_slice(&7_000_000u64.to_le_bytes());
+        data.extend_from_slice(&rate.to_le_bytes());
+        data.extend_from_slice(&(T0 - 3_600).to_le_bytes());
+        assert!(data.len() < FARM_ACCUMULATOR_LEN);
+        const { assert!(FARM_ACCUMULATOR_LEN <= 8 + FarmAccount::INIT_SPACE) };
+        data.resize(8 + FarmAccount::INIT_SPACE, 0);
+
+        let mut farm = FarmAccount::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!((farm.owner, farm.cows, farm.accumulated_rewards), (owner, 10, 7_000_000));
+        assert_eq!(farm.last_reward_rate, rate);
+        assert_eq!(farm.last_withdraw_time, T0 - 3_600);
+        assert_eq!(farm.reward_debt, 0);
+
+        // A day at the snapshotted rate is credited, then the accumulator
+        // takes over from now rather than from launch
+        let mut config = config(1_000, 1_000_000_000_000);
+        config.acc_reward_per_cow = 123 * ACC_REWARD_PRECISION;
+        let earned = adopt_reward_accumulator(&mut farm, &mut config, T0).unwrap();
+        assert_eq!(earned, 10 * rate);
+        assert_eq!(farm.accumulated_rewards, 7_000_000 + 10 * rate);
+        assert_eq!(farm.reward_debt, config.acc_reward_per_cow);
+        assert_eq!(farm.last_update_time, T0);
+        assert_eq!(farm.last_reward_rate, 0);
+        assert_eq!(farm.last_withdraw_time, T0 - 3_600);
+    }
+
+    /// Run `update_farm_rewards` on a farm held outside an `Account`
+    fn settle(farm: &mut FarmAccount, config: &mut Config, current_time: i64) {
+        let key = Pubkey::new_unique();
+        let mut lamports = 1;
+        let mut data = Vec::new();
+        farm.try_serialize(&mut data).unwrap();
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &crate::ID, false, 0);
+        let mut account = Account::<FarmAccount>::try_from(&info).unwrap();
+        update_farm_rewards(&mut account, config, current_time).unwrap();
+        *farm = (*account).clone();
+    }
+
+    fn farm_with(cows: u64, config: &mut Config, current_time: i64) -> FarmAccount {
+        let mut farm = empty_farm();
+        farm.cows = cows;
+        start_reward_clock(&mut farm, config, current_time).unwrap();
+        farm
+    }
+
+    #[test]
+    fn farms_sharing_an_interval_split_it_pro_rata() {
+        let mut config = config(1_000, 1_000_000_000_000);
+        let rate = config.reward_rate;
+        assert!(rate > 0);
+        let mut big = farm_with(30, &mut config, T0);
+        let mut small = farm_with(10, &mut config, T0);
+
+        // The small farm settling halfway doesn't change either share
+        settle(&mut small, &mut config, T0 + SECONDS_PER_DAY / 2);
+        settle(&mut small, &mut config, T0 + SECONDS_PER_DAY);
+        settle(&mut big, &mut config, T0 + SECONDS_PER_DAY);
+
+        assert_eq!(big.accumulated_rewards, 30 * rate);
+        assert_eq!(small.accumulated_rewards, 10 * rate);
+        assert_eq!(big.reward_debt, small.reward_debt);
+    }
+
+    #[test]
+    fn mid_interval_rate_change_settles_each_half_at_its_rate() {
+        let mut config = config(1_000, 1_000_000_000_000);
+        let before = config.reward_rate;
+        let mut farm = farm_with(10, &mut config, T0);
+
+        // Another farm's withdrawal halves the pool halfway through the day
+        config.tvl /= 2;
+        let after = sync_reward_accumulator(&mut config, T0 + SECONDS_PER_DAY / 2).unwrap();
+        assert_ne!(after, before);
+
+        settle(&mut farm, &mut config, T0 + SECONDS_PER_DAY);
+        let expected = 10 * (before / 2 + after / 2);
+        assert!(farm.accumulated_rewards.abs_diff(expected) <= 10);
+        assert_eq!(farm.reward_debt, config.acc_reward_per_cow);
+    }
+
+    #[test]
+    fn migrated_legacy_farm_accrues_like_a_new_one() {
+        let mut config = config(1_000, 1_000_000_000_000);
+        let mut legacy = empty_farm();
+        legacy.cows = 10;
+        legacy.last_update_time = T0 - SECONDS_PER_DAY;
+        legacy.last_reward_rate = 2_000_000;
+        adopt_reward_accumulator(&mut legacy, &mut config, T0).unwrap();
+        assert_eq!(legacy.accumulated_rewards, 10 * 2_000_000);
+
+        let mut fresh = farm_with(10, &mut config, T0);
+        config.tvl *= 2;
+        sync_reward_accumulator(&mut config, T0 + SECONDS_PER_DAY).unwrap();
+        settle(&mut legacy, &mut config, T0 + 2 * SECONDS_PER_DAY);
+        settle(&mut fresh, &mut config, T0 + 2 * SECONDS_PER_DAY);
+
+        // From the migration on it earns the live accumulator, not its snapshot
+        assert_eq!(legacy.accumulated_rewards - 10 * 2_000_000, fresh.accumulated_rewards);
+        assert!(fresh.accumulated_rewards > 0);
+        assert_eq!(legacy.reward_debt, fresh.reward_debt);
+    }
+
+    #[test]
+    fn multi_cow_purchases_climb_the_curve() {
+        let cfg = config(100_000, 1_000_000_000_000);
+        let start = calculate_cow_price(&cfg, 100_000, T0).unwrap();
+        let end = calculate_cow_price(&cfg, 100_050, T0).unwrap();
+        assert!(end > start);
+
+        // Fifty cows cost more than fifty at the first cow's price, and no
+        // more than fifty at the last one's
+        let cost = calculate_cow_cost(&cfg, 100_000, 50, T0).unwrap();
+        assert!(cost > 50 * start && cost <= 50 * end);
+
+        // Buying in two lots costs the same as one, up to rounding
+        let split = calculate_cow_cost(&cfg, 100_000, 20, T0).unwrap()
+            + calculate_cow_cost(&cfg, 100_020, 30, T0).unwrap();
+        assert!(split.abs_diff(cost) <= 2);
+
+        assert_eq!(max_affordable_cows(&cfg, 100_000, cost, T0).unwrap(), 50);
+        assert_eq!(max_affordable_cows(&cfg, 100_000, cost - 1, T0).unwrap(), 49);
+        assert_eq!(max_affordable_cows(&cfg, 100_000, start - 1, T0).unwrap(), 0);
+    }
+
+}
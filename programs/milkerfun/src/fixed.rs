@@ -0,0 +1,249 @@
+//! Q64.64 fixed-point arithmetic for the economic curves. Every operation is
+//! integer-only, so the curves evaluate bit-for-bit identically on every
+//! validator build, unlike f64 `powf` and `exp`.
+//!
+//! Values are `u128` with 64 integer and 64 fractional bits. Logarithms are
+//! signed and returned as `i128` in the same format.
+
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+pub(crate) const ONE: u128 = 1 << 64;
+
+/// log2(e), for evaluating natural exponentials with `exp2`
+const LOG2_E: u128 = 0x171547652b82fe177;
+
+/// 2^(2^-i) for i = 1..=64, rounded to nearest
+const EXP2_TABLE: [u128; 64] = [
+    0x16a09e667f3bcc909,
+    0x1306fe0a31b7152df,
+    0x1172b83c7d517adce,
+    0x10b5586cf9890f62a,
+    0x1059b0d31585743ae,
+    0x102c9a3e778060ee7,
+    0x10163da9fb33356d8,
+    0x100b1afa5abcbed61,
+    0x10058c86da1c09ea2,
+    0x1002c605e2e8cec50,
+    0x100162f3904051fa1,
+    0x1000b175effdc76ba,
+    0x100058ba01fb9f96d,
+    0x10002c5cc37da9492,
+    0x1000162e525ee0547,
+    0x10000b17255775c04,
+    0x1000058b91b5bc9ae,
+    0x100002c5c89d5ec6d,
+    0x10000162e43f4f831,
+    0x100000b1721bcfc9a,
+    0x10000058b90cf1e6e,
+    0x1000002c5c863b73f,
+    0x100000162e430e5a2,
+    0x1000000b172183551,
+    0x100000058b90c0b49,
+    0x10000002c5c8601cc,
+    0x1000000162e42fff0,
+    0x10000000b17217fbb,
+    0x1000000058b90bfce,
+    0x100000002c5c85fe3,
+    0x10000000162e42ff1,
+    0x100000000b17217f8,
+    0x10000000058b90bfc,
+    0x1000000002c5c85fe,
+    0x100000000162e42ff,
+    0x1000000000b17217f,
+    0x100000000058b90c0,
+    0x10000000002c5c860,
+    0x1000000000162e430,
+    0x10000000000b17218,
+    0x1000000000058b90c,
+    0x100000000002c5c86,
+    0x10000000000162e43,
+    0x100000000000b1721,
+    0x10000000000058b91,
+    0x1000000000002c5c8,
+    0x100000000000162e4,
+    0x1000000000000b172,
+    0x100000000000058b9,
+    0x10000000000002c5d,
+    0x1000000000000162e,
+    0x10000000000000b17,
+    0x1000000000000058c,
+    0x100000000000002c6,
+    0x10000000000000163,
+    0x100000000000000b1,
+    0x10000000000000059,
+    0x1000000000000002c,
+    0x10000000000000016,
+    0x1000000000000000b,
+    0x10000000000000006,
+    0x10000000000000003,
+    0x10000000000000001,
+    0x10000000000000001,
+];
+
+pub(crate) fn from_int(value: u64) -> u128 {
+    (value as u128) << 64
+}
+
+/// Basis points as a fixed-point factor, so 25_000 bps is 2.5
+pub(crate) fn from_bps(bps: u64) -> u128 {
+    from_int(bps) / 10_000
+}
+
+/// Integer part, rounded down
+pub(crate) fn to_int(value: u128) -> u64 {
+    (value >> 64) as u64
+}
+
+/// a * b, rounded down
+pub(crate) fn mul(a: u128, b: u128) -> Result<u128> {
+    let (a1, a0) = (a >> 64, a & (ONE - 1));
+    let (b1, b0) = (b >> 64, b & (ONE - 1));
+
+    // Exact floor of the 256-bit product shifted right by 64
+    let high = a1.checked_mul(b1)
+        .and_then(|v| v.checked_mul(ONE))
+        .ok_or(ErrorCode::MathOverflow)?;
+    high.checked_add(a1 * b0)
+        .and_then(|v| v.checked_add(a0 * b1))
+        .and_then(|v| v.checked_add((a0 * b0) >> 64))
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+/// a / b, rounded down. With integer arguments this is the fixed-point
+/// ratio a/b.
+pub(crate) fn div(a: u128, b: u128) -> Result<u128> {
+    require!(b > 0, ErrorCode::MathOverflow);
+    let quotient = a / b;
+    require!(quotient < ONE, ErrorCode::MathOverflow);
+
+    // Long division for the 64 fractional bits
+    let mut remainder = a % b;
+    let mut fraction = 0u128;
+    for _ in 0..64 {
+        let carry = remainder >> 127;
+        remainder <<= 1;
+        fraction <<= 1;
+        if carry == 1 || remainder >= b {
+            remainder = remainder.wrapping_sub(b);
+            fraction |= 1;
+        }
+    }
+    Ok((quotient << 64) | fraction)
+}
+
+/// log2(x) for x > 0
+pub(crate) fn log2(x: u128) -> Result<i128> {
+    require!(x > 0, ErrorCode::MathOverflow);
+
+    let msb = 127 - x.leading_zeros() as i128;
+    let mut result = (msb - 64) << 64;
+
+    // Normalise into [1, 2) and take fractional bits by repeated squaring
+    let mut y = if msb >= 64 { x >> (msb - 64) } else { x << (64 - msb) };
+    let mut bit = 1i128 << 63;
+    while bit > 0 {
+        y = mul(y, y)?;
+        if y >= 2 * ONE {
+            y >>= 1;
+            result += bit;
+        }
+        bit >>= 1;
+    }
+    Ok(result)
+}
+
+/// 2^x, flushing to zero below the smallest representable value
+pub(crate) fn exp2(x: i128) -> Result<u128> {
+    let integer = x >> 64;
+    let fraction = (x as u128) & (ONE - 1);
+    require!(integer < 64, ErrorCode::MathOverflow);
+    if integer < -64 {
+        return Ok(0);
+    }
+
+    let mut result = ONE;
+    for (i, factor) in EXP2_TABLE.iter().enumerate() {
+        if fraction & (1 << (63 - i)) != 0 {
+            result = mul(result, *factor)?;
+        }
+    }
+
+    if integer >= 0 {
+        Ok(result << integer)
+    } else {
+        Ok(result >> -integer)
+    }
+}
+
+/// base^exponent. Zero to any power is zero.
+pub(crate) fn pow(base: u128, exponent: u128) -> Result<u128> {
+    if base == 0 {
+        return Ok(0);
+    }
+
+    let log = log2(base)?;
+    let scaled = mul(log.unsigned_abs(), exponent)?;
+    let scaled = i128::try_from(scaled).map_err(|_| ErrorCode::MathOverflow)?;
+    exp2(if log < 0 { -scaled } else { scaled })
+}
+
+/// e^-x
+pub(crate) fn exp_neg(x: u128) -> Result<u128> {
+    match mul(x, LOG2_E) {
+        Ok(exponent) if exponent < from_int(128) => exp2(-(exponent as i128)),
+        _ => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_f64(value: u128) -> f64 {
+        value as f64 / ONE as f64
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        let tolerance = expected.abs() * 1e-12 + 1e-15;
+        assert!((actual - expected).abs() <= tolerance, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn div_and_mul_round_trip() {
+        let third = div(1, 3).unwrap();
+        assert_close(to_f64(third), 1.0 / 3.0);
+        assert_eq!(to_int(mul(from_int(6), from_bps(25_000)).unwrap()), 15);
+        assert!(div(1, 0).is_err());
+        assert!(div(u128::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn log2_matches_f64() {
+        for &(num, den) in &[(1u128, 1u128), (3, 1), (1, 3), (2_500, 1), (1, 2_500), (123_456_789, 1_000)] {
+            let expected = (num as f64 / den as f64).log2();
+            let actual = log2(div(num, den).unwrap()).unwrap() as f64 / ONE as f64;
+            assert_close(actual, expected);
+        }
+    }
+
+    #[test]
+    fn pow_matches_f64() {
+        let steepness = from_bps(25_000);
+        for &(num, den) in &[(1u128, 2_500u128), (1_000, 2_500), (2_500, 2_500), (10_000, 2_500), (1_000_000, 2_500)] {
+            let expected = (num as f64 / den as f64).powf(2.5);
+            assert_close(to_f64(pow(div(num, den).unwrap(), steepness).unwrap()), expected);
+        }
+        assert_eq!(pow(0, steepness).unwrap(), 0);
+        assert_eq!(pow(ONE, steepness).unwrap(), ONE);
+    }
+
+    #[test]
+    fn exp_neg_matches_f64() {
+        for &(num, den) in &[(0u128, 1u128), (1, 1_500), (1_500, 1_500), (10_000, 1_500), (30_000, 1_500)] {
+            let expected = (-(num as f64) / den as f64).exp();
+            assert_close(to_f64(exp_neg(div(num, den).unwrap()).unwrap()), expected);
+        }
+        assert_eq!(exp_neg(from_int(1_000)).unwrap(), 0);
+    }
+}
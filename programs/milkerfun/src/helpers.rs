@@ -0,0 +1,757 @@
+//! Shared plumbing for instruction handlers.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::get_instruction_relative;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use crate::contexts::{SellCows, WithdrawMilk};
+use crate::constants::{BPS_DENOMINATOR, INITIAL_TVL, MIGRATION_WINDOW, MIN_MIGRATION_DELAY, PHASE_BLOCK_SELL};
+use crate::rounding::{self, USER_CREDIT};
+use crate::errors::ErrorCode;
+use crate::events::{CowsSold, WithdrawEvent, MilkWithheld, ParamChanged};
+use crate::farm::{record_harvest, settle_withdrawal, sync_reward_accumulator, take_tier_cows, update_farm_rewards};
+use crate::phase::require_phase_allows;
+use crate::math::{calculate_cow_price, calculate_reward_rate, calculate_sell_payout, economic_params, DEFAULT_ECONOMIC_PARAMS};
+use crate::state::{Config, CowRarity, CowStakingPool, CowVault, FarmAccount, FeeHookKind, LoanPosition, Portfolio, Quote, YieldClaim};
+use crate::types::{ConfigParam, ParamValue};
+
+/// Settle the signer's farm and pay `amount` of its rewards out of the pool
+pub(crate) fn process_withdrawal(
+    accounts: &mut WithdrawMilk,
+    pool_authority_bump: u8,
+    amount: u64,
+    memo: Option<String>,
+) -> Result<()> {
+    let pool_balance = reloaded_pool_balance(&mut accounts.pool_token_account)?;
+    let config = &mut accounts.config;
+    let farm = &mut accounts.farm;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let (withdrawal_amount, penalty_amount) = settle_withdrawal(
+        farm,
+        config,
+        accounts.penalty_grace.as_deref(),
+        current_time,
+        Some(amount),
+        pool_balance,
+    )?;
+    farm.last_interaction_time = current_time;
+
+    require!(
+        config.escrow_threshold == 0 || withdrawal_amount <= config.escrow_threshold,
+        ErrorCode::WithdrawalRequiresEscrow
+    );
+
+    pay_withdrawal(
+        &accounts.token_program,
+        &accounts.pool_token_account,
+        accounts.user_token_account.to_account_info(),
+        accounts.withholding_account.as_ref(),
+        &accounts.pool_authority,
+        &accounts.milk_mint,
+        &config.key(),
+        pool_authority_bump,
+        farm,
+        withdrawal_amount,
+        current_time,
+    )?;
+    book_pool_outflow(config, withdrawal_amount)?;
+    cache_pool_balance(config, &mut accounts.pool_token_account)?;
+    let new_reward_rate = config.reward_rate;
+    if let Some(quote) = accounts.quote.as_mut() {
+        write_quote(quote, config, config.tvl)?;
+    }
+
+    if let Some(log) = accounts.harvest_log.as_mut() {
+        record_harvest(log, withdrawal_amount, penalty_amount, current_time);
+    }
+
+    if let (Some(bonus_pool), Some(user_bonus_account), Some(bonus_mint)) =
+        (&accounts.bonus_pool, &accounts.user_bonus_account, &accounts.bonus_mint)
+    {
+        let bonus = farm.bonus_rewards.min(bonus_pool.amount);
+        if bonus > 0 {
+            transfer_from_pool(
+                &accounts.token_program,
+                bonus_pool,
+                user_bonus_account.to_account_info(),
+                &accounts.pool_authority,
+                bonus_mint,
+                &config.key(),
+                pool_authority_bump,
+                bonus,
+            )?;
+            farm.bonus_rewards -= bonus;
+            msg!("Claimed {} bonus tokens from campaign {}", bonus, farm.bonus_campaign_id);
+        }
+    }
+
+    if let Some(receipt) = accounts.receipt.as_mut() {
+        receipt.farm = farm.key();
+        receipt.owner = farm.owner;
+        receipt.sequence = farm.withdrawal_count - 1;
+        receipt.amount = withdrawal_amount;
+        receipt.penalty = penalty_amount;
+        receipt.timestamp = current_time;
+        receipt.slot = Clock::get()?.slot;
+    }
+
+    emit!(WithdrawEvent {
+        farm: farm.key(),
+        owner: farm.owner,
+        tag: farm.tag,
+        amount: withdrawal_amount,
+        penalty: penalty_amount,
+        reward_rate: new_reward_rate,
+        memo,
+        timestamp: current_time,
+    });
+
+    if penalty_amount > 0 {
+        msg!("Successfully withdrew {} MILK tokens with {} MILK penalty remaining in pool. {} MILK left accruing. New rate: {} MILK/cow/day", 
+             withdrawal_amount / 1_000_000, penalty_amount / 1_000_000, farm.accumulated_rewards / 1_000_000, new_reward_rate / 1_000_000);
+    } else {
+        msg!("Successfully withdrew {} MILK tokens (penalty-free). {} MILK left accruing. New rate: {} MILK/cow/day", 
+             withdrawal_amount / 1_000_000, farm.accumulated_rewards / 1_000_000, new_reward_rate / 1_000_000);
+    }
+    
+    Ok(())
+}
+
+/// Settle the signer's farm and sell `num_cows` of `rarity` back to the pool
+pub(crate) fn process_sale(
+    accounts: &mut SellCows,
+    pool_authority_bump: u8,
+    rarity: CowRarity,
+    num_cows: u64,
+) -> Result<()> {
+    require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+    let config = &mut accounts.config;
+    require!(!config.paused, ErrorCode::ProgramPaused);
+    require_phase_allows(config, PHASE_BLOCK_SELL)?;
+    let farm = &mut accounts.farm;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    update_farm_rewards(farm, config, current_time)?;
+    farm.last_interaction_time = current_time;
+
+    let mut payout = calculate_sell_payout(&economic_params(config), config.global_cows_count, num_cows)?;
+    if rarity != CowRarity::Common {
+        payout = rounding::mul_div(payout, config.cow_tiers[rarity as usize].price_bps as u64, BPS_DENOMINATOR, USER_CREDIT)?;
+    }
+    require!(
+        payable_from_pool(config, &mut accounts.pool_token_account, payout)? == payout,
+        ErrorCode::InsufficientPoolBalance
+    );
+
+    msg!("Selling {} {:?} cows (global count: {}) for {} MILK", 
+         num_cows, rarity, config.global_cows_count, payout / 1_000_000);
+
+    take_tier_cows(farm, rarity, num_cows)?;
+    config.global_cows_count -= num_cows;
+    config.farming_cows_count = config.farming_cows_count.saturating_sub(num_cows);
+
+    transfer_from_pool(
+        &accounts.token_program,
+        &accounts.pool_token_account,
+        accounts.user_token_account.to_account_info(),
+        &accounts.pool_authority,
+        &accounts.milk_mint,
+        &config.key(),
+        pool_authority_bump,
+        payout,
+    )?;
+    book_pool_outflow(config, payout)?;
+    cache_pool_balance(config, &mut accounts.pool_token_account)?;
+
+    let new_reward_rate = sync_reward_accumulator(config, current_time)?;
+    if let Some(quote) = accounts.quote.as_mut() {
+        write_quote(quote, config, config.tvl)?;
+    }
+
+    emit!(CowsSold {
+        farm: farm.key(),
+        owner: farm.owner,
+        tag: farm.tag,
+        num_cows,
+        payout,
+        global_cows: config.global_cows_count,
+        reward_rate: new_reward_rate,
+        timestamp: current_time,
+    });
+
+    msg!("Successfully sold {} cows. User total: {}, Global total: {}, New rate: {} MILK/cow/day", 
+         num_cows, farm.cows, config.global_cows_count, new_reward_rate / 1_000_000);
+    Ok(())
+}
+
+/// Reload the pool's balance after this instruction's transfers and cache
+/// it for views called without the pool account. The booked TVL is capped
+/// at the reloaded balance: it can only run ahead if a transfer moved less
+/// than was booked, and rates must never be priced off MILK that isn't there.
+pub(crate) fn cache_pool_balance(config: &mut Config, pool_token_account: &mut InterfaceAccount<TokenAccount>) -> Result<()> {
+    pool_token_account.reload()?;
+    let current_time = Clock::get()?.unix_timestamp;
+    config.last_known_pool_balance = pool_token_account.amount;
+    config.pool_balance_cached_at = current_time;
+
+    if config.tvl > pool_token_account.amount {
+        msg!("Booked TVL {} above pool balance {}, capping", config.tvl, pool_token_account.amount);
+        config.tvl = pool_token_account.amount;
+        sync_reward_accumulator(config, current_time)?;
+    }
+    Ok(())
+}
+
+/// Reload the pool and return the MILK actually in it
+pub(crate) fn reloaded_pool_balance(pool_token_account: &mut InterfaceAccount<TokenAccount>) -> Result<u64> {
+    pool_token_account.reload()?;
+    Ok(pool_token_account.amount)
+}
+
+/// Bound a payout by what the pool can cover: the booked TVL, which leaves
+/// out earmarked funds, and the reloaded balance, which the booked TVL may
+/// have run ahead of since it was last capped
+pub(crate) fn payable_from_pool(config: &Config, pool_token_account: &mut InterfaceAccount<TokenAccount>, amount: u64) -> Result<u64> {
+    let balance = reloaded_pool_balance(pool_token_account)?;
+    Ok(amount.min(config.tvl).min(balance))
+}
+
+/// Fill in the Config fields added since the launch layout, which read as
+/// zero once the account is grown: the defaults `initialize_config` sets,
+/// the pool balance as booked TVL, and the herd split into tokenized cows
+/// (the live COW supply) and cows still in farms
+pub(crate) fn upgrade_launch_config(
+    config: &mut Config,
+    pool_balance: u64,
+    cow_supply: u64,
+    milk_mint_authority: Pubkey,
+    milk_freeze_authority: Pubkey,
+    current_time: i64,
+) -> Result<()> {
+    config.initial_tvl = INITIAL_TVL;
+    config.milk_mint_authority = milk_mint_authority;
+    config.milk_freeze_authority = milk_freeze_authority;
+    config.tokenized_cows_count = cow_supply.div_ceil(1_000_000);
+    config.farming_cows_count = config.global_cows_count.saturating_sub(config.tokenized_cows_count);
+    config.migration_deadline = current_time + MIGRATION_WINDOW;
+    config.migration_delay = MIN_MIGRATION_DELAY;
+    config.last_known_pool_balance = pool_balance;
+    config.tvl = pool_balance;
+    config.pool_balance_cached_at = current_time;
+    config.economic_params = DEFAULT_ECONOMIC_PARAMS;
+    sync_reward_accumulator(config, current_time)?;
+    Ok(())
+}
+
+/// Book MILK the program moved into the pool and re-price the reward rate
+pub(crate) fn book_pool_inflow(config: &mut Config, amount: u64) -> Result<()> {
+    config.tvl = config.tvl
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    sync_reward_accumulator(config, Clock::get()?.unix_timestamp)?;
+    Ok(())
+}
+
+/// Earmark a fee hook's share of `amount` for its destination. The MILK
+/// stays in the pool (and in TVL) until `sweep_fee_hook` pays it out.
+/// Returns the share, rounded down.
+pub(crate) fn route_fee(config: &mut Config, kind: FeeHookKind, amount: u64) -> Result<u64> {
+    let hook = config.fee_hooks[kind as usize];
+    if hook.bps == 0 || amount == 0 {
+        return Ok(0);
+    }
+
+    let share = rounding::mul_div(amount, hook.bps as u64, BPS_DENOMINATOR, USER_CREDIT)?;
+    config.fee_hook_pending[kind as usize] = config.fee_hook_pending[kind as usize]
+        .checked_add(share)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(share)
+}
+
+/// Book MILK the program moved out of the pool and re-price the reward rate
+pub(crate) fn book_pool_outflow(config: &mut Config, amount: u64) -> Result<()> {
+    config.tvl = config.tvl.saturating_sub(amount);
+    sync_reward_accumulator(config, Clock::get()?.unix_timestamp)?;
+    Ok(())
+}
+
+/// Check that minting `num_cows` more COW keeps the supply within the
+/// tokenized cows backing it. Call after the export has been booked.
+pub(crate) fn require_cow_backing(config: &Config, cow_mint: &Mint, num_cows: u64) -> Result<()> {
+    if !config.cow_backing_enforced {
+        return Ok(());
+    }
+
+    let supply_after = cow_mint.supply
+        .checked_add(num_cows.checked_mul(1_000_000).ok_or(ErrorCode::MathOverflow)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let backed_supply = config.tokenized_cows_count
+        .checked_mul(1_000_000)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(supply_after <= backed_supply, ErrorCode::CowSupplyExceedsBacking);
+    Ok(())
+}
+
+/// Rewrite the quote account from the current global state
+pub(crate) fn write_quote(quote: &mut Quote, config: &Config, pool_balance: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    quote.cow_price = calculate_cow_price(config, config.global_cows_count, clock.unix_timestamp)?;
+    quote.reward_rate = calculate_reward_rate(config, config.global_cows_count, pool_balance, clock.unix_timestamp)?;
+    quote.pool_balance = pool_balance;
+    quote.global_cows_count = config.global_cows_count;
+    quote.slot = clock.slot;
+    quote.updated_at = clock.unix_timestamp;
+    Ok(())
+}
+
+/// Flip the global circuit breaker and log the change. Whoever sets it
+/// takes over any pause the upgrade guard left behind.
+pub(crate) fn set_paused(config: &mut Config, paused: bool, admin: Pubkey) -> Result<()> {
+    require!(paused || config.final_milking_started_at == 0, ErrorCode::FinalMilkingActive);
+    let old_value = config.paused;
+    config.paused = paused;
+    config.upgrade_guard_tripped = false;
+
+    record_param_change(
+        config,
+        ConfigParam::Paused,
+        ParamValue::Unsigned(old_value as u64),
+        ParamValue::Unsigned(paused as u64),
+        admin,
+    )?;
+
+    msg!("Game {}", if paused { "paused" } else { "unpaused" });
+    Ok(())
+}
+
+/// Copy a farm's position, and its loan if any, into its portfolio alias
+pub(crate) fn snapshot_portfolio(
+    portfolio: &mut Portfolio,
+    farm: Option<&FarmAccount>,
+    loan_position: Option<&LoanPosition>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    if let Some(farm) = farm {
+        portfolio.cows = farm.cows;
+        portfolio.pending_cows = farm.pending_cows;
+        portfolio.accumulated_rewards = farm.accumulated_rewards;
+        portfolio.bonus_rewards = farm.bonus_rewards;
+        portfolio.referral_earnings = farm.referral_earnings;
+    }
+    if let Some(position) = loan_position {
+        portfolio.collateral_cows = position.collateral_cows;
+        portfolio.debt = position.debt;
+    }
+    portfolio.refreshed_at = clock.unix_timestamp;
+    portfolio.refreshed_slot = clock.slot;
+    Ok(())
+}
+
+/// Token-2022 transfer fee charged on moving `amount` of `mint` in the
+/// current epoch. Zero for classic SPL mints and mints without the extension.
+pub(crate) fn transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != anchor_spl::token_2022::ID {
+        return Ok(0);
+    }
+
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(ErrorCode::MathOverflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// What the recipient of a transfer of `amount` of `mint` actually receives
+pub(crate) fn net_of_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    Ok(amount.saturating_sub(transfer_fee(mint, amount)?))
+}
+
+/// Pay `amount` of a farm's settled rewards out of the pool to
+/// `destination`, sending the farm's withheld share to its withholding
+/// account. Every withdrawal route pays out through here. Returns the
+/// amount withheld.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pay_withdrawal<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    pool_token_account: &InterfaceAccount<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    withholding_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    pool_authority: &UncheckedAccount<'info>,
+    milk_mint: &InterfaceAccount<'info, Mint>,
+    config_key: &Pubkey,
+    pool_authority_bump: u8,
+    farm: &Account<'info, FarmAccount>,
+    amount: u64,
+    current_time: i64,
+) -> Result<u64> {
+    let withheld = rounding::mul_div(amount, farm.withholding_bps as u64, BPS_DENOMINATOR, USER_CREDIT)?;
+    if withheld > 0 {
+        let withholding_account = withholding_account.ok_or(ErrorCode::WithholdingAccountRequired)?;
+        transfer_from_pool(
+            token_program,
+            pool_token_account,
+            withholding_account.to_account_info(),
+            pool_authority,
+            milk_mint,
+            config_key,
+            pool_authority_bump,
+            withheld,
+        )?;
+        emit!(MilkWithheld {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            account: withholding_account.key(),
+            amount: withheld,
+            timestamp: current_time,
+        });
+    }
+
+    transfer_from_pool(
+        token_program,
+        pool_token_account,
+        destination,
+        pool_authority,
+        milk_mint,
+        config_key,
+        pool_authority_bump,
+        amount - withheld,
+    )?;
+    Ok(withheld)
+}
+
+/// Transfer tokens out of a pool-authority account, signed by the pool
+/// authority PDA. Under a transfer fee the recipient gets `amount` less the fee.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_from_pool<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    pool_token_account: &InterfaceAccount<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    pool_authority: &UncheckedAccount<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    config_key: &Pubkey,
+    pool_authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"pool_authority",
+        config_key.as_ref(),
+        &[pool_authority_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: pool_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to,
+                authority: pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Move staked COW out of the staking vault, signed by the staking pool PDA
+pub(crate) fn transfer_staked_cows<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    staking_pool: &Account<'info, CowStakingPool>,
+    cow_mint: &InterfaceAccount<'info, Mint>,
+    config_key: &Pubkey,
+    num_cows: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"cow_staking",
+        config_key.as_ref(),
+        &[staking_pool.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: token_vault.to_account_info(),
+                mint: cow_mint.to_account_info(),
+                to,
+                authority: staking_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        num_cows * 1_000_000, // COW tokens have 6 decimals
+        cow_mint.decimals,
+    )
+}
+
+/// Mint COW tokens for exported cows, signed by the COW mint authority PDA
+pub(crate) fn mint_cow_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    cow_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    cow_mint_authority: &UncheckedAccount<'info>,
+    config_key: &Pubkey,
+    cow_mint_authority_bump: u8,
+    num_cows: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"cow_mint_authority",
+        config_key.as_ref(),
+        &[cow_mint_authority_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: cow_mint.to_account_info(),
+                to,
+                authority: cow_mint_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        num_cows * 1_000_000, // COW tokens have 6 decimals, so 1 cow = 1,000,000 tokens
+    )
+}
+
+/// Mint COW vault shares, signed by the vault PDA
+pub(crate) fn mint_vault_shares<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    share_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    cow_vault: &Account<'info, CowVault>,
+    config_key: &Pubkey,
+    shares: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"cow_vault",
+        config_key.as_ref(),
+        &[cow_vault.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: share_mint.to_account_info(),
+                to,
+                authority: cow_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        shares,
+    )
+}
+
+/// Mint yield claim tokens, signed by the claim PDA
+pub(crate) fn mint_yield_claim_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    claim_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    yield_claim: &Account<'info, YieldClaim>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"yield_claim",
+        yield_claim.farm.as_ref(),
+        &[yield_claim.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: claim_mint.to_account_info(),
+                to,
+                authority: yield_claim.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Emit an auditable record of a Config parameter change. Every change gets a
+/// sequence number so indexers can detect gaps in the history.
+pub(crate) fn record_param_change(
+    config: &mut Config,
+    param: ConfigParam,
+    old_value: ParamValue,
+    new_value: ParamValue,
+    changed_by: Pubkey,
+) -> Result<()> {
+    record_param_changes(config, &[(param, old_value, new_value)], changed_by)
+}
+
+/// Record every change made by one instruction. If any rate-limited
+/// parameter moved, the batch as a whole counts as one update against the
+/// cooldown and each such change must stay within the step limit.
+pub(crate) fn record_param_changes(
+    config: &mut Config,
+    changes: &[(ConfigParam, ParamValue, ParamValue)],
+    changed_by: Pubkey,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let limited: Vec<_> = changes
+        .iter()
+        .filter(|(param, old_value, new_value)| param.is_rate_limited() && old_value != new_value)
+        .collect();
+    if !limited.is_empty() {
+        require!(
+            config.last_rate_limited_change == 0
+                || current_time - config.last_rate_limited_change >= config.param_update_cooldown,
+            ErrorCode::ParamUpdateCooldown
+        );
+        for (param, old_value, new_value) in limited {
+            require!(
+                within_param_step(param, config.param_max_step_bps, *old_value, *new_value),
+                ErrorCode::ParamStepTooLarge
+            );
+        }
+        config.last_rate_limited_change = current_time;
+    }
+
+    for (param, old_value, new_value) in changes {
+        config.param_change_count = config.param_change_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(ParamChanged {
+            sequence: config.param_change_count,
+            param: *param,
+            old_value: *old_value,
+            new_value: *new_value,
+            changed_by,
+            effective_at: current_time,
+        });
+    }
+    Ok(())
+}
+
+/// Whether a numeric parameter change stays within `max_step_bps` of the old
+/// value. A switch may always be turned off, and turned back on within its
+/// `switch_on_bounds`. Any other move to or from zero counts as a full step.
+fn within_param_step(param: &ConfigParam, max_step_bps: u16, old_value: ParamValue, new_value: ParamValue) -> bool {
+    if max_step_bps == 0 {
+        return true;
+    }
+
+    let (old, new) = match (old_value, new_value) {
+        (ParamValue::Unsigned(old), ParamValue::Unsigned(new)) => (old as i128, new as i128),
+        (ParamValue::Signed(old), ParamValue::Signed(new)) => (old as i128, new as i128),
+        _ => return true,
+    };
+    let full_step = max_step_bps as u64 >= BPS_DENOMINATOR;
+    if new == 0 {
+        return param.is_switch() || full_step;
+    }
+    if old == 0 {
+        return match param.switch_on_bounds() {
+            Some(bounds) => u64::try_from(new).is_ok_and(|new| bounds.contains(&new)),
+            None => full_step,
+        };
+    }
+
+    (new - old).abs() * BPS_DENOMINATOR as i128 <= old.abs() * max_step_bps as i128
+}
+
+/// Automation entry points must be reached by CPI from a whitelisted program:
+/// the top-level instruction currently executing belongs to the caller
+pub(crate) fn require_automation_caller(config: &Config, instructions: &AccountInfo) -> Result<()> {
+    let current_ix = get_instruction_relative(0, instructions)?;
+    require!(
+        current_ix.program_id != crate::ID
+            && current_ix.program_id != Pubkey::default()
+            && config.automation_programs.contains(&current_ix.program_id),
+        ErrorCode::AutomationNotWhitelisted
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: i64 = 1_700_000_000;
+
+    #[test]
+    fn switches_turn_off_freely_but_on_only_within_bounds() {
+        let (zero, huge) = (ParamValue::Unsigned(0), ParamValue::Unsigned(INITIAL_TVL));
+        let threshold = ConfigParam::SolvencyThreshold;
+
+        assert!(within_param_step(&threshold, 500, huge, zero));
+        assert!(!within_param_step(&threshold, 500, zero, huge));
+        assert!(within_param_step(&threshold, 500, zero, ParamValue::Unsigned(INITIAL_TVL / 10)));
+
+        // Cooldowns are signed and can't come back on at a negative value
+        let cooldown = ConfigParam::BuyCooldown;
+        assert!(within_param_step(&cooldown, 500, ParamValue::Signed(0), ParamValue::Signed(60)));
+        assert!(!within_param_step(&cooldown, 500, ParamValue::Signed(0), ParamValue::Signed(86_400)));
+
+        // Anything else to or from zero is a full step
+        let pivot = ConfigParam::PricePivot;
+        assert!(!within_param_step(&pivot, 500, ParamValue::Unsigned(1_000), zero));
+        assert!(!within_param_step(&pivot, 500, zero, ParamValue::Unsigned(1)));
+        assert!(within_param_step(&pivot, 10_000, zero, ParamValue::Unsigned(1)));
+        assert!(within_param_step(&pivot, 500, ParamValue::Unsigned(1_000), ParamValue::Unsigned(1_050)));
+        assert!(!within_param_step(&pivot, 500, ParamValue::Unsigned(1_000), ParamValue::Unsigned(1_051)));
+    }
+
+    #[test]
+    fn launch_config_decodes_and_upgrades_after_growing() {
+        let (admin, milk_mint, cow_mint, pool) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        // The launch layout: four keys, start time, global cows, initial TVL
+        let mut data = Config::DISCRIMINATOR.to_vec();
+        for key in [admin, milk_mint, cow_mint, pool] {
+            data.extend_from_slice(key.as_ref());
+        }
+        data.extend_from_slice(&(T0 - 86_400).to_le_bytes());
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&INITIAL_TVL.to_le_bytes());
+        assert_eq!(data.len(), 160);
+        data.resize(8 + Config::INIT_SPACE, 0);
+
+        let mut config = Config::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(config.layout_version, 0);
+        assert_eq!((config.admin, config.milk_mint, config.cow_mint, config.pool_token_account), (admin, milk_mint, cow_mint, pool));
+        assert_eq!(config.start_time, T0 - 86_400);
+        assert_eq!(config.global_cows_count, 1_000);
+
+        upgrade_launch_config(&mut config, 5_000_000_000_000, 150_500_000, Pubkey::default(), Pubkey::default(), T0).unwrap();
+        assert_eq!(config.tvl, 5_000_000_000_000);
+        assert_eq!(config.last_known_pool_balance, 5_000_000_000_000);
+        assert_eq!(config.tokenized_cows_count, 151);
+        assert_eq!(config.farming_cows_count, 849);
+        assert_eq!(config.economic_params.price_pivot, DEFAULT_ECONOMIC_PARAMS.price_pivot);
+        assert_eq!(config.reward_rate_updated_at, T0);
+        assert!(config.reward_rate > 0);
+        assert_eq!(config.migration_deadline, T0 + MIGRATION_WINDOW);
+
+        // Round-trips through the grown account
+        let mut grown = vec![0u8; 8 + Config::INIT_SPACE];
+        let mut writer: &mut [u8] = &mut grown[..];
+        config.try_serialize(&mut writer).unwrap();
+        let decoded = Config::try_deserialize(&mut &grown[..]).unwrap();
+        assert_eq!(decoded.tvl, config.tvl);
+        assert_eq!(decoded.admin, admin);
+    }
+}
@@ -0,0 +1,140 @@
+//! Cow-collateralized MILK loans.
+
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::errors::ErrorCode;
+use crate::math::{apply_bonus_bps, calculate_sell_payout, economic_params};
+use crate::rounding::{self, USER_CREDIT, USER_DEBIT};
+use crate::state::{Config, LoanPosition};
+
+/// What a loan's collateral would fetch if sold back to the curve now: the
+/// sell payout at the post-sale herd, exactly what liquidation pays out.
+/// Event discounts only apply to buys, not to collateral.
+pub(crate) fn collateral_value(config: &Config, collateral_cows: u64) -> Result<u64> {
+    calculate_sell_payout(&economic_params(config), config.global_cows_count, collateral_cows)
+}
+
+/// Simple interest owed on a loan since its last accrual
+pub(crate) fn pending_loan_interest(position: &LoanPosition, config: &Config, current_time: i64) -> Result<u64> {
+    if position.debt == 0 || current_time <= position.last_accrual_time {
+        return Ok(0);
+    }
+
+    let elapsed = (current_time - position.last_accrual_time) as u128;
+    let interest = (position.debt as u128)
+        .checked_mul(config.loan_interest_bps_per_year as u128)
+        .and_then(|v| v.checked_mul(elapsed))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    rounding::div(interest, BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128, USER_DEBIT)
+}
+
+/// Accrue simple interest on a loan since its last update
+pub(crate) fn accrue_loan_interest(position: &mut LoanPosition, config: &Config, current_time: i64) -> Result<()> {
+    let interest = pending_loan_interest(position, config, current_time)?;
+    if interest > 0 {
+        position.debt = position.debt
+            .checked_add(interest)
+            .ok_or(ErrorCode::MathOverflow)?;
+        msg!("Loan interest accrued: {} (debt now {})", interest, position.debt);
+    }
+    position.last_accrual_time = current_time;
+    Ok(())
+}
+
+/// Largest debt a position may carry at the given collateral ratio
+pub(crate) fn max_debt_at(config: &Config, collateral_cows: u64, ratio_bps: u16) -> Result<u64> {
+    let value = collateral_value(config, collateral_cows)?;
+    rounding::mul_div(value, ratio_bps as u64, BPS_DENOMINATOR, USER_CREDIT)
+}
+
+/// Split liquidation proceeds past the cleared debt into the keeper's bonus
+/// and the owner's surplus. The bonus is sized on the debt but can only come
+/// out of the surplus, so the pool never pays more than the collateral is
+/// worth.
+pub(crate) fn liquidation_split(debt: u64, proceeds: u64, bonus_bps: u16, tvl: u64) -> Result<(u64, u64)> {
+    let excess = proceeds.saturating_sub(debt);
+    let bonus = apply_bonus_bps(debt, bonus_bps)?
+        .saturating_sub(debt)
+        .min(excess)
+        .min(tvl);
+    Ok((bonus, excess - bonus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::calculate_cow_price_with;
+
+    const T0: i64 = 1_700_000_000;
+
+    fn config(global_cows: u64) -> Config {
+        let zeroed = vec![0u8; Config::INIT_SPACE];
+        let mut config = Config::deserialize(&mut &zeroed[..]).unwrap();
+        config.global_cows_count = global_cows;
+        config.loan_interest_bps_per_year = 1_000;
+        config
+    }
+
+    fn position(debt: u64) -> LoanPosition {
+        let zeroed = [0u8; LoanPosition::INIT_SPACE];
+        let mut position = LoanPosition::deserialize(&mut &zeroed[..]).unwrap();
+        position.collateral_cows = 10;
+        position.debt = debt;
+        position.last_accrual_time = T0;
+        position
+    }
+
+    #[test]
+    fn collateral_is_valued_at_the_sell_payout() {
+        let config = config(1_000);
+        let params = economic_params(&config);
+        let spot = calculate_cow_price_with(&params, 1_000).unwrap() * 10;
+
+        let value = collateral_value(&config, 10).unwrap();
+        assert_eq!(value, calculate_sell_payout(&params, 1_000, 10).unwrap());
+        assert!(value < spot);
+    }
+
+    #[test]
+    fn max_debt_scales_the_sell_payout_by_the_ratio() {
+        let config = config(1_000);
+        let value = collateral_value(&config, 10).unwrap();
+
+        assert_eq!(max_debt_at(&config, 10, 5_000).unwrap(), value / 2);
+        assert_eq!(max_debt_at(&config, 10, 10_000).unwrap(), value);
+        assert_eq!(max_debt_at(&config, 0, 5_000).unwrap(), 0);
+        assert!(max_debt_at(&config, 1_001, 5_000).is_err());
+    }
+
+    #[test]
+    fn interest_accrues_simply_and_rounds_against_the_borrower() {
+        let config = config(1_000);
+        let mut loan = position(1_000_000_000);
+
+        assert_eq!(pending_loan_interest(&loan, &config, T0).unwrap(), 0);
+        assert_eq!(pending_loan_interest(&loan, &config, T0 + SECONDS_PER_YEAR).unwrap(), 100_000_000);
+
+        // One second of interest is a fraction of a base unit and rounds up
+        let mut dust = position(1);
+        accrue_loan_interest(&mut dust, &config, T0 + 1).unwrap();
+        assert_eq!(dust.debt, 2);
+
+        accrue_loan_interest(&mut loan, &config, T0 + SECONDS_PER_YEAR / 2).unwrap();
+        assert_eq!(loan.debt, 1_050_000_000);
+        assert_eq!(loan.last_accrual_time, T0 + SECONDS_PER_YEAR / 2);
+        assert_eq!(pending_loan_interest(&loan, &config, loan.last_accrual_time).unwrap(), 0);
+    }
+
+    #[test]
+    fn liquidation_bonus_comes_out_of_the_surplus() {
+        // Plenty of surplus: full 5% bonus, rest to the owner
+        assert_eq!(liquidation_split(1_000, 2_000, 500, u64::MAX).unwrap(), (50, 950));
+        // Thin surplus caps the bonus and leaves the owner nothing
+        assert_eq!(liquidation_split(1_000, 1_020, 500, u64::MAX).unwrap(), (20, 0));
+        // Underwater collateral pays neither
+        assert_eq!(liquidation_split(1_000, 900, 500, u64::MAX).unwrap(), (0, 0));
+        // The bonus never exceeds what the pool holds
+        assert_eq!(liquidation_split(1_000, 2_000, 500, 30).unwrap(), (30, 970));
+    }
+}
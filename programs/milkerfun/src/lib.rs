@@ -1,18 +1,48 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, MintTo, Burn};
-
-const SECONDS_PER_DAY: i64 = 86400; // 24 * 60 * 60
-const COW_BASE_PRICE: u64 = 6_000_000_000; // 6,000 MILK (6 decimals)
-const PRICE_PIVOT: f64 = 2_500.0; // C_pivot
-const PRICE_STEEPNESS: f64 = 2.5; // α
-const REWARD_BASE: u64 = 25_000_000_000; // 25,000 MILK (6 decimals) - B
-const REWARD_SENSITIVITY: f64 = 0.5; // α_reward
-const TVL_NORMALIZATION: f64 = 100_000_000_000.0; // 100,000 MILK (6 decimals) - S
-const MIN_REWARD_PER_DAY: u64 = 1_000_000_000; // 1,000 MILK per day (6 decimals) - R_min
-const GREED_MULTIPLIER: f64 = 8.0; // β
-const GREED_DECAY_PIVOT: f64 = 1_500.0; // C₀
-const INITIAL_TVL: u64 = 100_000_000_000_000; // 100M MILK (6 decimals)
-const MAX_COWS_PER_TRANSACTION: u64 = 50; // Maximum cows per buy transaction
+use anchor_lang::system_program;
+use anchor_spl::memo::{self, BuildMemo};
+use anchor_spl::token_interface::{self, Burn, FreezeAccount, ThawAccount, TransferChecked};
+
+pub mod constants;
+pub mod contexts;
+pub mod errors;
+pub mod events;
+pub mod state;
+pub mod types;
+
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(any(test, feature = "client"))]
+pub mod vectors;
+
+mod cosmetics;
+mod farm;
+mod fixed;
+mod helpers;
+mod lending;
+mod math;
+mod phase;
+mod pyth;
+mod relay;
+mod rounding;
+mod staking;
+mod vrf;
+
+pub use constants::*;
+pub use contexts::*;
+pub use errors::ErrorCode;
+pub use events::*;
+pub use state::*;
+pub use types::*;
+
+use cosmetics::*;
+use farm::*;
+use helpers::*;
+use lending::*;
+use math::*;
+use phase::*;
+use relay::*;
+use staking::*;
 
 declare_id!("AQcStgNbBkLKDQNtQkKYvj8rtHMqeeynfHePXVYghqRS");
 
@@ -20,779 +50,5535 @@ declare_id!("AQcStgNbBkLKDQNtQkKYvj8rtHMqeeynfHePXVYghqRS");
 pub mod milkerfun {
     use super::*;
 
-    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    /// Create the Config. In strict mode, with `expected_authorities` given,
+    /// initialization fails unless the MILK mint's mint and freeze
+    /// authorities are exactly as declared, so users can check on-chain that
+    /// MILK can't be inflated outside the program.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        expected_authorities: Option<MintAuthorityExpectation>,
+    ) -> Result<()> {
         let config = &mut ctx.accounts.config;
         let current_time = Clock::get()?.unix_timestamp;
+        let milk_mint = &ctx.accounts.milk_mint;
+        let mint_authority = Option::<Pubkey>::from(milk_mint.mint_authority);
+        let freeze_authority = Option::<Pubkey>::from(milk_mint.freeze_authority);
+
+        if let Some(expected) = expected_authorities {
+            require!(
+                mint_authority == expected.mint_authority && freeze_authority == expected.freeze_authority,
+                ErrorCode::MilkMintAuthorityMismatch
+            );
+        }
         
         config.admin = ctx.accounts.admin.key();
-        config.milk_mint = ctx.accounts.milk_mint.key();
+        config.milk_mint = milk_mint.key();
+        config.milk_mint_authority = mint_authority.unwrap_or_default();
+        config.milk_freeze_authority = freeze_authority.unwrap_or_default();
+        config.milk_mint_verified = expected_authorities.is_some();
         config.pool_token_account = ctx.accounts.pool_token_account.key();
         config.cow_mint = ctx.accounts.cow_mint.key();
         config.start_time = current_time;
         config.global_cows_count = 0;
         config.initial_tvl = INITIAL_TVL;
+        config.import_quarantine_seconds = 0;
+        config.farming_cows_count = 0;
+        config.tokenized_cows_count = 0;
+        config.streak_days_required = 0;
+        config.streak_bonus_bps = 0;
+        config.pricing_mode = PricingMode::Milk;
+        config.buy_cooldown_seconds = 0;
+        config.migration_deadline = current_time + MIGRATION_WINDOW;
+        config.migration_delay = MIN_MIGRATION_DELAY;
+        config.last_known_pool_balance = ctx.accounts.pool_token_account.amount;
+        config.tvl = ctx.accounts.pool_token_account.amount;
+        config.pool_balance_cached_at = current_time;
+        config.economic_params = DEFAULT_ECONOMIC_PARAMS;
+        config.layout_version = CONFIG_LAYOUT_VERSION;
+        sync_reward_accumulator(config, current_time)?;
         
         msg!("Config initialized - Start time: {}, Initial TVL: {} MILK, Pool: {}, COW Mint: {}", 
              current_time, INITIAL_TVL / 1_000_000, config.pool_token_account, config.cow_mint);
         Ok(())
     }
 
-
-    pub fn buy_cows(ctx: Context<BuyCows>, num_cows: u64) -> Result<()> {
+    /// Buy cows with MILK at the current curve price. Aborts if the price per
+    /// cow at execution exceeds `max_price_per_cow`.
+    pub fn buy_cows(ctx: Context<BuyCows>, num_cows: u64, max_price_per_cow: u64) -> Result<()> {
         require!(num_cows > 0, ErrorCode::InvalidAmount);
-        require!(num_cows <= MAX_COWS_PER_TRANSACTION, ErrorCode::ExceedsMaxCowsPerTransaction);
         
         let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
         let farm = &mut ctx.accounts.farm;
         let current_time = Clock::get()?.unix_timestamp;
 
-        if farm.owner == Pubkey::default() {
-            farm.owner = ctx.accounts.user.key();
-            farm.cows = 0;
-            farm.last_update_time = current_time;
-            farm.accumulated_rewards = 0;
+        if open_farm(farm, ctx.accounts.user.key(), config, current_time)? {
+            if let Some(referrer_farm) = &ctx.accounts.referrer_farm {
+                let referrer_stats = ctx.accounts.referrer_stats
+                    .as_mut()
+                    .ok_or(ErrorCode::ReferrerNotRegistered)?;
+                require!(referrer_stats.owner == referrer_farm.owner, ErrorCode::InvalidReferrer);
+                require!(referrer_farm.owner != farm.owner, ErrorCode::InvalidReferrer);
+                referrer_stats.referred_farms = referrer_stats.referred_farms.saturating_add(1);
+                farm.referrer = referrer_farm.owner;
+            }
             msg!("Initialized new farm for user: {}", ctx.accounts.user.key());
         } else {
-            update_farm_rewards(farm, config, current_time, ctx.accounts.pool_token_account.amount)?;
+            update_farm_rewards(farm, config, current_time)?;
         }
+        farm.last_interaction_time = current_time;
+        check_buy_limits(farm, config, num_cows, current_time)?;
 
-        let cost_per_cow = calculate_cow_price(config.global_cows_count)?;
-        let total_cost = cost_per_cow
-            .checked_mul(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let total_cost = calculate_purchase_cost(
+            config,
+            ctx.accounts.milk_price.as_deref(),
+            ctx.accounts.pyth_price_update.as_ref().map(|a| a.to_account_info()).as_ref(),
+            num_cows,
+            current_time,
+        )?;
+        let total_cost = phase_buy_price(config, total_cost)?;
+        // Cows climb the curve, so slippage is bounded on the average price
+        require!(
+            total_cost <= max_price_per_cow.saturating_mul(num_cows),
+            ErrorCode::SlippageExceeded
+        );
+        let cost_per_cow = total_cost / num_cows;
 
         msg!("Buying {} cows at {} each (global count: {}), total cost: {}", 
              num_cows, cost_per_cow, config.global_cows_count, total_cost);
 
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.milk_mint.to_account_info(),
                     to: ctx.accounts.pool_token_account.to_account_info(),
                     authority: ctx.accounts.user.to_account_info(),
                 },
             ),
             total_cost,
+            ctx.accounts.milk_mint.decimals,
         )?;
+        let pool_inflow = net_of_transfer_fee(&ctx.accounts.milk_mint, total_cost)?;
+        book_pool_inflow(config, pool_inflow)?;
+        route_fee(config, FeeHookKind::BuyFee, pool_inflow)?;
 
-        config.global_cows_count = config.global_cows_count
-            .checked_add(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let genetics = roll_genetics(
+            &ctx.accounts.slot_hashes,
+            &ctx.accounts.user.key(),
+            config.global_cows_count.saturating_add(num_cows),
+        )?;
+        add_bought_cows(farm, config, num_cows, total_cost, genetics, current_time)?;
+        msg!("Purchase genetics: {} ({} bps), farm modifier: {} bps", 
+             genetics, genetics_bps(genetics), farm_genetics_bps(farm));
 
-        farm.cows = farm.cows
-            .checked_add(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
+        if let Some(referrer_farm) = ctx.accounts.referrer_farm.as_mut() {
+            require!(referrer_farm.owner == farm.referrer, ErrorCode::InvalidReferrer);
+            let share = credit_referral(referrer_farm, total_cost, config.referral_buy_bps)?;
+            if let Some(referrer_stats) = ctx.accounts.referrer_stats.as_mut() {
+                record_referral(referrer_stats, farm, total_cost, share)?;
+            }
+            msg!("Referrer {} credited {} MILK", farm.referrer, share / 1_000_000);
+        }
+        if let Some(goal) = ctx.accounts.community_goal.as_mut() {
+            record_goal_contribution(goal, farm, total_cost, current_time);
+        }
 
-        let new_tvl = ctx.accounts.pool_token_account.amount
-            .checked_add(total_cost)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        let new_reward_rate = calculate_reward_rate(config.global_cows_count, new_tvl)?;
-        farm.last_reward_rate = new_reward_rate;
+        sync_reward_accumulator(config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        let new_reward_rate = config.reward_rate;
+        if let Some(quote) = ctx.accounts.quote.as_mut() {
+            write_quote(quote, config, config.tvl)?;
+        }
+
+        emit!(BuyEvent {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            num_cows,
+            price_per_cow: cost_per_cow,
+            total_cost,
+            global_cows: config.global_cows_count,
+            reward_rate: new_reward_rate,
+            timestamp: current_time,
+        });
 
         msg!("Successfully bought {} cows. User total: {}, Global total: {}, New rate: {} MILK/cow/day", 
              num_cows, farm.cows, config.global_cows_count, new_reward_rate / 1_000_000);
         Ok(())
     }
 
-    pub fn withdraw_milk(ctx: Context<WithdrawMilk>) -> Result<()> {
-        let config = &ctx.accounts.config;
+    /// Sell cows from the farm back to the pool for MILK at a discount to the
+    /// curve price. The farm is settled first, so it keeps the rewards the
+    /// sold cows produced up to now.
+    pub fn sell_cows(ctx: Context<SellCows>, num_cows: u64) -> Result<()> {
+        process_sale(ctx.accounts, ctx.bumps.pool_authority, CowRarity::Common, num_cows)
+    }
+
+    /// Sell Rare or Epic cows back to the pool. Each pays the Common sale
+    /// price scaled by its tier's price multiplier.
+    pub fn sell_tiered_cows(ctx: Context<SellCows>, rarity: CowRarity, num_cows: u64) -> Result<()> {
+        process_sale(ctx.accounts, ctx.bumps.pool_authority, rarity, num_cows)
+    }
+
+    /// Combine `num_cows * cow_upgrade_ratio` cows of the tier below
+    /// `rarity` into `num_cows` cows of `rarity`. The consumed cows beyond
+    /// the upgraded ones leave the herd, and so the global count.
+    pub fn upgrade_cows(ctx: Context<SetFarmPreferences>, rarity: CowRarity, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(config.cow_upgrade_ratio > 0, ErrorCode::CowUpgradesDisabled);
         let farm = &mut ctx.accounts.farm;
         let current_time = Clock::get()?.unix_timestamp;
 
-        update_farm_rewards(farm, config, current_time, ctx.accounts.pool_token_account.amount)?;
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
 
-        require!(farm.accumulated_rewards > 0, ErrorCode::NoRewardsAvailable);
+        let removed = upgrade_tier_cows(farm, rarity, num_cows, config.cow_upgrade_ratio)?;
+        config.global_cows_count -= removed;
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(removed);
+        sync_reward_accumulator(config, current_time)?;
 
-        let total_rewards = farm.accumulated_rewards;
-        
-        let hours_since_last_withdraw = if farm.last_withdraw_time == 0 {
-            25 // First withdrawal - no penalty
-        } else {
-            (current_time - farm.last_withdraw_time) / 3600 // Convert to hours
-        };
-        
-        let (withdrawal_amount, penalty_amount) = if hours_since_last_withdraw >= 24 {
-            msg!("Penalty-free withdrawal: {} MILK tokens", total_rewards / 1_000_000);
-            (total_rewards, 0)
+        emit!(CowsUpgraded {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            rarity,
+            num_cows,
+            cows_consumed: removed + num_cows,
+            cows: farm.cows,
+            global_cows: config.global_cows_count,
+            timestamp: current_time,
+        });
+
+        msg!("Upgraded {} cows into {} {:?} cows. Farm: {} cows ({} Rare, {} Epic), rarity bonus {} bps", 
+             removed + num_cows, num_cows, rarity, farm.cows, farm.rare_cows, farm.epic_cows, farm_rarity_bps(farm, config));
+        Ok(())
+    }
+
+    /// Escape hatch: burn every producing cow in the farm for the emergency
+    /// exit share of their curve value, forfeiting all unclaimed rewards.
+    /// Skips reward settlement and works while the game is paused, so it
+    /// stays usable if withdrawals are halted or broken. Quarantined cows
+    /// are left to mature.
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.emergency_exit_bps > 0, ErrorCode::EmergencyExitDisabled);
+        require!(config.final_milking_started_at == 0, ErrorCode::FinalMilkingActive);
+        require!(
+            farm.rental_share_bps == 0 && farm.rental_owed == 0,
+            ErrorCode::FarmEncumbered
+        );
+        require!(farm.cows > 0, ErrorCode::InsufficientCows);
+
+        let num_cows = farm.cows;
+        // Pay what the pool can cover rather than trap the user
+        let payout = calculate_emergency_payout(&economic_params(config), config.global_cows_count, num_cows, config.emergency_exit_bps)?;
+        let payout = payable_from_pool(config, &mut ctx.accounts.pool_token_account, payout)?;
+
+        // Upgraded cows exit at the same rate as commons
+        farm.rare_cows = 0;
+        farm.epic_cows = 0;
+        take_cows_from_herd(farm, num_cows)?;
+        config.global_cows_count -= num_cows;
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(num_cows);
+
+        // Forfeited rewards were never paid out, so they simply stay in the pool
+        let forfeited_rewards = farm.accumulated_rewards + farm.locked_rewards;
+        farm.accumulated_rewards = 0;
+        farm.locked_rewards = 0;
+        farm.rewards_locked_until = 0;
+        farm.lock_boost_bps = 0;
+        farm.paused_cow_seconds = 0;
+        farm.paused_clock = paused_clock_at(config, current_time);
+        start_reward_clock(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+
+        if payout > 0 {
+            transfer_from_pool(
+                &ctx.accounts.token_program,
+                &ctx.accounts.pool_token_account,
+                ctx.accounts.user_token_account.to_account_info(),
+                &ctx.accounts.pool_authority,
+                &ctx.accounts.milk_mint,
+                &config.key(),
+                ctx.bumps.pool_authority,
+                payout,
+            )?;
+            book_pool_outflow(config, payout)?;
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(EmergencyWithdrawn {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            num_cows,
+            payout,
+            forfeited_rewards,
+            global_cows: config.global_cows_count,
+            timestamp: current_time,
+        });
+
+        msg!("Emergency exit: burned {} cows for {} MILK, forfeited {} MILK of rewards. Global total: {}", 
+             num_cows, payout / 1_000_000, forfeited_rewards / 1_000_000, config.global_cows_count);
+        Ok(())
+    }
+
+    /// Grant a new wallet a few free cows, once, creating its farm if needed.
+    /// The cows are paid for at the curve price out of the marketing vault so
+    /// the pool stays fully backed. When a gatekeeper is configured it must
+    /// co-sign, which is how allowlists or identity checks are enforced.
+    pub fn claim_starter_pack(ctx: Context<ClaimStarterPack>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+        let num_cows = config.starter_pack_cows;
+
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(num_cows > 0, ErrorCode::StarterPackDisabled);
+        if config.starter_pack_gatekeeper != Pubkey::default() {
+            require!(
+                ctx.accounts.gatekeeper.as_ref().map(|g| g.key()) == Some(config.starter_pack_gatekeeper),
+                ErrorCode::StarterPackNotApproved
+            );
+        }
+
+        if open_farm(farm, ctx.accounts.user.key(), config, current_time)? {
+            msg!("Initialized new farm for user: {}", ctx.accounts.user.key());
         } else {
-            let withdrawal = total_rewards / 2;
-            let penalty = total_rewards - withdrawal;
-            msg!("Withdrawal with 50% penalty: withdrawing {} MILK, {} MILK penalty stays in pool (last withdraw: {} hours ago)", 
-                 withdrawal / 1_000_000, penalty / 1_000_000, hours_since_last_withdraw);
-            (withdrawal, penalty)
-        };
+            update_farm_rewards(farm, config, current_time)?;
+        }
+        farm.last_interaction_time = current_time;
 
-        let pool_balance = ctx.accounts.pool_token_account.amount;
-        let withdrawal_amount = withdrawal_amount.min(pool_balance);
+        let total_cost = calculate_cow_cost(config, config.global_cows_count, num_cows, current_time)?;
+        require!(ctx.accounts.marketing_vault.amount >= total_cost, ErrorCode::MarketingBudgetExhausted);
 
         let config_key = config.key();
         let seeds = &[
-            b"pool_authority",
+            b"marketing_authority",
             config_key.as_ref(),
-            &[ctx.bumps.pool_authority],
+            &[ctx.bumps.marketing_authority],
         ];
         let signer_seeds = &[&seeds[..]];
 
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.pool_token_account.to_account_info(),
-                    to: ctx.accounts.user_token_account.to_account_info(),
-                    authority: ctx.accounts.pool_authority.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.marketing_vault.to_account_info(),
+                    mint: ctx.accounts.milk_mint.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.marketing_authority.to_account_info(),
                 },
                 signer_seeds,
             ),
-            withdrawal_amount,
+            total_cost,
+            ctx.accounts.milk_mint.decimals,
         )?;
+        book_pool_inflow(config, net_of_transfer_fee(&ctx.accounts.milk_mint, total_cost)?)?;
 
-        let new_tvl = ctx.accounts.pool_token_account.amount
-            .checked_sub(withdrawal_amount)
+        config.global_cows_count = config.global_cows_count
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.farming_cows_count = config.farming_cows_count
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.starter_packs_claimed = config.starter_packs_claimed.saturating_add(1);
+        farm.cows = farm.cows
+            .checked_add(num_cows)
             .ok_or(ErrorCode::MathOverflow)?;
-        
-        let new_reward_rate = calculate_reward_rate(config.global_cows_count, new_tvl)?;
-        farm.last_reward_rate = new_reward_rate;
 
-        farm.accumulated_rewards = 0;
-        farm.last_withdraw_time = current_time;
+        let claim = &mut ctx.accounts.claim;
+        claim.cows = num_cows;
+        claim.claimed_at = current_time;
 
-        if penalty_amount > 0 {
-            msg!("Successfully withdrew {} MILK tokens with {} MILK penalty remaining in pool. New rate: {} MILK/cow/day", 
-                 withdrawal_amount / 1_000_000, penalty_amount / 1_000_000, new_reward_rate / 1_000_000);
-        } else {
-            msg!("Successfully withdrew {} MILK tokens (penalty-free). New rate: {} MILK/cow/day", 
-                 withdrawal_amount / 1_000_000, new_reward_rate / 1_000_000);
-        }
-        
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        sync_reward_accumulator(config, current_time)?;
+
+        msg!("Starter pack of {} cows claimed by {} ({} MILK from the marketing budget)", 
+             num_cows, ctx.accounts.user.key(), total_cost / 1_000_000);
         Ok(())
     }
 
-    pub fn compound_cows(ctx: Context<CompoundCows>, num_cows: u64) -> Result<()> {
-        require!(num_cows > 0, ErrorCode::InvalidAmount);
-        
-        let config = &mut ctx.accounts.config;
-        let farm = &mut ctx.accounts.farm;
-        let current_time = Clock::get()?.unix_timestamp;
-
-        update_farm_rewards(farm, config, current_time, ctx.accounts.pool_token_account.amount)?;
-
-        let cow_price = calculate_cow_price(config.global_cows_count)?;
-        let total_cost = cow_price
-            .checked_mul(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
+    /// Withdraw `amount` of the farm's accumulated rewards. The
+    /// early-withdrawal penalty applies only to the amount withdrawn; the
+    /// rest stays on the farm.
+    pub fn withdraw_milk(ctx: Context<WithdrawMilk>, amount: u64) -> Result<()> {
+        process_withdrawal(ctx.accounts, ctx.bumps.pool_authority, amount, None)
+    }
 
+    /// Withdraw with an accounting tag carried in the withdrawal event, so
+    /// treasuries harvesting through a multisig can reconcile payouts. When
+    /// the memo program is passed the tag is also written as an SPL Memo.
+    pub fn withdraw_milk_with_memo(ctx: Context<WithdrawMilkWithMemo>, amount: u64, memo: String) -> Result<()> {
         require!(
-            farm.accumulated_rewards >= total_cost,
-            ErrorCode::InsufficientRewards
+            !memo.is_empty() && memo.len() <= MAX_WITHDRAW_MEMO_LEN,
+            ErrorCode::InvalidMemo
         );
 
-        msg!("Compounding {} cows using {} rewards (global count: {})", 
-             num_cows, total_cost, config.global_cows_count);
+        if let Some(memo_program) = &ctx.accounts.memo_program {
+            memo::build_memo(
+                CpiContext::new(memo_program.to_account_info(), BuildMemo {})
+                    .with_remaining_accounts(vec![ctx.accounts.withdraw.user.to_account_info()]),
+                memo.as_bytes(),
+            )?;
+        }
 
-        farm.accumulated_rewards = farm.accumulated_rewards
-            .checked_sub(total_cost)
-            .ok_or(ErrorCode::MathOverflow)?;
+        process_withdrawal(&mut ctx.accounts.withdraw, ctx.bumps.withdraw.pool_authority, amount, Some(memo))
+    }
 
-        config.global_cows_count = config.global_cows_count
-            .checked_add(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
+    /// Take all accumulated rewards as cows in one step: as many cows as the
+    /// rewards buy at the current price are compounded into the farm, penalty
+    /// free, and with `HarvestTarget::Tokens` exported straight to the
+    /// signer's COW account. Rewards left over stay on the farm.
+    pub fn harvest_as_cows(ctx: Context<HarvestAsCows>, target: HarvestTarget) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
 
-        farm.cows = farm.cows
-            .checked_add(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
+        update_farm_rewards(farm, config, current_time)?;
+        let num_cows = max_affordable_cows(config, config.global_cows_count, farm.accumulated_rewards, current_time)?;
+        require!(num_cows > 0, ErrorCode::InsufficientRewards);
+        if target == HarvestTarget::Farm {
+            check_barn_capacity(farm, config, num_cows)?;
+        }
 
-        let new_reward_rate = calculate_reward_rate(config.global_cows_count, ctx.accounts.pool_token_account.amount)?;
-        farm.last_reward_rate = new_reward_rate;
+        compound_farm(farm, config, current_time, num_cows)?;
+        farm.last_interaction_time = current_time;
 
-        msg!("Successfully compounded {} cows. User total: {}. Global total: {}. New rate: {} MILK/cow/day", 
-             num_cows, farm.cows, config.global_cows_count, new_reward_rate / 1_000_000);
+        if target == HarvestTarget::Tokens {
+            let token_program = ctx.accounts.token_program.as_ref().ok_or(ErrorCode::InvalidParameter)?;
+            let cow_mint = ctx.accounts.cow_mint.as_ref().ok_or(ErrorCode::InvalidParameter)?;
+            let cow_mint_authority = ctx.accounts.cow_mint_authority.as_ref().ok_or(ErrorCode::InvalidParameter)?;
+            let user_cow_token_account = ctx.accounts.user_cow_token_account.as_ref().ok_or(ErrorCode::InvalidParameter)?;
+
+            export_from_farm(farm, config, current_time, num_cows)?;
+            require_cow_backing(config, cow_mint, num_cows)?;
+            mint_cow_tokens(
+                token_program,
+                cow_mint,
+                user_cow_token_account.to_account_info(),
+                cow_mint_authority,
+                &config.key(),
+                ctx.bumps.cow_mint_authority.ok_or(ErrorCode::InvalidParameter)?,
+                num_cows,
+            )?;
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("Harvested {} cows into {:?} for farm owner: {}. Rewards left: {}", 
+             num_cows, target, farm.owner, farm.accumulated_rewards);
         Ok(())
     }
 
-    pub fn get_global_stats(ctx: Context<GetGlobalStats>) -> Result<GlobalStats> {
-        let config = &ctx.accounts.config;
-        let pool_balance = ctx.accounts.pool_token_account.amount;
-        
-        Ok(GlobalStats {
-            global_cows_count: config.global_cows_count,
-            pool_balance_milk: pool_balance,
-        })
+    /// Close a withdrawal receipt and reclaim its rent
+    pub fn close_withdrawal_receipt(ctx: Context<CloseWithdrawalReceipt>) -> Result<()> {
+        msg!("Closed withdrawal receipt {} for farm {}", ctx.accounts.receipt.sequence, ctx.accounts.receipt.farm);
+        Ok(())
     }
 
-    pub fn v3_migrating(ctx: Context<V3Migrating>) -> Result<()> {
-        let config = &ctx.accounts.config;
-        let pool_balance = ctx.accounts.pool_token_account.amount;
-        
-        require!(pool_balance > 0, ErrorCode::NoFundsToMigrate);
-        
-        msg!("V3 Migration");
-
-        let config_key = config.key();
-        let seeds = &[
-            b"pool_authority",
-            config_key.as_ref(),
-            &[ctx.bumps.pool_authority],
-        ];
-        let signer_seeds = &[&seeds[..]];
+    /// Arm (or disarm) a forgiveness pass. An armed pass is spent by the next
+    /// withdrawal that would otherwise pay the early-withdrawal penalty.
+    pub fn set_forgiveness_pass(ctx: Context<UpdateFarm>, armed: bool) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        require!(farm.forgiveness_pass_armed != armed, ErrorCode::InvalidParameter);
 
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.pool_token_account.to_account_info(),
-                    to: ctx.accounts.admin_token_account.to_account_info(),
-                    authority: ctx.accounts.pool_authority.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            pool_balance,
-        )?;
+        if armed {
+            require!(farm.forgiveness_passes > 0, ErrorCode::NoForgivenessPass);
+            farm.forgiveness_passes -= 1;
+        } else {
+            farm.forgiveness_passes = farm.forgiveness_passes.saturating_add(1);
+        }
+        farm.forgiveness_pass_armed = armed;
 
-        msg!("V3 Migration completed");
+        msg!("Forgiveness pass {} for farm owner: {}. Passes held: {}", 
+             if armed { "armed" } else { "disarmed" }, farm.owner, farm.forgiveness_passes);
         Ok(())
     }
 
-    pub fn export_cows(ctx: Context<ExportCows>, num_cows: u64) -> Result<()> {
+    /// Compound on the owner's behalf, authorized by an owner-signed message
+    /// verified through a preceding Ed25519 program instruction. The relayer
+    /// pays the transaction fees.
+    pub fn relayed_compound(ctx: Context<RelayedCompound>, num_cows: u64, nonce: u64, expiry: i64) -> Result<()> {
         require!(num_cows > 0, ErrorCode::InvalidAmount);
-        
-        let config = &ctx.accounts.config;
+
         let farm = &mut ctx.accounts.farm;
         let current_time = Clock::get()?.unix_timestamp;
 
-        // Update rewards before export (user keeps accumulated rewards)
-        update_farm_rewards(farm, config, current_time, ctx.accounts.pool_token_account.amount)?;
+        consume_relayed_authorization(
+            farm,
+            &ctx.accounts.instructions,
+            RelayedAction::Compound { num_cows },
+            nonce,
+            expiry,
+            current_time,
+        )?;
 
-        require!(farm.cows >= num_cows, ErrorCode::InsufficientCows);
+        let config = &mut ctx.accounts.config;
+        check_barn_capacity(farm, config, num_cows)?;
+        compound_farm(farm, config, current_time, num_cows)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
 
-        msg!("Exporting {} cows to COW tokens for user: {}", num_cows, ctx.accounts.user.key());
+        msg!("Relayed compound by {} for farm owner: {}", ctx.accounts.relayer.key(), farm.owner);
+        Ok(())
+    }
 
-        // Reduce cow count in farm
-        farm.cows = farm.cows
-            .checked_sub(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
+    /// Withdraw to the owner's MILK account, authorized by an owner-signed
+    /// message. Same penalty and escrow rules as withdraw_milk.
+    pub fn relayed_withdraw(ctx: Context<RelayedWithdraw>, nonce: u64, expiry: i64) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
 
-        // Mint COW tokens to user (1 cow = 1 COW token with 0 decimals)
-        // Mint COW tokens to user (1 cow = 1 COW token with 6 decimals)
-        let config_key = config.key();
-        let seeds = &[
-            b"cow_mint_authority",
-            config_key.as_ref(),
-            &[ctx.bumps.cow_mint_authority],
-        ];
-        let signer_seeds = &[&seeds[..]];
+        consume_relayed_authorization(
+            farm,
+            &ctx.accounts.instructions,
+            RelayedAction::Withdraw,
+            nonce,
+            expiry,
+            current_time,
+        )?;
 
-        token::mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.cow_mint.to_account_info(),
-                    to: ctx.accounts.user_cow_token_account.to_account_info(),
-                    authority: ctx.accounts.cow_mint_authority.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            num_cows * 1_000_000, // COW tokens have 6 decimals, so 1 cow = 1,000,000 tokens
+        let pool_balance = reloaded_pool_balance(&mut ctx.accounts.pool_token_account)?;
+        let config = &mut ctx.accounts.config;
+        let (withdrawal_amount, penalty_amount) = settle_withdrawal(
+            farm,
+            config,
+            ctx.accounts.penalty_grace.as_deref(),
+            current_time,
+            None,
+            pool_balance,
         )?;
+        farm.last_interaction_time = current_time;
 
-        msg!("Successfully exported {} cows to COW tokens. User cows remaining: {}", 
-             num_cows, farm.cows);
+        require!(
+            config.escrow_threshold == 0 || withdrawal_amount <= config.escrow_threshold,
+            ErrorCode::WithdrawalRequiresEscrow
+        );
+
+        pay_withdrawal(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.owner_token_account.to_account_info(),
+            ctx.accounts.withholding_account.as_ref(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            farm,
+            withdrawal_amount,
+            current_time,
+        )?;
+        book_pool_outflow(config, withdrawal_amount)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        if let Some(log) = ctx.accounts.harvest_log.as_mut() {
+            record_harvest(log, withdrawal_amount, penalty_amount, current_time);
+        }
+
+        msg!("Relayed withdrawal by {}: {} MILK to {} ({} MILK penalty)", 
+             ctx.accounts.relayer.key(), withdrawal_amount / 1_000_000, farm.owner, penalty_amount / 1_000_000);
         Ok(())
     }
 
-    pub fn import_cows(ctx: Context<ImportCows>, num_cows: u64) -> Result<()> {
-        require!(num_cows > 0, ErrorCode::InvalidAmount);
-        
+    /// Withdraw through the dispute window: rewards are settled now (penalty
+    /// included) but paid out only once the escrow window passes unflagged
+    pub fn request_escrowed_withdrawal(ctx: Context<RequestEscrowedWithdrawal>) -> Result<()> {
         let config = &mut ctx.accounts.config;
         let farm = &mut ctx.accounts.farm;
         let current_time = Clock::get()?.unix_timestamp;
 
-        // Initialize farm if needed
-        if farm.owner == Pubkey::default() {
-            farm.owner = ctx.accounts.user.key();
-            farm.cows = 0;
-            farm.last_update_time = current_time;
-            farm.accumulated_rewards = 0;
-            msg!("Initialized new farm for user: {}", ctx.accounts.user.key());
-        } else {
-            // Update rewards before import
-            update_farm_rewards(farm, config, current_time, ctx.accounts.pool_token_account.amount)?;
-        }
+        require!(config.escrow_threshold > 0, ErrorCode::EscrowDisabled);
 
-        msg!("Importing {} COW tokens to cows for user: {}", num_cows, ctx.accounts.user.key());
+        // Snapshot what settling moves, so a rejection can put it back
+        update_farm_rewards(farm, config, current_time)?;
+        let gross_amount = farm.accumulated_rewards;
+        let prior_withdraw_time = farm.last_withdraw_time;
+        let passes_redeemed = farm.forgiveness_passes_redeemed;
+        let grants_pending = config.grants_pending;
+        let fee_pending = config.fee_hook_pending[FeeHookKind::WithdrawalPenalty as usize];
+        let tax_pending = config.new_farmer_fund_pending;
 
-        // Burn COW tokens from user
-        token::burn(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Burn {
-                    mint: ctx.accounts.cow_mint.to_account_info(),
-                    from: ctx.accounts.user_cow_token_account.to_account_info(),
-                    authority: ctx.accounts.user.to_account_info(),
-                },
-            ),
-            num_cows * 1_000_000, // COW tokens have 6 decimals
+        let pool_balance = reloaded_pool_balance(&mut ctx.accounts.pool_token_account)?;
+        let (withdrawal_amount, penalty_amount) = settle_withdrawal(
+            farm,
+            config,
+            ctx.accounts.penalty_grace.as_deref(),
+            current_time,
+            None,
+            pool_balance,
         )?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
 
-        // Add cows to farm
-        farm.cows = farm.cows
-            .checked_add(num_cows)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        // Update global cow count
-        config.global_cows_count = config.global_cows_count
-            .checked_add(num_cows)
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.farm = farm.key();
+        escrow.owner = ctx.accounts.user.key();
+        escrow.destination = ctx.accounts.user_token_account.key();
+        escrow.amount = withdrawal_amount;
+        escrow.requested_at = current_time;
+        escrow.release_at = current_time
+            .checked_add(config.escrow_window)
             .ok_or(ErrorCode::MathOverflow)?;
+        escrow.flagged = false;
+        escrow.gross_amount = gross_amount;
+        escrow.penalty = penalty_amount;
+        escrow.tax = config.new_farmer_fund_pending - tax_pending;
+        escrow.grants_earmarked = config.grants_pending - grants_pending;
+        escrow.fee_earmarked = config.fee_hook_pending[FeeHookKind::WithdrawalPenalty as usize] - fee_pending;
+        escrow.pass_redeemed = farm.forgiveness_passes_redeemed != passes_redeemed;
+        escrow.prior_withdraw_time = prior_withdraw_time;
 
-        // Calculate new reward rate
-        let new_reward_rate = calculate_reward_rate(config.global_cows_count, ctx.accounts.pool_token_account.amount)?;
-        farm.last_reward_rate = new_reward_rate;
+        if let Some(log) = ctx.accounts.harvest_log.as_mut() {
+            record_harvest(log, withdrawal_amount, penalty_amount, current_time);
+        }
 
-        msg!("Successfully imported {} COW tokens to cows. User total cows: {}, Global total: {}", 
-             num_cows, farm.cows, config.global_cows_count);
+        msg!("Escrowed withdrawal of {} MILK ({} MILK penalty), releasable at {}", 
+             withdrawal_amount / 1_000_000, penalty_amount / 1_000_000, escrow.release_at);
         Ok(())
     }
-}
 
-/// Calculate dynamic cow price based on global cow count
-/// P(c) = 6,000 * (1 + (c / 1,500)^1.2)
-fn calculate_cow_price(global_cows: u64) -> Result<u64> {
-    if global_cows == 0 {
-        return Ok(COW_BASE_PRICE);
-    }
-
-    let c = global_cows as f64;
-    let ratio = c / PRICE_PIVOT;
-    let power_term = if ratio == 0.0 { 0.0 } else { ratio.powf(PRICE_STEEPNESS) };
-    let multiplier = 1.0 + power_term;
-    
-    let price_f64 = (COW_BASE_PRICE as f64) * multiplier;
-    
-    if price_f64 > (u64::MAX as f64) {
-        return Err(ErrorCode::MathOverflow.into());
-    }
-    
-    let price = price_f64 as u64;
-    
-    msg!("Cow price calculation: global_cows={}, ratio={:.4}, power_term={:.4}, multiplier={:.4}, price={}", 
-         global_cows, ratio, power_term, multiplier, price);
-    
-    Ok(price)
-}
+    /// Pay out an escrowed withdrawal whose window passed without a flag. Permissionless.
+    pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        let current_time = Clock::get()?.unix_timestamp;
 
-/// Calculate dynamic reward rate per cow per day
-/// R_cow = max(B / (1 + α_reward * (TVL/C) / S), R_min) * G(C)
-fn calculate_reward_rate(global_cows: u64, tvl: u64) -> Result<u64> {
-    if global_cows == 0 {
-        return Ok(MIN_REWARD_PER_DAY);
-    }
-
-    let tvl_f64 = tvl as f64;
-    let cows_f64 = global_cows as f64;
-    let tvl_per_cow = tvl_f64 / cows_f64;
-    let normalized_ratio = tvl_per_cow / TVL_NORMALIZATION;
-    
-    let denominator = 1.0 + (REWARD_SENSITIVITY * normalized_ratio);
-    let base_reward = (REWARD_BASE as f64) / denominator;
-    
-    let greed_decay = if cows_f64 == 0.0 { 1.0 } else { (-cows_f64 / GREED_DECAY_PIVOT).exp() };
-    let greed_multiplier = 1.0 + (GREED_MULTIPLIER * greed_decay);
-    
-    let reward_with_greed = base_reward * greed_multiplier;
-    let final_reward = reward_with_greed.max(MIN_REWARD_PER_DAY as f64);
-    
-    if final_reward > (u64::MAX as f64) {
-        return Err(ErrorCode::MathOverflow.into());
-    }
-    
-    let reward_rate = final_reward as u64;
-    
-    msg!("Reward calculation: cows={}, tvl={}, tvl_per_cow={:.2}, ratio={:.6}, base={:.2}, greed={:.4}, final={}", 
-         global_cows, tvl, tvl_per_cow / 1_000_000.0, normalized_ratio, 
-         base_reward / 1_000_000.0, greed_multiplier, reward_rate / 1_000_000);
-    
-    Ok(reward_rate)
-}
+        require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+        require!(!escrow.flagged, ErrorCode::EscrowFlagged);
+        require!(current_time >= escrow.release_at, ErrorCode::EscrowWindowOpen);
 
-/// Update farm rewards using the stored reward rate
-/// Only recalculates rate when triggered by buy/compound operations
-fn update_farm_rewards(
-    farm: &mut FarmAccount, 
-    config: &Config, 
-    current_time: i64,
-    current_tvl: u64
-) -> Result<()> {
-    if farm.cows > 0 && current_time > farm.last_update_time {
-        let time_elapsed = (current_time - farm.last_update_time) as u64;
-        
-        let reward_rate = if farm.last_reward_rate == 0 {
-            calculate_reward_rate(config.global_cows_count, current_tvl)?
-        } else {
-            farm.last_reward_rate
-        };
-        
-        let reward_per_cow_per_second = reward_rate / (SECONDS_PER_DAY as u64);
-        
-        let new_rewards = farm.cows
-            .checked_mul(reward_per_cow_per_second)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_mul(time_elapsed)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let amount = payable_from_pool(&ctx.accounts.config, &mut ctx.accounts.pool_token_account, escrow.amount)?;
+        pay_withdrawal(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.withholding_account.as_ref(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &ctx.accounts.config.key(),
+            ctx.bumps.pool_authority,
+            &ctx.accounts.farm,
+            amount,
+            current_time,
+        )?;
+        book_pool_outflow(&mut ctx.accounts.config, amount)?;
+        cache_pool_balance(&mut ctx.accounts.config, &mut ctx.accounts.pool_token_account)?;
 
-        if new_rewards > 0 {
-            farm.accumulated_rewards = farm.accumulated_rewards
-                .checked_add(new_rewards)
-                .ok_or(ErrorCode::MathOverflow)?;
-            
-            msg!("Updated rewards: +{} (rate: {} MILK/cow/day, time: {}s), Total: {}", 
-                 new_rewards, reward_rate / 1_000_000, time_elapsed, farm.accumulated_rewards);
-        }
+        msg!("Released escrowed withdrawal of {} MILK to {}", amount / 1_000_000, escrow.destination);
+        Ok(())
     }
-    
-    farm.last_update_time = current_time;
-    Ok(())
-}
 
-#[account]
-pub struct Config {
-    pub admin: Pubkey,                    // 32 bytes
-    pub milk_mint: Pubkey,               // 32 bytes  
-    pub cow_mint: Pubkey,                // 32 bytes
-    pub pool_token_account: Pubkey,      // 32 bytes
-    pub start_time: i64,                 // 8 bytes
-    pub global_cows_count: u64,          // 8 bytes
-    pub initial_tvl: u64,                // 8 bytes - for reference
-}
+    /// Penalty-free alternative to `withdraw_milk`: move `amount` of the
+    /// farm's rewards into its vesting stream, released linearly over the
+    /// stream period and paid out with `claim_vested`. The large-withdrawal
+    /// tax still applies, and the 24-hour penalty clock isn't touched.
+    pub fn withdraw_streamed(ctx: Context<WithdrawStreamed>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
 
-#[account]
-pub struct FarmAccount {
-    pub owner: Pubkey,               // 32 bytes
-    pub cows: u64,                   // 8 bytes
-    pub last_update_time: i64,       // 8 bytes
-    pub accumulated_rewards: u64,    // 8 bytes
-    pub last_reward_rate: u64,       // 8 bytes - MILK per cow per day
-    pub last_withdraw_time: i64,     // 8 bytes - timestamp of last withdrawal
-}
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let vesting = &mut ctx.accounts.vesting;
+        let current_time = Clock::get()?.unix_timestamp;
 
-#[derive(Accounts)]
-pub struct InitializeConfig<'info> {
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8, // discriminator + Config struct
-        seeds = [b"config"],
-        bump
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(constraint = milk_mint.decimals == 6)]
-    pub milk_mint: Account<'info, Mint>,
-
-    #[account(constraint = cow_mint.decimals == 6)]
-    pub cow_mint: Account<'info, Mint>,
-
-    #[account(
-        seeds = [b"cow_mint_authority", config.key().as_ref()],
-        bump
-    )]
-    /// CHECK: This is a PDA used as authority for COW token mint
-    pub cow_mint_authority: UncheckedAccount<'info>,
-
-    /// CHECK: Pool token account will be validated during runtime
-    pub pool_token_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-}
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(config.stream_period > 0, ErrorCode::StreamingDisabled);
 
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        require!(amount <= farm.accumulated_rewards, ErrorCode::InsufficientRewards);
 
-#[derive(Accounts)]
-pub struct BuyCows<'info> {
-    #[account(
-        mut,
-        seeds = [b"config"], 
-        bump
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + 32 + 8 + 8 + 8 + 8 + 8, // discriminator + FarmAccount struct
-        seeds = [b"farm", user.key().as_ref()],
-        bump
-    )]
-    pub farm: Account<'info, FarmAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
-        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
-    )]
-    pub user_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        mut,
-        constraint = pool_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
-        constraint = pool_token_account.owner == pool_authority.key() @ ErrorCode::InvalidOwner
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        seeds = [b"pool_authority", config.key().as_ref()],
-        bump
-    )]
-    /// CHECK: This is a PDA used as authority for token transfers
-    pub pool_authority: UncheckedAccount<'info>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+        farm.accumulated_rewards -= amount;
+        let tax = charge_withdrawal_tax(config, farm, amount, current_time)?;
+        let streamed = amount - tax;
+        farm.total_milk_withdrawn = farm.total_milk_withdrawn.saturating_add(streamed);
 
-#[derive(Accounts)]
-pub struct CompoundCows<'info> {
-    #[account(
-        mut,
-        seeds = [b"config"], 
-        bump
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(
-        mut,
-        seeds = [b"farm", user.key().as_ref()],
-        bump,
-        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
-    )]
-    pub farm: Account<'info, FarmAccount>,
-
-    #[account(
-        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-}
+        if vesting.owner == Pubkey::default() {
+            vesting.farm = farm.key();
+            vesting.owner = farm.owner;
+        }
+        top_up_stream(vesting, streamed, current_time, config.stream_period)?;
 
-#[derive(Accounts)]
-pub struct WithdrawMilk<'info> {
-    #[account(
-        seeds = [b"config"], 
-        bump
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(
-        mut,
-        seeds = [b"farm", user.key().as_ref()],
-        bump,
-        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
-    )]
-    pub farm: Account<'info, FarmAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        constraint = user_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
-        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidOwner
-    )]
-    pub user_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        mut,
-        constraint = pool_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        seeds = [b"pool_authority", config.key().as_ref()],
-        bump
-    )]
-    /// CHECK: This is a PDA used as authority for token transfers
-    pub pool_authority: UncheckedAccount<'info>,
-
-    pub token_program: Program<'info, Token>,
-}
+        emit!(MilkStreamed {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            amount: streamed,
+            tax,
+            unvested: vesting.amount,
+            ends_at: vesting.ends_at,
+            timestamp: current_time,
+        });
 
-#[derive(Accounts)]
-pub struct GetGlobalStats<'info> {
-    #[account(
-        seeds = [b"config"], 
-        bump
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(
-        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-}
+        msg!("Streaming {} MILK, vesting until {}. Left on farm: {}", 
+             streamed / 1_000_000, vesting.ends_at, farm.accumulated_rewards / 1_000_000);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct ExportCows<'info> {
-    #[account(
-        seeds = [b"config"], 
-        bump
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(
-        mut,
-        seeds = [b"farm", user.key().as_ref()],
-        bump,
-        constraint = farm.owner == user.key() @ ErrorCode::Unauthorized
-    )]
-    pub farm: Account<'info, FarmAccount>,
-
-    #[account(
-        mut,
-        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
-    )]
-    pub cow_mint: Account<'info, Mint>,
-
-    #[account(
-        seeds = [b"cow_mint_authority", config.key().as_ref()],
-        bump
-    )]
-    /// CHECK: This is a PDA used as authority for COW token mint
-    pub cow_mint_authority: UncheckedAccount<'info>,
-
-    #[account(
-        mut,
-        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
-        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
-    )]
-    pub user_cow_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    pub token_program: Program<'info, Token>,
-}
+    /// Pay out everything a vesting stream has released so far
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let vesting = &mut ctx.accounts.vesting;
+        let current_time = Clock::get()?.unix_timestamp;
 
-#[derive(Accounts)]
-pub struct ImportCows<'info> {
-    #[account(
-        mut,
-        seeds = [b"config"], 
-        bump
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + 32 + 8 + 8 + 8 + 8 + 8, // discriminator + FarmAccount struct
-        seeds = [b"farm", user.key().as_ref()],
-        bump
-    )]
-    pub farm: Account<'info, FarmAccount>,
-
-    #[account(
-        mut,
-        constraint = cow_mint.key() == config.cow_mint @ ErrorCode::InvalidCowMint
-    )]
-    pub cow_mint: Account<'info, Mint>,
-
-    #[account(
-        mut,
-        constraint = user_cow_token_account.mint == config.cow_mint @ ErrorCode::InvalidMint,
-        constraint = user_cow_token_account.owner == user.key() @ ErrorCode::InvalidOwner
-    )]
-    pub user_cow_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+        require!(!config.paused, ErrorCode::ProgramPaused);
 
-#[derive(Accounts)]
-pub struct V3Migrating<'info> {
-    #[account(
-        seeds = [b"config"], 
-        bump,
-        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized
-    )]
-    pub config: Account<'info, Config>,
-
-    #[account(mut)]
-    pub admin: Signer<'info>,
-
-    #[account(
-        mut,
-        constraint = admin_token_account.mint == config.milk_mint @ ErrorCode::InvalidMint,
-        constraint = admin_token_account.owner == admin.key() @ ErrorCode::InvalidOwner
-    )]
-    pub admin_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        mut,
-        constraint = pool_token_account.key() == config.pool_token_account @ ErrorCode::InvalidPoolAccount
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        seeds = [b"pool_authority", config.key().as_ref()],
-        bump
-    )]
-    /// CHECK: This is a PDA used as authority for token transfers
-    pub pool_authority: UncheckedAccount<'info>,
-
-    pub token_program: Program<'info, Token>,
-}
+        let amount = claimable_vested(vesting, current_time)?;
+        require!(amount > 0, ErrorCode::NothingVested);
+        require!(
+            payable_from_pool(config, &mut ctx.accounts.pool_token_account, amount)? == amount,
+            ErrorCode::InsufficientPoolBalance
+        );
 
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct GlobalStats {
-    pub global_cows_count: u64,
-    pub pool_balance_milk: u64,
-}
+        vesting.claimed = vested_in_schedule(vesting, current_time)?;
+        vesting.carried = 0;
+        vesting.total_claimed = vesting.total_claimed.saturating_add(amount);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        book_pool_outflow(config, amount)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(VestedMilkClaimed {
+            farm: vesting.farm,
+            owner: vesting.owner,
+            amount,
+            timestamp: current_time,
+        });
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Math overflow")]
-    MathOverflow,
-    #[msg("Unauthorized access")]
-    Unauthorized,
-    #[msg("Insufficient rewards to buy cows")]
-    InsufficientRewards,
-    #[msg("No rewards available for withdrawal")]
-    NoRewardsAvailable,
-    #[msg("Invalid amount - must be greater than 0")]
-    InvalidAmount,
-    #[msg("Invalid mint address")]
-    InvalidMint,
-    #[msg("Invalid token account owner")]
-    InvalidOwner,
-    #[msg("Invalid pool token account")]
-    InvalidPoolAccount,
-    #[msg("No funds available for migration")]
-    NoFundsToMigrate,
-    #[msg("Cannot buy more than 50 cows per transaction")]
-    ExceedsMaxCowsPerTransaction,
-    #[msg("Insufficient cows to export")]
-    InsufficientCows,
-    #[msg("Invalid COW mint address")]
-    InvalidCowMint,
-}
\ No newline at end of file
+        msg!("Claimed {} vested MILK, {} still vesting", 
+             amount / 1_000_000, (vesting.amount - vesting.claimed) / 1_000_000);
+        Ok(())
+    }
+
+    /// Unlock a cosmetic from the catalog once the farm's stats meet its
+    /// threshold, recording it in the wallet's profile. Seasonal cosmetics
+    /// can only be unlocked during their season. No economic effect.
+    pub fn unlock_cosmetic(ctx: Context<UnlockCosmetic>, cosmetic_id: u8) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let profile = &mut ctx.accounts.profile;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(cosmetic_id < 64, ErrorCode::UnknownCosmetic);
+        require!(profile.cosmetics & (1 << cosmetic_id) == 0, ErrorCode::CosmeticAlreadyUnlocked);
+        check_cosmetic_unlock(cosmetic_id, &ctx.accounts.farm, config, current_time)?;
+
+        profile.wallet = ctx.accounts.user.key();
+        profile.cosmetics |= 1 << cosmetic_id;
+        profile.last_unlocked_at = current_time;
+
+        emit!(CosmeticUnlocked {
+            wallet: profile.wallet,
+            cosmetic_id,
+            cosmetics: profile.cosmetics,
+            season: config.cosmetic_season,
+            timestamp: current_time,
+        });
+
+        msg!("Unlocked cosmetic {} for {}", cosmetic_id, profile.wallet);
+        Ok(())
+    }
+
+    /// Hold an escrowed withdrawal for admin review
+    pub fn flag_escrow(ctx: Context<FlagEscrow>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(current_time < escrow.release_at, ErrorCode::EscrowWindowClosed);
+        escrow.flagged = true;
+
+        msg!("Escrowed withdrawal from farm {} flagged for review", escrow.farm);
+        Ok(())
+    }
+
+    /// Resolve a flagged escrow: approve pays it out, reject returns the
+    /// amount to the farm's accumulated rewards. Approving waits out a pause.
+    pub fn resolve_escrow(ctx: Context<ResolveEscrow>, approve: bool) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        require!(escrow.flagged, ErrorCode::EscrowNotFlagged);
+
+        if approve {
+            require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+            let amount = payable_from_pool(&ctx.accounts.config, &mut ctx.accounts.pool_token_account, escrow.amount)?;
+            pay_withdrawal(
+                &ctx.accounts.token_program,
+                &ctx.accounts.pool_token_account,
+                ctx.accounts.destination.to_account_info(),
+                ctx.accounts.withholding_account.as_ref(),
+                &ctx.accounts.pool_authority,
+                &ctx.accounts.milk_mint,
+                &ctx.accounts.config.key(),
+                ctx.bumps.pool_authority,
+                &ctx.accounts.farm,
+                amount,
+                Clock::get()?.unix_timestamp,
+            )?;
+            book_pool_outflow(&mut ctx.accounts.config, amount)?;
+            cache_pool_balance(&mut ctx.accounts.config, &mut ctx.accounts.pool_token_account)?;
+            msg!("Approved flagged withdrawal of {} MILK to {}", amount / 1_000_000, escrow.destination);
+        } else {
+            let config = &mut ctx.accounts.config;
+            let farm = &mut ctx.accounts.farm;
+            unsettle_escrowed_withdrawal(farm, config, escrow)?;
+            msg!("Rejected flagged withdrawal, {} MILK returned to farm {}", escrow.gross_amount / 1_000_000, escrow.farm);
+        }
+
+        Ok(())
+    }
+
+    pub fn compound_cows(ctx: Context<CompoundCows>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+        
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+        let cost_basis_before = farm.cost_basis;
+
+        check_barn_capacity(farm, config, num_cows)?;
+        compound_farm(farm, config, current_time, num_cows)?;
+        farm.last_interaction_time = current_time;
+        let volume = farm.cost_basis - cost_basis_before;
+
+        // Compounds earn referrers a smaller share than fresh buys
+        if let Some(referrer_farm) = ctx.accounts.referrer_farm.as_mut() {
+            require!(referrer_farm.owner == farm.referrer, ErrorCode::InvalidReferrer);
+            let share = credit_referral(referrer_farm, volume, config.referral_compound_bps)?;
+            if let Some(referrer_stats) = ctx.accounts.referrer_stats.as_mut() {
+                record_referral(referrer_stats, farm, volume, share)?;
+            }
+            msg!("Referrer {} credited {} MILK", farm.referrer, share / 1_000_000);
+        }
+        if let Some(goal) = ctx.accounts.community_goal.as_mut() {
+            record_goal_contribution(goal, farm, volume, current_time);
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        if let Some(quote) = ctx.accounts.quote.as_mut() {
+            write_quote(quote, config, config.tvl)?;
+        }
+        Ok(())
+    }
+
+    /// Compound on behalf of an opted-in farm from a whitelisted automation program
+    pub fn automated_compound(ctx: Context<AutomatedFarm>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+        require_automation_caller(&ctx.accounts.config, &ctx.accounts.instructions)?;
+        require!(ctx.accounts.farm.automation_opt_in, ErrorCode::AutomationNotEnabled);
+        require!(ctx.accounts.farm.preferences & FARM_PREF_AUTO_REINVEST != 0, ErrorCode::PreferenceNotSet);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        msg!("Automated compound for farm owner: {}", farm.owner);
+        check_barn_capacity(farm, config, num_cows)?;
+        compound_farm(farm, config, current_time, num_cows)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        Ok(())
+    }
+
+    /// Settle rewards on an opted-in farm from a whitelisted automation program
+    pub fn automated_poke(ctx: Context<AutomatedFarm>) -> Result<()> {
+        require_automation_caller(&ctx.accounts.config, &ctx.accounts.instructions)?;
+        require!(ctx.accounts.farm.automation_opt_in, ErrorCode::AutomationNotEnabled);
+        require!(ctx.accounts.farm.preferences & FARM_PREF_KEEPER_POKES != 0, ErrorCode::PreferenceNotSet);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("Automated poke for farm owner: {}. Accumulated rewards: {}", farm.owner, farm.accumulated_rewards);
+        Ok(())
+    }
+
+    /// Master switch for whitelisted automation. Enabling it also opts in to
+    /// keeper pokes and auto-reinvest; use set_farm_preferences to narrow.
+    pub fn set_automation_opt_in(ctx: Context<UpdateFarm>, enabled: bool) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        farm.automation_opt_in = enabled;
+        if enabled {
+            farm.preferences |= FARM_PREF_KEEPER_POKES | FARM_PREF_AUTO_REINVEST;
+        }
+
+        msg!("Automation {} for farm owner: {}", if enabled { "enabled" } else { "disabled" }, farm.owner);
+        Ok(())
+    }
+
+    /// Choose which third-party actions the farm accepts: keeper pokes,
+    /// auto-reinvest and boost-event participation (FARM_PREF_* bits).
+    /// The farm is settled first, so a change to boost-event participation
+    /// only applies from now on.
+    pub fn set_farm_preferences(ctx: Context<SetFarmPreferences>, preferences: u8) -> Result<()> {
+        require!(preferences & !FARM_PREF_ALL == 0, ErrorCode::InvalidPreferences);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        farm.preferences = preferences;
+
+        msg!("Preferences for farm owner {}: keeper pokes {}, auto-reinvest {}, boost events {}, premium renewal {}", 
+             farm.owner,
+             preferences & FARM_PREF_KEEPER_POKES != 0,
+             preferences & FARM_PREF_AUTO_REINVEST != 0,
+             preferences & FARM_PREF_BOOST_EVENTS != 0,
+             preferences & FARM_PREF_PREMIUM_RENEW != 0);
+        Ok(())
+    }
+
+    /// Pay one Premium Dairy period from the farm's rewards: a raised buy
+    /// cap, a partly waived early-withdrawal penalty and a yield bonus.
+    /// Paying while subscribed extends the current period. With
+    /// FARM_PREF_PREMIUM_RENEW set the subscription renews itself on the
+    /// farm's first interaction after it lapses.
+    pub fn subscribe_premium(ctx: Context<SetFarmPreferences>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        charge_premium(farm, config, current_time)?;
+
+        msg!("Premium Dairy for {} until {} ({} MILK)", 
+             farm.owner, farm.premium_until, config.premium_fee / 1_000_000);
+        Ok(())
+    }
+
+    /// Lock all of the farm's accumulated rewards for one of the
+    /// LOCK_DURATIONS in exchange for that term's reward boost until the
+    /// lock ends. `unlock_rewards` returns them once it has.
+    pub fn lock_rewards(ctx: Context<SetFarmPreferences>, duration: i64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let tier = LOCK_DURATIONS
+            .iter()
+            .position(|d| *d == duration)
+            .ok_or(ErrorCode::InvalidLockDuration)?;
+        let boost_bps = config.lock_boost_bps[tier];
+        require!(boost_bps > 0, ErrorCode::LockBoostDisabled);
+        require!(farm.locked_rewards == 0, ErrorCode::RewardsLocked);
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        require!(farm.accumulated_rewards > 0, ErrorCode::NoRewardsAvailable);
+
+        farm.locked_rewards = farm.accumulated_rewards;
+        farm.accumulated_rewards = 0;
+        farm.rewards_locked_until = current_time
+            .checked_add(duration)
+            .ok_or(ErrorCode::MathOverflow)?;
+        farm.lock_boost_bps = boost_bps;
+
+        emit!(RewardsLocked {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            amount: farm.locked_rewards,
+            boost_bps,
+            locked_until: farm.rewards_locked_until,
+            timestamp: current_time,
+        });
+
+        msg!("Locked {} MILK until {} for a +{} bps boost", 
+             farm.locked_rewards / 1_000_000, farm.rewards_locked_until, boost_bps);
+        Ok(())
+    }
+
+    /// Return locked rewards to the farm's accumulated rewards once the lock
+    /// has ended
+    pub fn unlock_rewards(ctx: Context<SetFarmPreferences>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(farm.locked_rewards > 0, ErrorCode::NoLockedRewards);
+        require!(current_time >= farm.rewards_locked_until, ErrorCode::RewardsLocked);
+
+        // Settle first so the boost is paid up to the end of the lock
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let amount = farm.locked_rewards;
+        farm.accumulated_rewards = farm.accumulated_rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        farm.locked_rewards = 0;
+        farm.lock_boost_bps = 0;
+
+        emit!(RewardsUnlocked {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            amount,
+            timestamp: current_time,
+        });
+
+        msg!("Unlocked {} MILK. Accumulated rewards: {}", 
+             amount / 1_000_000, farm.accumulated_rewards / 1_000_000);
+        Ok(())
+    }
+
+    /// Withhold `bps` of every `withdraw_milk` payout into a second MILK
+    /// account the owner designates, such as a tax-savings wallet. The split
+    /// happens in the same transaction as the withdrawal. 0 turns it off.
+    pub fn set_withholding(ctx: Context<SetWithholding>, bps: u16) -> Result<()> {
+        require!(bps <= MAX_WITHHOLDING_BPS, ErrorCode::InvalidParameter);
+
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        farm.withholding_account = if bps > 0 {
+            ctx.accounts.withholding_account
+                .as_ref()
+                .ok_or(ErrorCode::WithholdingAccountRequired)?
+                .key()
+        } else {
+            Pubkey::default()
+        };
+        farm.withholding_bps = bps;
+
+        emit!(WithholdingSet {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            account: farm.withholding_account,
+            bps,
+            timestamp: current_time,
+        });
+
+        msg!("Withholding for farm owner {}: {} bps to {}", farm.owner, bps, farm.withholding_account);
+        Ok(())
+    }
+
+    /// Burn MILK from the signer's wallet to raise the farm's barn one level,
+    /// adding `barn_capacity_per_level` to the cows it can hold. Each level
+    /// costs `barn_upgrade_cost` more than the last.
+    pub fn upgrade_barn(ctx: Context<UpgradeBarn>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(config.barn_base_capacity > 0, ErrorCode::InvalidParameter);
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(farm.barn_level < MAX_BARN_LEVEL, ErrorCode::BarnMaxLevel);
+
+        let cost = barn_upgrade_cost(farm, config)?;
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.milk_mint.to_account_info(),
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            cost,
+        )?;
+        farm.barn_level += 1;
+        farm.last_interaction_time = current_time;
+
+        let capacity = barn_capacity(farm, config);
+        emit!(BarnUpgraded {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            barn_level: farm.barn_level,
+            capacity,
+            milk_burned: cost,
+            timestamp: current_time,
+        });
+
+        msg!("Barn upgraded to level {} for {} MILK. Capacity: {} cows", 
+             farm.barn_level, cost / 1_000_000, capacity);
+        Ok(())
+    }
+
+    /// Put two Common cows to breeding for `breeding_fee` MILK, paid into
+    /// the pool. The parents leave the herd now; the client must request
+    /// ORAO VRF randomness for `seed` in the same transaction, and
+    /// `fulfill_breeding` brings in the calves once it is fulfilled.
+    pub fn breed_cows(ctx: Context<BreedCows>, seed: [u8; 32]) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(config.breeding_odds.iter().any(|odds| *odds > 0), ErrorCode::BreedingDisabled);
+        require_phase_allows(config, PHASE_BLOCK_COMPOUND)?;
+        // Committing to randomness that's already known would let the
+        // breeder pick their outcome
+        require!(
+            vrf::fulfilled_randomness(&ctx.accounts.randomness, &seed)?.is_none(),
+            ErrorCode::InvalidRandomness
+        );
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+
+        let (genetics_bps_sum, cost_basis) = take_breeding_parents(farm)?;
+        config.global_cows_count -= BREEDING_PARENTS;
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(BREEDING_PARENTS);
+
+        let fee = config.breeding_fee;
+        if fee > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.user_token_account.to_account_info(),
+                        mint: ctx.accounts.milk_mint.to_account_info(),
+                        to: ctx.accounts.pool_token_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                fee,
+                ctx.accounts.milk_mint.decimals,
+            )?;
+            book_pool_inflow(config, net_of_transfer_fee(&ctx.accounts.milk_mint, fee)?)?;
+            farm.total_milk_spent = farm.total_milk_spent.saturating_add(fee);
+        }
+        sync_reward_accumulator(config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let breeding = &mut ctx.accounts.breeding;
+        breeding.farm = farm.key();
+        breeding.owner = farm.owner;
+        breeding.seed = seed;
+        breeding.genetics_bps_sum = genetics_bps_sum;
+        breeding.cost_basis = cost_basis.saturating_add(fee);
+        breeding.requested_at = current_time;
+
+        emit!(BreedingRequested {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            seed,
+            fee,
+            cows: farm.cows,
+            timestamp: current_time,
+        });
+
+        msg!("Breeding 2 cows for {} MILK, waiting on VRF. Farm: {} cows", fee / 1_000_000, farm.cows);
+        Ok(())
+    }
+
+    /// VRF callback: once the breeding's randomness is fulfilled, roll the
+    /// outcome against the configured odds and add the calves to the farm.
+    /// Permissionless, so a crank can complete breedings.
+    pub fn fulfill_breeding(ctx: Context<FulfillBreeding>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        let breeding = &ctx.accounts.breeding;
+        let randomness = vrf::fulfilled_randomness(&ctx.accounts.randomness, &breeding.seed)?
+            .ok_or(ErrorCode::RandomnessNotFulfilled)?;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let outcome = roll_breeding(config, &randomness);
+        let calves = breeding_calves(farm, config, outcome);
+        add_calves(farm, config, breeding, calves, outcome == BreedingOutcome::RareCalf)?;
+        sync_reward_accumulator(config, current_time)?;
+
+        emit!(CowsBred {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            outcome,
+            calves,
+            cows: farm.cows,
+            global_cows: config.global_cows_count,
+            timestamp: current_time,
+        });
+
+        msg!("Breeding outcome {:?}: {} calves. Farm: {} cows ({} Rare)", 
+             outcome, calves, farm.cows, farm.rare_cows);
+        Ok(())
+    }
+
+    /// Return the parents of a breeding whose randomness never arrived.
+    /// Only after `BREEDING_TIMEOUT`, and only while still unfulfilled so a
+    /// known bad roll can't be backed out of. The fee isn't refunded.
+    pub fn cancel_breeding(ctx: Context<CancelBreeding>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let breeding = &ctx.accounts.breeding;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time >= breeding.requested_at.saturating_add(BREEDING_TIMEOUT),
+            ErrorCode::BreedingPending
+        );
+        require!(
+            vrf::fulfilled_randomness(&ctx.accounts.randomness, &breeding.seed)?.is_none(),
+            ErrorCode::InvalidRandomness
+        );
+        let farm = &mut ctx.accounts.farm;
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        add_calves(farm, config, breeding, BREEDING_PARENTS, false)?;
+        sync_reward_accumulator(config, current_time)?;
+
+        emit!(BreedingCancelled {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            cows: farm.cows,
+            timestamp: current_time,
+        });
+
+        msg!("Breeding cancelled, parents returned. Farm: {} cows", farm.cows);
+        Ok(())
+    }
+
+    /// Feed the farm's cows, paying `feed_cost_per_cow` MILK per producing
+    /// cow into the pool. The farm is settled first, so any hunger up to now
+    /// still counts, then produces in full for another feeding period.
+    pub fn feed_cows(ctx: Context<FeedCows>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(config.feed_interval > 0, ErrorCode::FeedingDisabled);
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+
+        let cost = config.feed_cost_per_cow
+            .checked_mul(farm.cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if cost > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.user_token_account.to_account_info(),
+                        mint: ctx.accounts.milk_mint.to_account_info(),
+                        to: ctx.accounts.pool_token_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                cost,
+                ctx.accounts.milk_mint.decimals,
+            )?;
+            book_pool_inflow(config, net_of_transfer_fee(&ctx.accounts.milk_mint, cost)?)?;
+        }
+        farm.last_fed_at = current_time;
+        sync_reward_accumulator(config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let next_feed_due = current_time.saturating_add(config.feed_interval);
+        emit!(CowsFed {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            cows: farm.cows,
+            cost,
+            next_feed_due,
+            timestamp: current_time,
+        });
+
+        msg!("Fed {} cows for {} MILK. Next feeding due at {}", 
+             farm.cows, cost / 1_000_000, next_feed_due);
+        Ok(())
+    }
+
+    /// Attach a custodian's sub-account tag (e.g. a hash of an exchange's
+    /// internal user id) to the farm. It is carried in every event the farm
+    /// emits for per-customer reconciliation. Set once, normally in the same
+    /// transaction as the farm's first purchase.
+    pub fn set_farm_tag(ctx: Context<UpdateFarm>, tag: [u8; 32]) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        require!(farm.tag == [0u8; 32], ErrorCode::FarmTagAlreadySet);
+        require!(tag != [0u8; 32], ErrorCode::InvalidParameter);
+        farm.tag = tag;
+
+        msg!("Tagged farm for owner: {}", farm.owner);
+        Ok(())
+    }
+
+    /// Close an empty farm and return its rent to the owner. The farm is
+    /// settled first and must then hold no cows (producing or quarantined)
+    /// and no rewards, and owe nothing to a manager or yield claim holders.
+    pub fn close_farm(ctx: Context<CloseFarm>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        require!(
+            farm.cows == 0 && farm.pending_cows == 0 && farm.accumulated_rewards == 0 && farm.locked_rewards == 0,
+            ErrorCode::FarmNotEmpty
+        );
+        require!(
+            farm.rental_share_bps == 0 && farm.rental_owed == 0,
+            ErrorCode::FarmEncumbered
+        );
+        require!(
+            farm.yield_claim_until <= current_time && farm.yield_claim_owed == 0,
+            ErrorCode::FarmEncumbered
+        );
+
+        msg!("Closed farm of {}", farm.owner);
+        Ok(())
+    }
+
+    /// Grow the Config to the current layout and fill in what the fields
+    /// added since its version need, with the admin topping up rent. Run
+    /// once after an upgrade that changes the layout: until then the Config
+    /// doesn't deserialize and every other instruction fails.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let config_info = ctx.accounts.config.to_account_info();
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(data.starts_with(Config::DISCRIMINATOR), ErrorCode::InvalidParameter);
+            // The admin key leads every Config layout
+            require!(data.get(8..40) == Some(ctx.accounts.admin.key().as_ref()), ErrorCode::Unauthorized);
+        }
+
+        let old_len = config_info.data_len();
+        let new_len = 8 + Config::INIT_SPACE;
+        if old_len < new_len {
+            let rent_due = Rent::get()?
+                .minimum_balance(new_len)
+                .saturating_sub(config_info.lamports());
+            if rent_due > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.admin.to_account_info(),
+                            to: config_info.clone(),
+                        },
+                    ),
+                    rent_due,
+                )?;
+            }
+            config_info.resize(new_len)?;
+        }
+
+        let mut data = config_info.try_borrow_mut_data()?;
+        let mut config = Config::try_deserialize(&mut &data[..])?;
+        require!(config.layout_version < CONFIG_LAYOUT_VERSION, ErrorCode::ConfigAlreadyMigrated);
+        require!(ctx.accounts.pool_token_account.key() == config.pool_token_account, ErrorCode::InvalidPoolAccount);
+        require!(ctx.accounts.milk_mint.key() == config.milk_mint, ErrorCode::InvalidMint);
+        require!(ctx.accounts.cow_mint.key() == config.cow_mint, ErrorCode::InvalidMint);
+
+        let old_version = config.layout_version;
+        if old_version == 0 {
+            upgrade_launch_config(
+                &mut config,
+                ctx.accounts.pool_token_account.amount,
+                ctx.accounts.cow_mint.supply,
+                Option::<Pubkey>::from(ctx.accounts.milk_mint.mint_authority).unwrap_or_default(),
+                Option::<Pubkey>::from(ctx.accounts.milk_mint.freeze_authority).unwrap_or_default(),
+                Clock::get()?.unix_timestamp,
+            )?;
+        }
+        config.layout_version = CONFIG_LAYOUT_VERSION;
+
+        let mut writer: &mut [u8] = &mut data[..];
+        config.try_serialize(&mut writer)?;
+
+        msg!("Migrated config from layout {} ({} bytes) to {} ({} bytes)", 
+             old_version, old_len, CONFIG_LAYOUT_VERSION, new_len);
+        Ok(())
+    }
+
+    /// Grow a farm created under an older, shorter FarmAccount layout to the
+    /// current size, with the payer topping up rent. Fields added since read
+    /// as zero, so lifetime stats count from the migration on and
+    /// farm_created_at stays 0. A farm from before the reward accumulator
+    /// has its rewards settled at its old rate and joins the accumulator
+    /// from now. Permissionless.
+    pub fn migrate_farm(ctx: Context<MigrateFarm>) -> Result<()> {
+        let farm = ctx.accounts.farm.to_account_info();
+        require!(
+            farm.try_borrow_data()?.starts_with(FarmAccount::DISCRIMINATOR),
+            ErrorCode::InvalidParameter
+        );
+
+        let old_len = farm.data_len();
+        let new_len = 8 + FarmAccount::INIT_SPACE;
+        require!(old_len < new_len, ErrorCode::FarmAlreadyMigrated);
+
+        let rent_due = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(farm.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: farm.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        farm.resize(new_len)?;
+
+        // Farms from before the accumulator settle at their old rate and
+        // start their reward_debt here, or they'd be credited from launch
+        if old_len < FARM_ACCUMULATOR_LEN {
+            let mut data = farm.try_borrow_mut_data()?;
+            let mut state = FarmAccount::try_deserialize(&mut &data[..])?;
+            let earned = adopt_reward_accumulator(&mut state, &mut ctx.accounts.config, Clock::get()?.unix_timestamp)?;
+            let mut writer: &mut [u8] = &mut data[..];
+            state.try_serialize(&mut writer)?;
+            msg!("Settled {} MILK of legacy rewards", earned / 1_000_000);
+        }
+
+        msg!("Migrated farm {} from {} to {} bytes", farm.key(), old_len, new_len);
+        Ok(())
+    }
+
+    pub fn add_automation_program(ctx: Context<AdminConfig>, program_id: Pubkey) -> Result<()> {
+        require!(program_id != Pubkey::default() && program_id != crate::ID, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        require!(!config.automation_programs.contains(&program_id), ErrorCode::InvalidParameter);
+
+        let slot = config.automation_programs
+            .iter_mut()
+            .find(|p| **p == Pubkey::default())
+            .ok_or(ErrorCode::AutomationRegistryFull)?;
+        *slot = program_id;
+
+        record_param_change(
+            config,
+            ConfigParam::AutomationProgram,
+            ParamValue::Key(Pubkey::default()),
+            ParamValue::Key(program_id),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Whitelisted automation program: {}", program_id);
+        Ok(())
+    }
+
+    pub fn remove_automation_program(ctx: Context<AdminConfig>, program_id: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        let slot = config.automation_programs
+            .iter_mut()
+            .find(|p| **p == program_id && program_id != Pubkey::default())
+            .ok_or(ErrorCode::AutomationNotWhitelisted)?;
+        *slot = Pubkey::default();
+
+        record_param_change(
+            config,
+            ConfigParam::AutomationProgram,
+            ParamValue::Key(program_id),
+            ParamValue::Key(Pubkey::default()),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Removed automation program: {}", program_id);
+        Ok(())
+    }
+
+    /// Create the quote account. Anyone may pay for it; it only ever holds
+    /// public state.
+    pub fn init_quote(ctx: Context<InitQuote>) -> Result<()> {
+        let quote = &mut ctx.accounts.quote;
+        quote.slot = 0;
+        quote.updated_at = 0;
+
+        msg!("Quote account created");
+        Ok(())
+    }
+
+    /// Reset the booked TVL to the pool's actual balance, picking up MILK
+    /// sent to the pool directly. Configs created before TVL was booked
+    /// need this once. Permissionless.
+    pub fn sync_tvl(ctx: Context<SyncTvl>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_tvl = config.tvl;
+        config.tvl = ctx.accounts.pool_token_account.amount;
+        sync_reward_accumulator(config, Clock::get()?.unix_timestamp)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("TVL synced from {} to {} MILK", old_tvl / 1_000_000, config.tvl / 1_000_000);
+        Ok(())
+    }
+
+    /// Rewrite the quote account from Config and the booked pool balance.
+    /// Permissionless, so keepers can crank it every slot; buys, compounds
+    /// and withdrawals that pass the account refresh it too.
+    pub fn refresh_quote(ctx: Context<RefreshQuote>) -> Result<()> {
+        let quote = &mut ctx.accounts.quote;
+        write_quote(quote, &ctx.accounts.config, ctx.accounts.config.tvl)?;
+
+        msg!("Quote refreshed at slot {}: price {}, rate {} MILK/cow/day", 
+             quote.slot, quote.cow_price, quote.reward_rate / 1_000_000);
+        Ok(())
+    }
+
+    /// Start recording the signer's withdrawals on-chain. Withdrawals that
+    /// pass the log account append to it; the last HARVEST_LOG_LEN are kept.
+    pub fn init_harvest_log(ctx: Context<InitHarvestLog>) -> Result<()> {
+        let log = &mut ctx.accounts.harvest_log;
+        log.farm = ctx.accounts.farm.key();
+        log.total_harvests = 0;
+
+        msg!("Harvest log created for farm owner: {}", ctx.accounts.farm.owner);
+        Ok(())
+    }
+
+    /// Publish a read-alias listing every account the signer's position
+    /// spans, so portfolio trackers can find them without knowing each
+    /// feature's PDA seeds. The farm need not exist yet.
+    pub fn register_portfolio(ctx: Context<RegisterPortfolio>) -> Result<()> {
+        let wallet = ctx.accounts.wallet.key();
+        let (farm, _) = Pubkey::find_program_address(&[b"farm", wallet.as_ref()], &crate::ID);
+        let address = |seed: &[u8]| Pubkey::find_program_address(&[seed, farm.as_ref()], &crate::ID).0;
+
+        let portfolio = &mut ctx.accounts.portfolio;
+        portfolio.wallet = wallet;
+        portfolio.farm = farm;
+        portfolio.loan_position = address(b"loan");
+        portfolio.harvest_log = address(b"harvest_log");
+        portfolio.escrow = address(b"escrow");
+        portfolio.rental = address(b"rental");
+        snapshot_portfolio(portfolio, ctx.accounts.farm.as_deref(), None)?;
+
+        msg!("Portfolio registered for {}: farm {}", wallet, farm);
+        Ok(())
+    }
+
+    /// Refresh a portfolio's snapshot from its farm and loan. Rewards are as
+    /// of the farm's last settlement. Permissionless.
+    pub fn refresh_portfolio(ctx: Context<RefreshPortfolio>) -> Result<()> {
+        let portfolio = &mut ctx.accounts.portfolio;
+        snapshot_portfolio(
+            portfolio,
+            Some(&ctx.accounts.farm),
+            ctx.accounts.loan_position.as_deref(),
+        )?;
+
+        msg!("Portfolio refreshed for {}: {} cows, {} MILK unclaimed",
+             portfolio.wallet, portfolio.cows, portfolio.accumulated_rewards / 1_000_000);
+        Ok(())
+    }
+
+    /// Settle the signer's farm and freeze it, with the global context it was
+    /// priced against, into a checkpoint account that can never be changed
+    /// or closed. Serves as evidence of a historical position for audits,
+    /// disputes and other programs.
+    pub fn export_farm_checkpoint(ctx: Context<ExportFarmCheckpoint>, checkpoint_id: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let clock = Clock::get()?;
+
+        update_farm_rewards(farm, config, clock.unix_timestamp)?;
+        farm.last_interaction_time = clock.unix_timestamp;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let checkpoint = &mut ctx.accounts.checkpoint;
+        checkpoint.farm = farm.key();
+        checkpoint.checkpoint_id = checkpoint_id;
+        checkpoint.slot = clock.slot;
+        checkpoint.timestamp = clock.unix_timestamp;
+        checkpoint.state = (**farm).clone();
+        checkpoint.global_cows_count = config.global_cows_count;
+        checkpoint.farming_cows_count = config.farming_cows_count;
+        checkpoint.tokenized_cows_count = config.tokenized_cows_count;
+        checkpoint.pool_balance = config.tvl;
+        checkpoint.cow_price = calculate_cow_price(config, config.global_cows_count, clock.unix_timestamp)?;
+        checkpoint.reward_rate = calculate_reward_rate(config, config.global_cows_count, config.tvl, clock.unix_timestamp)?;
+        checkpoint.param_change_count = config.param_change_count;
+        checkpoint.economic_params = economic_params(config);
+
+        emit!(FarmCheckpointed {
+            farm: farm.key(),
+            tag: farm.tag,
+            checkpoint: checkpoint.key(),
+            checkpoint_id,
+            cows: farm.cows,
+            accumulated_rewards: farm.accumulated_rewards,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Checkpoint {} for farm owner {}: {} cows, {} MILK unclaimed", 
+             checkpoint_id, farm.owner, farm.cows, farm.accumulated_rewards / 1_000_000);
+        Ok(())
+    }
+
+    /// Register the signer as a referrer. New farms can only name a
+    /// registered referrer, whose stats then track the farms it brought in.
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.owner = ctx.accounts.user.key();
+        referrer.registered_at = Clock::get()?.unix_timestamp;
+
+        msg!("Registered referrer: {}", referrer.owner);
+        Ok(())
+    }
+
+    /// Write a short-lived, program-owned summary of the signer's farm that
+    /// other programs can read as proof of position size
+    pub fn attest_farm(ctx: Context<AttestFarm>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let clock = Clock::get()?;
+        let current_time = clock.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.farm = farm.key();
+        attestation.owner = farm.owner;
+        attestation.cows = farm.cows;
+        attestation.pending_cows = farm.pending_cows;
+        attestation.pending_rewards = farm.accumulated_rewards;
+        attestation.cost_basis = farm.cost_basis;
+        attestation.slot = clock.slot;
+        attestation.attested_at = current_time;
+        attestation.expires_at = current_time
+            .checked_add(ATTESTATION_TTL)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        msg!("Attested farm {}: {} cows, {} pending rewards, cost basis {}, valid until {}", 
+             attestation.farm, attestation.cows, attestation.pending_rewards, 
+             attestation.cost_basis, attestation.expires_at);
+        Ok(())
+    }
+
+    /// Move cows out of the signer's farm into their loan position as collateral.
+    /// Collateral cows stop producing until withdrawn.
+    pub fn deposit_collateral(ctx: Context<DepositCollateral>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let position = &mut ctx.accounts.position;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.loan_ltv_bps > 0, ErrorCode::LendingDisabled);
+
+        if position.owner == Pubkey::default() {
+            position.owner = ctx.accounts.user.key();
+            position.farm = farm.key();
+            position.opened_at = current_time;
+            position.last_accrual_time = current_time;
+        }
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        accrue_loan_interest(position, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        take_cows_from_herd(farm, num_cows)?;
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(num_cows);
+        config.collateral_cows_count = config.collateral_cows_count
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        position.collateral_cows = position.collateral_cows
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        msg!("Deposited {} cows as collateral. Position collateral: {}, debt: {}", 
+             num_cows, position.collateral_cows, position.debt);
+        Ok(())
+    }
+
+    /// Borrow MILK from the pool against locked cows, up to the configured LTV
+    pub fn borrow_milk(ctx: Context<BorrowMilk>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let position = &mut ctx.accounts.position;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(config.loan_ltv_bps > 0, ErrorCode::LendingDisabled);
+        accrue_loan_interest(position, config, current_time)?;
+
+        let new_debt = position.debt
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            new_debt <= max_debt_at(config, position.collateral_cows, config.loan_ltv_bps)?,
+            ErrorCode::ExceedsLoanToValue
+        );
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        book_pool_outflow(config, amount)?;
+
+        position.debt = new_debt;
+        config.total_loan_principal = config.total_loan_principal
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("Borrowed {} MILK against {} cows. Debt: {}", 
+             amount / 1_000_000, position.collateral_cows, position.debt);
+        Ok(())
+    }
+
+    /// Repay loan debt (interest first, then principal) back into the pool
+    pub fn repay_loan(ctx: Context<RepayLoan>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let position = &mut ctx.accounts.position;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        accrue_loan_interest(position, config, current_time)?;
+
+        let repay_amount = amount.min(position.debt);
+        require!(repay_amount > 0, ErrorCode::NoOutstandingDebt);
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.milk_mint.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            repay_amount,
+            ctx.accounts.milk_mint.decimals,
+        )?;
+        // Only what reaches the pool after any transfer fee pays down the debt
+        let received = net_of_transfer_fee(&ctx.accounts.milk_mint, repay_amount)?;
+        book_pool_inflow(config, received)?;
+
+        position.debt -= received;
+        config.total_loan_principal = config.total_loan_principal.saturating_sub(received);
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("Repaid {} MILK. Remaining debt: {}", received / 1_000_000, position.debt);
+        Ok(())
+    }
+
+    /// Return collateral cows to the farm, as long as the remaining collateral
+    /// still covers the debt at the configured LTV
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let position = &mut ctx.accounts.position;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(position.collateral_cows >= num_cows, ErrorCode::InsufficientCows);
+        accrue_loan_interest(position, config, current_time)?;
+
+        let remaining = position.collateral_cows - num_cows;
+        require!(
+            position.debt <= max_debt_at(config, remaining, config.loan_ltv_bps)?,
+            ErrorCode::ExceedsLoanToValue
+        );
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        position.collateral_cows = remaining;
+        config.collateral_cows_count = config.collateral_cows_count.saturating_sub(num_cows);
+        config.farming_cows_count = config.farming_cows_count
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        farm.cows = farm.cows
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        msg!("Withdrew {} collateral cows back to farm. Position collateral: {}, debt: {}", 
+             num_cows, position.collateral_cows, position.debt);
+        Ok(())
+    }
+
+    /// Liquidation crank: once debt passes the liquidation threshold the
+    /// collateral is sold back to the curve at the sell price, the debt is cleared from the
+    /// proceeds and the caller is paid a bonus out of the surplus. Whatever
+    /// is left is credited to the owner's farm. Permissionless.
+    pub fn liquidate_position(ctx: Context<LiquidatePosition>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let position = &mut ctx.accounts.position;
+        let owner_farm = &mut ctx.accounts.owner_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+        let config_key = config.key();
+
+        accrue_loan_interest(position, config, current_time)?;
+
+        require!(position.debt > 0, ErrorCode::NoOutstandingDebt);
+        require!(
+            position.debt > max_debt_at(config, position.collateral_cows, config.liquidation_threshold_bps)?,
+            ErrorCode::PositionHealthy
+        );
+
+        let proceeds = collateral_value(config, position.collateral_cows)?;
+        let debt_cleared = position.debt;
+        let seized_cows = position.collateral_cows;
+
+        let (bonus, surplus) = liquidation_split(debt_cleared, proceeds, config.liquidation_bonus_bps, config.tvl)?;
+
+        // Sold cows leave the game entirely
+        config.global_cows_count = config.global_cows_count.saturating_sub(seized_cows);
+        sync_reward_accumulator(config, current_time)?;
+        config.collateral_cows_count = config.collateral_cows_count.saturating_sub(seized_cows);
+        config.total_loan_principal = config.total_loan_principal.saturating_sub(debt_cleared);
+
+        if surplus > 0 {
+            owner_farm.accumulated_rewards = owner_farm.accumulated_rewards
+                .checked_add(surplus)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        position.collateral_cows = 0;
+        position.debt = 0;
+
+        if bonus > 0 {
+            transfer_from_pool(
+                &ctx.accounts.token_program,
+                &ctx.accounts.pool_token_account,
+                ctx.accounts.liquidator_token_account.to_account_info(),
+                &ctx.accounts.pool_authority,
+                &ctx.accounts.milk_mint,
+                &config_key,
+                ctx.bumps.pool_authority,
+                bonus,
+            )?;
+            book_pool_outflow(config, bonus)?;
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(PositionLiquidated {
+            position: position.key(),
+            owner: position.owner,
+            tag: owner_farm.tag,
+            liquidator: ctx.accounts.liquidator.key(),
+            seized_cows,
+            proceeds,
+            debt_cleared,
+            liquidator_bonus: bonus,
+            owner_surplus: surplus,
+            timestamp: current_time,
+        });
+
+        msg!("Liquidated position of {}: {} cows sold for {} MILK, debt {} cleared, bonus {} paid, surplus {} credited", 
+             position.owner, seized_cows, proceeds / 1_000_000, debt_cleared / 1_000_000, bonus / 1_000_000, surplus / 1_000_000);
+        Ok(())
+    }
+
+    pub fn set_liquidation_bonus(ctx: Context<AdminConfig>, bonus_bps: u16) -> Result<()> {
+        require!(bonus_bps <= MAX_LIQUIDATION_BONUS_BPS, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let old_value = config.liquidation_bonus_bps;
+        config.liquidation_bonus_bps = bonus_bps;
+        record_param_change(
+            config,
+            ConfigParam::LiquidationBonusBps,
+            ParamValue::Unsigned(old_value as u64),
+            ParamValue::Unsigned(bonus_bps as u64),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Liquidation bonus set to {} bps", bonus_bps);
+        Ok(())
+    }
+
+    pub fn set_lending_params(
+        ctx: Context<AdminConfig>,
+        ltv_bps: u16,
+        liquidation_threshold_bps: u16,
+        interest_bps_per_year: u16,
+    ) -> Result<()> {
+        if ltv_bps > 0 {
+            require!(ltv_bps < liquidation_threshold_bps, ErrorCode::InvalidParameter);
+            require!(liquidation_threshold_bps <= MAX_LIQUIDATION_THRESHOLD_BPS, ErrorCode::InvalidParameter);
+        }
+        require!(interest_bps_per_year <= MAX_LOAN_INTEREST_BPS, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::LoanLtvBps, ParamValue::Unsigned(config.loan_ltv_bps as u64), ParamValue::Unsigned(ltv_bps as u64)),
+            (ConfigParam::LiquidationThresholdBps, ParamValue::Unsigned(config.liquidation_threshold_bps as u64), ParamValue::Unsigned(liquidation_threshold_bps as u64)),
+            (ConfigParam::LoanInterestBps, ParamValue::Unsigned(config.loan_interest_bps_per_year as u64), ParamValue::Unsigned(interest_bps_per_year as u64)),
+        ];
+
+        config.loan_ltv_bps = ltv_bps;
+        config.liquidation_threshold_bps = liquidation_threshold_bps;
+        config.loan_interest_bps_per_year = interest_bps_per_year;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Lending params: LTV {} bps, liquidation at {} bps, {} bps APR", 
+             ltv_bps, liquidation_threshold_bps, interest_bps_per_year);
+        Ok(())
+    }
+
+    /// Choose whether early-withdrawal penalties stay in the pool or accrue
+    /// to the community grants vault
+    pub fn set_penalty_destination(ctx: Context<AdminConfig>, destination: PenaltyDestination) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_value = config.penalty_destination;
+        config.penalty_destination = destination;
+        record_param_change(
+            config,
+            ConfigParam::PenaltyDestination,
+            ParamValue::PenaltyDestination(old_value),
+            ParamValue::PenaltyDestination(destination),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Early-withdrawal penalties now go to {:?}", destination);
+        Ok(())
+    }
+
+    pub fn set_grant_governance(
+        ctx: Context<AdminConfig>,
+        voting_period: i64,
+        quorum_cows: u64,
+    ) -> Result<()> {
+        require!(voting_period > 0 && voting_period <= MAX_GRANT_VOTING_PERIOD, ErrorCode::InvalidParameter);
+        require!(quorum_cows > 0, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::GrantVotingPeriod, ParamValue::Signed(config.grant_voting_period), ParamValue::Signed(voting_period)),
+            (ConfigParam::GrantQuorumCows, ParamValue::Unsigned(config.grant_quorum_cows), ParamValue::Unsigned(quorum_cows)),
+        ];
+
+        config.grant_voting_period = voting_period;
+        config.grant_quorum_cows = quorum_cows;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Grant governance: {} second votes, quorum {} cows", voting_period, quorum_cows);
+        Ok(())
+    }
+
+    /// Move penalties earmarked for grants out of the pool into the grants
+    /// vault. Permissionless.
+    pub fn sweep_grant_penalties(ctx: Context<SweepGrantPenalties>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let amount = config.grants_pending.min(ctx.accounts.pool_token_account.amount);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.grants_vault.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        book_pool_outflow(config, amount)?;
+
+        config.grants_pending -= amount;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("Swept {} MILK of penalties into the grants vault", amount / 1_000_000);
+        Ok(())
+    }
+
+    /// Move withdrawal tax collected in the pool into a marketing vault, where
+    /// it funds starter packs. Permissionless.
+    pub fn sweep_new_farmer_fund(ctx: Context<SweepNewFarmerFund>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let amount = config.new_farmer_fund_pending.min(ctx.accounts.pool_token_account.amount);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.marketing_vault.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        book_pool_outflow(config, amount)?;
+
+        config.new_farmer_fund_pending -= amount;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("Swept {} MILK of withdrawal tax into the new farmer fund", amount / 1_000_000);
+        Ok(())
+    }
+
+    /// Pay out MILK earmarked by a fee hook to its destination. Permissionless.
+    pub fn sweep_fee_hook(ctx: Context<SweepFeeHook>, kind: FeeHookKind) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let amount = config.fee_hook_pending[kind as usize].min(ctx.accounts.pool_token_account.amount);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.destination_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        book_pool_outflow(config, amount)?;
+
+        config.fee_hook_pending[kind as usize] -= amount;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(FeeHookSwept {
+            kind,
+            destination: ctx.accounts.destination_token_account.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Swept {} MILK of {:?} to {}", amount / 1_000_000, kind, ctx.accounts.destination_token_account.key());
+        Ok(())
+    }
+
+    /// Propose a payout from the grants vault. Any farmer with cows may propose.
+    pub fn propose_grant(
+        ctx: Context<ProposeGrant>,
+        recipient: Pubkey,
+        amount: u64,
+        description: String,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(description.len() <= MAX_GRANT_DESCRIPTION_LEN, ErrorCode::InvalidParameter);
+        require!(ctx.accounts.config.grant_voting_period > 0, ErrorCode::GrantsDisabled);
+        require!(ctx.accounts.farm.cows > 0, ErrorCode::InsufficientCows);
+
+        let config = &mut ctx.accounts.config;
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        proposal.id = config.grant_proposal_count;
+        proposal.proposer = ctx.accounts.user.key();
+        proposal.recipient = recipient;
+        proposal.amount = amount;
+        proposal.description = description;
+        proposal.created_at = current_time;
+        proposal.voting_ends_at = current_time + config.grant_voting_period;
+        proposal.votes_for = 0;
+        proposal.votes_against = 0;
+        proposal.executed = false;
+
+        config.grant_proposal_count = config.grant_proposal_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        msg!("Grant proposal {} opened: {} MILK to {}, voting until {}", 
+             proposal.id, amount / 1_000_000, recipient, proposal.voting_ends_at);
+        Ok(())
+    }
+
+    /// Vote on a grant proposal, weighted by the voter's producing herd
+    pub fn vote_grant(ctx: Context<VoteGrant>, approve: bool) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let vote = &mut ctx.accounts.vote;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(current_time < proposal.voting_ends_at, ErrorCode::GrantVotingClosed);
+
+        let weight = ctx.accounts.farm.cows;
+        require!(weight > 0, ErrorCode::InsufficientCows);
+
+        if approve {
+            proposal.votes_for = proposal.votes_for
+                .checked_add(weight)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            proposal.votes_against = proposal.votes_against
+                .checked_add(weight)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        vote.voter = ctx.accounts.user.key();
+        vote.proposal = proposal.key();
+        vote.weight = weight;
+        vote.approve = approve;
+
+        msg!("Voted {} on grant {} with {} cows (for: {}, against: {})", 
+             if approve { "for" } else { "against" }, proposal.id, weight, proposal.votes_for, proposal.votes_against);
+        Ok(())
+    }
+
+    /// Pay out a grant that passed its vote. Permissionless once voting ends.
+    pub fn execute_grant(ctx: Context<ExecuteGrant>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!proposal.executed, ErrorCode::GrantAlreadyExecuted);
+        require!(current_time >= proposal.voting_ends_at, ErrorCode::GrantVotingOpen);
+        require!(
+            proposal.votes_for > proposal.votes_against
+                && proposal.votes_for + proposal.votes_against >= config.grant_quorum_cows,
+            ErrorCode::GrantNotApproved
+        );
+        require!(ctx.accounts.grants_vault.amount >= proposal.amount, ErrorCode::GrantsVaultUnderfunded);
+
+        let config_key = config.key();
+        let seeds = &[
+            b"grants_authority",
+            config_key.as_ref(),
+            &[ctx.bumps.grants_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.grants_vault.to_account_info(),
+                    mint: ctx.accounts.milk_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.grants_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            proposal.amount,
+            ctx.accounts.milk_mint.decimals,
+        )?;
+
+        proposal.executed = true;
+
+        msg!("Grant {} executed: {} MILK paid to {}", 
+             proposal.id, proposal.amount / 1_000_000, proposal.recipient);
+        Ok(())
+    }
+
+    /// Soft cap on whale farms: past `threshold_cows`, each further tier of
+    /// that many cows earns `marginal_bps` of the tier before it (0 threshold = off)
+    pub fn set_whale_taper(ctx: Context<AdminConfig>, threshold_cows: u64, marginal_bps: u16) -> Result<()> {
+        if threshold_cows > 0 {
+            require!(marginal_bps > 0 && marginal_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+        }
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::WhaleThresholdCows, ParamValue::Unsigned(config.whale_threshold_cows), ParamValue::Unsigned(threshold_cows)),
+            (ConfigParam::WhaleMarginalBps, ParamValue::Unsigned(config.whale_marginal_bps as u64), ParamValue::Unsigned(marginal_bps as u64)),
+        ];
+
+        config.whale_threshold_cows = threshold_cows;
+        config.whale_marginal_bps = marginal_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Whale taper: full rate up to {} cows, {} bps per tier after", threshold_cows, marginal_bps);
+        Ok(())
+    }
+
+    /// Reclaim policy for abandoned farms: rewards unclaimed for `idle_seconds`
+    /// can be swept back to the pool `grace_seconds` after a warning (0 = off)
+    pub fn set_idle_reclamation(ctx: Context<AdminConfig>, idle_seconds: i64, grace_seconds: i64) -> Result<()> {
+        if idle_seconds != 0 {
+            require!(idle_seconds >= MIN_RECLAIM_IDLE, ErrorCode::InvalidParameter);
+            require!(
+                (MIN_RECLAIM_GRACE..=MAX_RECLAIM_GRACE).contains(&grace_seconds),
+                ErrorCode::InvalidParameter
+            );
+        }
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::ReclaimIdleSeconds, ParamValue::Signed(config.reclaim_idle_seconds), ParamValue::Signed(idle_seconds)),
+            (ConfigParam::ReclaimGraceSeconds, ParamValue::Signed(config.reclaim_grace_seconds), ParamValue::Signed(grace_seconds)),
+        ];
+
+        config.reclaim_idle_seconds = idle_seconds;
+        config.reclaim_grace_seconds = grace_seconds;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Idle reclamation: after {}s idle, {}s grace", idle_seconds, grace_seconds);
+        Ok(())
+    }
+
+    /// Put an idle farm on notice that its unclaimed rewards will be
+    /// reclaimed once the grace window passes. Permissionless.
+    pub fn warn_idle_farm(ctx: Context<CrankFarm>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.reclaim_idle_seconds > 0, ErrorCode::ReclamationDisabled);
+        // Farms that predate interaction tracking are never considered idle
+        require!(
+            farm.last_interaction_time > 0
+                && current_time - farm.last_interaction_time >= config.reclaim_idle_seconds,
+            ErrorCode::FarmNotIdle
+        );
+        require!(farm.reclaim_warned_at <= farm.last_interaction_time, ErrorCode::ReclamationAlreadyWarned);
+
+        update_farm_rewards(farm, config, current_time)?;
+        require!(farm.accumulated_rewards > 0, ErrorCode::NoRewardsAvailable);
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        farm.reclaim_warned_at = current_time;
+        let reclaimable_at = current_time + config.reclaim_grace_seconds;
+
+        emit!(IdleFarmWarned {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            unclaimed_rewards: farm.accumulated_rewards,
+            idle_since: farm.last_interaction_time,
+            reclaimable_at,
+        });
+
+        msg!("Idle farm {} warned: {} MILK unclaimed, reclaimable at {}", 
+             farm.owner, farm.accumulated_rewards / 1_000_000, reclaimable_at);
+        Ok(())
+    }
+
+    /// Sweep a warned farm's unclaimed rewards back into the pool once the
+    /// grace window has passed without the owner returning. Permissionless.
+    pub fn reclaim_idle_rewards(ctx: Context<CrankFarm>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.reclaim_idle_seconds > 0, ErrorCode::ReclamationDisabled);
+        // Any owner interaction since the warning cancels it
+        require!(
+            farm.reclaim_warned_at > 0 && farm.reclaim_warned_at > farm.last_interaction_time,
+            ErrorCode::ReclamationNotWarned
+        );
+        require!(
+            current_time >= farm.reclaim_warned_at + config.reclaim_grace_seconds,
+            ErrorCode::ReclamationGraceActive
+        );
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let reclaimed = farm.accumulated_rewards;
+        farm.accumulated_rewards = 0;
+        farm.reclaim_warned_at = 0;
+        config.total_reclaimed_rewards = config.total_reclaimed_rewards
+            .checked_add(reclaimed)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(IdleRewardsReclaimed {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            amount: reclaimed,
+            timestamp: current_time,
+        });
+
+        msg!("Reclaimed {} MILK of unclaimed rewards from idle farm {}", reclaimed / 1_000_000, farm.owner);
+        Ok(())
+    }
+
+    /// Credit a farm for production missed while accrual was paused, at the
+    /// governance-set catch-up rate. Only once the pool is solvent again.
+    /// Permissionless.
+    pub fn catch_up_paused_rewards(ctx: Context<CrankFarm>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        require!(config.accrual_paused_since == 0, ErrorCode::AccrualPaused);
+        require!(config.catch_up_rate > 0, ErrorCode::CatchUpDisabled);
+        require!(farm.paused_cow_seconds > 0, ErrorCode::NothingToCatchUp);
+
+        let cow_seconds = farm.paused_cow_seconds;
+        let amount = rounding::mul_div(cow_seconds, config.catch_up_rate, SECONDS_PER_DAY as u64, rounding::USER_CREDIT)?;
+        farm.paused_cow_seconds = 0;
+        farm.accumulated_rewards = farm.accumulated_rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(PausedRewardsCaughtUp {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            cow_seconds,
+            amount,
+            timestamp: current_time,
+        });
+
+        msg!("Caught up {} MILK of paused production for farm {}", amount / 1_000_000, farm.owner);
+        Ok(())
+    }
+
+    /// Re-emit a farm's heartbeat without touching it, for dashboards that
+    /// want to refresh an idle farm. Rewards are as of the last settlement.
+    /// Permissionless.
+    pub fn emit_heartbeat(ctx: Context<EmitHeartbeat>) -> Result<()> {
+        let farm = &ctx.accounts.farm;
+        farm::emit_heartbeat(farm, Clock::get()?.unix_timestamp);
+
+        msg!("Heartbeat for farm owner: {}", farm.owner);
+        Ok(())
+    }
+
+    /// Move accumulated rewards between two farms with both owners signing.
+    /// No tokens leave the pool; both farms are settled first. The receiving
+    /// farm inherits the later of the two withdrawal clocks so a transfer
+    /// can't be used to dodge the early-withdrawal penalty.
+    pub fn transfer_farm_rewards(ctx: Context<TransferFarmRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            ctx.accounts.sender.key() != ctx.accounts.recipient.key(),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let from_farm = &mut ctx.accounts.from_farm;
+        let to_farm = &mut ctx.accounts.to_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(from_farm, config, current_time)?;
+        update_farm_rewards(to_farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        from_farm.last_interaction_time = current_time;
+        to_farm.last_interaction_time = current_time;
+
+        require!(from_farm.accumulated_rewards >= amount, ErrorCode::InsufficientRewards);
+
+        from_farm.accumulated_rewards -= amount;
+        to_farm.accumulated_rewards = to_farm.accumulated_rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        to_farm.last_withdraw_time = to_farm.last_withdraw_time.max(from_farm.last_withdraw_time);
+
+        emit!(FarmRewardsTransferred {
+            from_farm: from_farm.key(),
+            to_farm: to_farm.key(),
+            from_owner: from_farm.owner,
+            to_owner: to_farm.owner,
+            from_tag: from_farm.tag,
+            to_tag: to_farm.tag,
+            amount,
+            timestamp: current_time,
+        });
+
+        msg!("Transferred {} MILK of rewards from farm {} to farm {}", 
+             amount / 1_000_000, from_farm.owner, to_farm.owner);
+        Ok(())
+    }
+
+    /// Move the signer's whole farm (cows, rewards and lifetime stats) to
+    /// the farm of a new wallet, creating it if needed, e.g. when rotating
+    /// keys. Both wallets sign. The source farm is left empty, and loan
+    /// positions opened by the old wallet stay with it.
+    pub fn transfer_farm(ctx: Context<TransferFarm>) -> Result<()> {
+        require!(
+            ctx.accounts.sender.key() != ctx.accounts.recipient.key(),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let from_farm = &mut ctx.accounts.from_farm;
+        let to_farm = &mut ctx.accounts.to_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        update_farm_rewards(from_farm, config, current_time)?;
+        let fresh = open_farm(to_farm, ctx.accounts.recipient.key(), config, current_time)?;
+        if !fresh {
+            update_farm_rewards(to_farm, config, current_time)?;
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let cows = from_farm.cows + from_farm.pending_cows;
+        let rewards = from_farm.accumulated_rewards;
+        move_farm(from_farm, to_farm, fresh, current_time)?;
+        from_farm.last_interaction_time = current_time;
+        to_farm.last_interaction_time = current_time;
+
+        emit!(FarmTransferred {
+            from_farm: from_farm.key(),
+            to_farm: to_farm.key(),
+            from_owner: from_farm.owner,
+            to_owner: to_farm.owner,
+            cows,
+            rewards,
+            timestamp: current_time,
+        });
+
+        msg!("Transferred farm of {} ({} cows, {} MILK) to {}", 
+             from_farm.owner, cows, rewards / 1_000_000, to_farm.owner);
+        Ok(())
+    }
+
+    /// Give producing cows to another player's farm, creating it if needed.
+    /// Both farms are settled first and the cows never leave the game, so
+    /// there is no export, import or quarantine.
+    pub fn gift_cows(ctx: Context<GiftCows>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+        require!(
+            ctx.accounts.sender.key() != ctx.accounts.recipient.key(),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let from_farm = &mut ctx.accounts.from_farm;
+        let to_farm = &mut ctx.accounts.to_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        update_farm_rewards(from_farm, config, current_time)?;
+        if open_farm(to_farm, ctx.accounts.recipient.key(), config, current_time)? {
+            msg!("Initialized new farm for recipient: {}", to_farm.owner);
+        } else {
+            update_farm_rewards(to_farm, config, current_time)?;
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        from_farm.last_interaction_time = current_time;
+
+        check_barn_capacity(to_farm, config, num_cows)?;
+        gift_cows_between(from_farm, to_farm, num_cows)?;
+
+        emit!(CowsGifted {
+            from_farm: from_farm.key(),
+            to_farm: to_farm.key(),
+            from_owner: from_farm.owner,
+            to_owner: to_farm.owner,
+            from_tag: from_farm.tag,
+            to_tag: to_farm.tag,
+            num_cows,
+            timestamp: current_time,
+        });
+
+        msg!("Gifted {} cows from {} to {}. Sender total: {}, recipient total: {}", 
+             num_cows, from_farm.owner, to_farm.owner, from_farm.cows, to_farm.cows);
+        Ok(())
+    }
+
+    /// Set aside `num_cows` from the sponsor's farm for a gift campaign.
+    /// Wallets under `allowlist_root` (see `GiftCampaign::leaf`) can each
+    /// claim `cows_per_claim` until `expires_at`; anyone can sweep what's
+    /// left back to the sponsor after that.
+    pub fn create_gift_campaign(
+        ctx: Context<CreateGiftCampaign>,
+        id: u64,
+        num_cows: u64,
+        cows_per_claim: u64,
+        allowlist_root: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        require!(num_cows > 0 && cows_per_claim > 0, ErrorCode::InvalidAmount);
+        require!(cows_per_claim <= num_cows, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let campaign = &mut ctx.accounts.campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(
+            expires_at > current_time && expires_at - current_time <= MAX_GIFT_CAMPAIGN_DURATION,
+            ErrorCode::InvalidParameter
+        );
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        // Escrowed cows leave the producing herd but still count towards
+        // the global total, like loan collateral
+        take_cows_from_herd(farm, num_cows)?;
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(num_cows);
+        config.gift_campaign_cows_count = config.gift_campaign_cows_count
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        campaign.sponsor = ctx.accounts.sponsor.key();
+        campaign.id = id;
+        campaign.allowlist_root = allowlist_root;
+        campaign.cows_per_claim = cows_per_claim;
+        campaign.cows_remaining = num_cows;
+        campaign.claims = 0;
+        campaign.created_at = current_time;
+        campaign.expires_at = expires_at;
+
+        emit!(GiftCampaignCreated {
+            campaign: campaign.key(),
+            sponsor: campaign.sponsor,
+            id,
+            num_cows,
+            cows_per_claim,
+            expires_at,
+            timestamp: current_time,
+        });
+
+        msg!("Gift campaign {} created with {} cows, {} per claim, expiring at {}", 
+             id, num_cows, cows_per_claim, expires_at);
+        Ok(())
+    }
+
+    /// Claim a listed wallet's gift cows into its farm, opening the farm if
+    /// needed. The last claimant gets whatever is left if that's less than
+    /// a full share.
+    pub fn claim_gift_cows(ctx: Context<ClaimGiftCows>, proof: Vec<[u8; 32]>) -> Result<()> {
+        require!(proof.len() <= MAX_GIFT_PROOF_LEN, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let campaign = &mut ctx.accounts.campaign;
+        let farm = &mut ctx.accounts.farm;
+        let wallet = ctx.accounts.wallet.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require!(current_time < campaign.expires_at, ErrorCode::GiftCampaignExpired);
+        require!(campaign.cows_remaining > 0, ErrorCode::InsufficientCows);
+        require!(
+            verify_allowlist_proof(GiftCampaign::leaf(&campaign.key(), &wallet), &proof, &campaign.allowlist_root),
+            ErrorCode::NotOnAllowlist
+        );
+
+        if open_farm(farm, wallet, config, current_time)? {
+            msg!("Initialized new farm for user: {}", farm.owner);
+        } else {
+            update_farm_rewards(farm, config, current_time)?;
+        }
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let num_cows = campaign.cows_per_claim.min(campaign.cows_remaining);
+        check_barn_capacity(farm, config, num_cows)?;
+        campaign.cows_remaining -= num_cows;
+        campaign.claims += 1;
+        config.gift_campaign_cows_count = config.gift_campaign_cows_count.saturating_sub(num_cows);
+        config.farming_cows_count = config.farming_cows_count
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        farm.cows = farm.cows
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.campaign = campaign.key();
+        claim.wallet = wallet;
+        claim.cows = num_cows;
+        claim.claimed_at = current_time;
+
+        emit!(GiftCowsClaimed {
+            campaign: campaign.key(),
+            wallet,
+            farm: farm.key(),
+            tag: farm.tag,
+            num_cows,
+            cows_remaining: campaign.cows_remaining,
+            timestamp: current_time,
+        });
+
+        msg!("Claimed {} gift cows. Farm total: {}, left in campaign: {}", 
+             num_cows, farm.cows, campaign.cows_remaining);
+        Ok(())
+    }
+
+    /// Permissionless: once a gift campaign has expired, return its
+    /// unclaimed cows to the sponsor's farm and close it, refunding the
+    /// rent to the sponsor
+    pub fn sweep_gift_campaign(ctx: Context<SweepGiftCampaign>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let campaign = &ctx.accounts.campaign;
+        let farm = &mut ctx.accounts.sponsor_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(current_time >= campaign.expires_at, ErrorCode::GiftCampaignActive);
+        // Cows returned after the final milking snapshot would dilute everyone
+        // else's share of the distribution
+        require!(config.final_milking_started_at == 0, ErrorCode::FinalMilkingActive);
+
+        let returned_cows = campaign.cows_remaining;
+        if returned_cows > 0 {
+            update_farm_rewards(farm, config, current_time)?;
+            cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+            config.gift_campaign_cows_count = config.gift_campaign_cows_count.saturating_sub(returned_cows);
+            config.farming_cows_count = config.farming_cows_count
+                .checked_add(returned_cows)
+                .ok_or(ErrorCode::MathOverflow)?;
+            farm.cows = farm.cows
+                .checked_add(returned_cows)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        emit!(GiftCampaignSwept {
+            campaign: campaign.key(),
+            sponsor: campaign.sponsor,
+            returned_cows,
+            claims: campaign.claims,
+            timestamp: current_time,
+        });
+
+        msg!("Swept gift campaign {}: {} cows returned to {} after {} claims", 
+             campaign.id, returned_cows, campaign.sponsor, campaign.claims);
+        Ok(())
+    }
+
+    /// Start the timelock on moving the whole pool to `destination`, for a
+    /// protocol upgrade. Nothing moves until `execute_migration` after the
+    /// migration delay, giving farmers time to withdraw first.
+    pub fn queue_migration(ctx: Context<QueueMigration>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.migration_executable_at == 0, ErrorCode::MigrationAlreadyQueued);
+        require!(current_time < config.migration_deadline, ErrorCode::MigrationWindowClosed);
+
+        let delay = config.migration_delay.max(MIN_MIGRATION_DELAY);
+        config.migration_executable_at = current_time
+            .checked_add(delay)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.migration_destination = ctx.accounts.destination_token_account.key();
+        config.migration_vetoes = 0;
+
+        emit!(MigrationQueued {
+            destination: config.migration_destination,
+            pool_balance: ctx.accounts.pool_token_account.amount,
+            executable_at: config.migration_executable_at,
+            timestamp: current_time,
+        });
+
+        msg!("Migration to {} queued, executable from {}", 
+             config.migration_destination, config.migration_executable_at);
+        Ok(())
+    }
+
+    /// Drop the queued migration
+    pub fn cancel_migration(ctx: Context<AdminConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(config.migration_executable_at != 0, ErrorCode::MigrationNotQueued);
+
+        let destination = config.migration_destination;
+        config.migration_executable_at = 0;
+        config.migration_destination = Pubkey::default();
+        config.migration_vetoes = 0;
+
+        emit!(MigrationCancelled {
+            destination,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Migration to {} cancelled", destination);
+        Ok(())
+    }
+
+    /// Guardian veto on the queued migration, usable any time before it
+    /// executes. Once `guardian_threshold` distinct guardians have vetoed,
+    /// the migration is cancelled and has to be queued again.
+    pub fn veto_migration(ctx: Context<VetoMigration>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let guardian = ctx.accounts.guardian.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.migration_executable_at != 0, ErrorCode::MigrationNotQueued);
+        let seat = config.guardians
+            .iter()
+            .position(|g| *g == guardian && guardian != Pubkey::default())
+            .ok_or(ErrorCode::NotGuardian)?;
+        let seat_bit = 1u8 << seat;
+        require!(config.migration_vetoes & seat_bit == 0, ErrorCode::AlreadyVetoed);
+
+        config.migration_vetoes |= seat_bit;
+        let vetoes = config.migration_vetoes.count_ones() as u8;
+        let destination = config.migration_destination;
+        let cancelled = vetoes >= config.guardian_threshold;
+        if cancelled {
+            config.migration_executable_at = 0;
+            config.migration_destination = Pubkey::default();
+            config.migration_vetoes = 0;
+        }
+
+        emit!(MigrationVetoed {
+            destination,
+            guardian,
+            vetoes,
+            threshold: config.guardian_threshold,
+            cancelled,
+            timestamp: current_time,
+        });
+
+        msg!("Guardian {} vetoed migration to {} ({}/{}){}", 
+             guardian, destination, vetoes, config.guardian_threshold,
+             if cancelled { ", cancelled" } else { "" });
+        Ok(())
+    }
+
+    /// Move the whole pool to the queued destination once the timelock has
+    /// elapsed, as long as the migration window is still open
+    pub fn execute_migration(ctx: Context<ExecuteMigration>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let pool_balance = ctx.accounts.pool_token_account.amount;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require!(config.migration_executable_at != 0, ErrorCode::MigrationNotQueued);
+        require!(current_time >= config.migration_executable_at, ErrorCode::MigrationTimelocked);
+        require!(current_time < config.migration_deadline, ErrorCode::MigrationWindowClosed);
+        require!(pool_balance > 0, ErrorCode::NoFundsToMigrate);
+        
+        msg!("V3 Migration");
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.destination_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            pool_balance,
+        )?;
+        book_pool_outflow(config, pool_balance)?;
+        config.migration_executable_at = 0;
+        config.migration_vetoes = 0;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(MigrationExecuted {
+            destination: config.migration_destination,
+            amount: pool_balance,
+            timestamp: current_time,
+        });
+
+        msg!("V3 Migration completed");
+        Ok(())
+    }
+
+    /// Set the rule table of a phase the game hasn't reached yet. The rules
+    /// of the current phase are frozen, so what is in force only changes
+    /// through a timelocked transition.
+    pub fn set_phase_rules(ctx: Context<AdminConfig>, phase: GamePhase, rules: PhaseRules) -> Result<()> {
+        require!(rules.blocked_actions & !PHASE_BLOCK_ALL == 0, ErrorCode::InvalidParameter);
+        require!(rules.buy_cap_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+        require!(rules.buy_fee_bps <= MAX_PHASE_BUY_FEE_BPS, ErrorCode::InvalidParameter);
+        require!(rules.penalty_waiver_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        require!(phase as u8 > config.phase as u8, ErrorCode::PhaseRulesFrozen);
+        let admin = ctx.accounts.admin.key();
+        let old = config.phase_rules[phase as usize];
+        let changes = [
+            (
+                ConfigParam::PhaseBlockedActions(phase),
+                ParamValue::Unsigned(old.blocked_actions as u64),
+                ParamValue::Unsigned(rules.blocked_actions as u64),
+            ),
+            (
+                ConfigParam::PhaseBuyCapBps(phase),
+                ParamValue::Unsigned(old.buy_cap_bps as u64),
+                ParamValue::Unsigned(rules.buy_cap_bps as u64),
+            ),
+            (
+                ConfigParam::PhaseBuyFeeBps(phase),
+                ParamValue::Unsigned(old.buy_fee_bps as u64),
+                ParamValue::Unsigned(rules.buy_fee_bps as u64),
+            ),
+            (
+                ConfigParam::PhasePenaltyWaiverBps(phase),
+                ParamValue::Unsigned(old.penalty_waiver_bps as u64),
+                ParamValue::Unsigned(rules.penalty_waiver_bps as u64),
+            ),
+        ];
+
+        config.phase_rules[phase as usize] = rules;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("{:?} rules: blocked actions {:#07b}, buy cap {} bps, buy fee {} bps, penalty waiver {} bps", 
+             phase, rules.blocked_actions, rules.buy_cap_bps, rules.buy_fee_bps, rules.penalty_waiver_bps);
+        Ok(())
+    }
+
+    /// Start the timelock on moving the game to its next phase. Anyone can
+    /// execute the transition once PHASE_TRANSITION_DELAY has passed, unless
+    /// enough guardians veto it first.
+    pub fn queue_phase_transition(ctx: Context<AdminConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let next = next_phase(config.phase).ok_or(ErrorCode::FinalPhase)?;
+        require!(config.phase_transition_at == 0, ErrorCode::PhaseTransitionQueued);
+        config.phase_transition_at = current_time
+            .checked_add(PHASE_TRANSITION_DELAY)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.phase_vetoes = 0;
+
+        emit!(PhaseTransitionQueued {
+            from: config.phase,
+            to: next,
+            executable_at: config.phase_transition_at,
+            timestamp: current_time,
+        });
+
+        msg!("Transition from {:?} to {:?} queued, executable from {}", 
+             config.phase, next, config.phase_transition_at);
+        Ok(())
+    }
+
+    /// Drop the queued phase transition
+    pub fn cancel_phase_transition(ctx: Context<AdminConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(config.phase_transition_at != 0, ErrorCode::PhaseTransitionNotQueued);
+
+        config.phase_transition_at = 0;
+        config.phase_vetoes = 0;
+
+        msg!("Transition out of {:?} cancelled", config.phase);
+        Ok(())
+    }
+
+    /// Guardian veto on the queued phase transition. Once
+    /// `guardian_threshold` distinct guardians have vetoed, the transition
+    /// is cancelled and has to be queued again.
+    pub fn veto_phase_transition(ctx: Context<VetoPhaseTransition>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let guardian = ctx.accounts.guardian.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.phase_transition_at != 0, ErrorCode::PhaseTransitionNotQueued);
+        let seat = config.guardians
+            .iter()
+            .position(|g| *g == guardian && guardian != Pubkey::default())
+            .ok_or(ErrorCode::NotGuardian)?;
+        let seat_bit = 1u8 << seat;
+        require!(config.phase_vetoes & seat_bit == 0, ErrorCode::AlreadyVetoed);
+
+        config.phase_vetoes |= seat_bit;
+        let vetoes = config.phase_vetoes.count_ones() as u8;
+        let cancelled = vetoes >= config.guardian_threshold;
+        if cancelled {
+            config.phase_transition_at = 0;
+            config.phase_vetoes = 0;
+        }
+
+        emit!(PhaseTransitionVetoed {
+            to: next_phase(config.phase).ok_or(ErrorCode::FinalPhase)?,
+            guardian,
+            vetoes,
+            threshold: config.guardian_threshold,
+            cancelled,
+            timestamp: current_time,
+        });
+
+        msg!("Guardian {} vetoed the transition out of {:?} ({}/{}){}", 
+             guardian, config.phase, vetoes, config.guardian_threshold,
+             if cancelled { ", cancelled" } else { "" });
+        Ok(())
+    }
+
+    /// Move the game to its next phase once the queued transition's
+    /// timelock has elapsed. Permissionless.
+    pub fn execute_phase_transition(ctx: Context<ExecutePhaseTransition>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.phase_transition_at != 0, ErrorCode::PhaseTransitionNotQueued);
+        require!(current_time >= config.phase_transition_at, ErrorCode::PhaseTransitionTimelocked);
+
+        let from = config.phase;
+        config.phase = next_phase(from).ok_or(ErrorCode::FinalPhase)?;
+        config.phase_transition_at = 0;
+        config.phase_vetoes = 0;
+
+        emit!(PhaseChanged {
+            from,
+            to: config.phase,
+            timestamp: current_time,
+        });
+
+        msg!("Game phase: {:?} -> {:?}", from, config.phase);
+        Ok(())
+    }
+
+    /// Wind the game down for good ("final milking"). The game is paused
+    /// for the rest of its life and the booked pool, less MILK earmarked for
+    /// the grants and new farmer vaults, is split pro-rata over the cows
+    /// farming right now. Owners claim their share with `claim_final_milk`
+    /// during the claim window.
+    pub fn begin_final_milking(ctx: Context<BeginFinalMilking>, claim_window: i64) -> Result<()> {
+        require!(
+            (MIN_FINAL_MILKING_WINDOW..=MAX_FINAL_MILKING_WINDOW).contains(&claim_window),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(config.final_milking_started_at == 0, ErrorCode::FinalMilkingActive);
+        require!(config.farming_cows_count > 0, ErrorCode::InsufficientCows);
+
+        let admin = ctx.accounts.admin.key();
+        if !config.paused {
+            set_paused(config, true, admin)?;
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        config.final_milking_started_at = current_time;
+        config.final_milking_ends_at = current_time
+            .checked_add(claim_window)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.final_milking_pool = config.tvl
+            .min(ctx.accounts.pool_token_account.amount)
+            .saturating_sub(config.grants_pending)
+            .saturating_sub(config.new_farmer_fund_pending)
+            .saturating_sub(config.fee_hook_pending.iter().sum());
+        config.final_milking_cows = config.farming_cows_count;
+        config.final_milking_claimed = 0;
+
+        emit!(FinalMilkingStarted {
+            pool: config.final_milking_pool,
+            cows: config.final_milking_cows,
+            claim_ends_at: config.final_milking_ends_at,
+            timestamp: current_time,
+        });
+
+        msg!("Final milking: {} MILK over {} cows, claimable until {}", 
+             config.final_milking_pool / 1_000_000, config.final_milking_cows, config.final_milking_ends_at);
+        Ok(())
+    }
+
+    /// Take the farm's pro-rata share of the final distribution. The herd
+    /// is retired and unwithdrawn rewards are forfeited in the process.
+    pub fn claim_final_milk(ctx: Context<ClaimFinalMilk>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.final_milking_started_at != 0, ErrorCode::FinalMilkingNotActive);
+        require!(current_time < config.final_milking_ends_at, ErrorCode::FinalMilkingClaimWindowClosed);
+        require!(farm.cows > 0, ErrorCode::InsufficientCows);
+
+        let cows = farm.cows;
+        let amount = rounding::mul_div(cows, config.final_milking_pool, config.final_milking_cows, rounding::USER_CREDIT)?
+            .min(config.final_milking_pool - config.final_milking_claimed);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        config.final_milking_claimed += amount;
+        config.global_cows_count = config.global_cows_count.saturating_sub(cows);
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(cows);
+        book_pool_outflow(config, amount)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        farm.cows = 0;
+        farm.rare_cows = 0;
+        farm.epic_cows = 0;
+        farm.genetics_bps_sum = 0;
+        farm.cost_basis = 0;
+        farm.accumulated_rewards = 0;
+        farm.total_milk_withdrawn = farm.total_milk_withdrawn.saturating_add(amount);
+        farm.last_update_time = current_time;
+        farm.last_interaction_time = current_time;
+
+        emit!(FinalMilkClaimed {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            cows,
+            amount,
+            timestamp: current_time,
+        });
+
+        msg!("Final milk for {}: {} cows retired for {} MILK", farm.owner, cows, amount / 1_000_000);
+        Ok(())
+    }
+
+    /// Close a farm during the wind-down and return its rent to the owner:
+    /// any time once its share is claimed, or by anyone after the claim
+    /// window, forfeiting an unclaimed share
+    pub fn close_retired_farm(ctx: Context<CloseRetiredFarm>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.final_milking_started_at != 0, ErrorCode::FinalMilkingNotActive);
+        require!(
+            farm.cows == 0 || current_time >= config.final_milking_ends_at,
+            ErrorCode::FinalMilkingClaimWindowOpen
+        );
+
+        config.global_cows_count = config.global_cows_count.saturating_sub(farm.cows);
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(farm.cows);
+
+        msg!("Closed retired farm of {}", farm.owner);
+        Ok(())
+    }
+
+    /// After the claim window, move whatever is left in the pool (unclaimed
+    /// shares and earmarked MILK) to `destination`
+    pub fn conclude_final_milking(ctx: Context<ConcludeFinalMilking>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let amount = ctx.accounts.pool_token_account.amount;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(config.final_milking_started_at != 0, ErrorCode::FinalMilkingNotActive);
+        require!(current_time >= config.final_milking_ends_at, ErrorCode::FinalMilkingClaimWindowOpen);
+        require!(amount > 0, ErrorCode::NoFundsToMigrate);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.destination_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        book_pool_outflow(config, amount)?;
+        config.grants_pending = 0;
+        config.new_farmer_fund_pending = 0;
+        config.fee_hook_pending = [0; FEE_HOOK_COUNT];
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(FinalMilkingConcluded {
+            destination: ctx.accounts.destination_token_account.key(),
+            amount,
+            timestamp: current_time,
+        });
+
+        msg!("Final milking concluded: {} MILK to {}", 
+             amount / 1_000_000, ctx.accounts.destination_token_account.key());
+        Ok(())
+    }
+
+    pub fn export_cows(ctx: Context<ExportCows>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+        
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        msg!("Exporting {} cows to COW tokens for user: {}", num_cows, ctx.accounts.user.key());
+
+        // Update rewards before export (user keeps accumulated rewards)
+        export_from_farm(farm, config, current_time, num_cows)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        // Mint COW tokens to user (1 cow = 1 COW token with 6 decimals)
+        require_cow_backing(config, &ctx.accounts.cow_mint, num_cows)?;
+        mint_cow_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.cow_mint,
+            ctx.accounts.user_cow_token_account.to_account_info(),
+            &ctx.accounts.cow_mint_authority,
+            &config.key(),
+            ctx.bumps.cow_mint_authority,
+            num_cows,
+        )?;
+
+        msg!("Successfully exported {} cows to COW tokens. User cows remaining: {}", 
+             num_cows, farm.cows);
+        Ok(())
+    }
+
+    /// Export cows into a fresh COW token account that is frozen until
+    /// `unlock_at`, as a public pre-commitment not to sell. The account is
+    /// owned by the user; only the freeze stops it moving.
+    pub fn export_cows_locked(ctx: Context<ExportCowsLocked>, num_cows: u64, unlock_at: i64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            unlock_at > current_time && unlock_at - current_time <= MAX_COW_LOCK_DURATION,
+            ErrorCode::InvalidLockDuration
+        );
+        require!(
+            ctx.accounts.cow_mint.freeze_authority == Some(ctx.accounts.cow_mint_authority.key()).into(),
+            ErrorCode::FreezeAuthorityNotDelegated
+        );
+
+        export_from_farm(farm, config, current_time, num_cows)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        require_cow_backing(config, &ctx.accounts.cow_mint, num_cows)?;
+        let config_key = config.key();
+        mint_cow_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.cow_mint,
+            ctx.accounts.locked_cow_account.to_account_info(),
+            &ctx.accounts.cow_mint_authority,
+            &config_key,
+            ctx.bumps.cow_mint_authority,
+            num_cows,
+        )?;
+
+        let seeds = &[
+            b"cow_mint_authority",
+            config_key.as_ref(),
+            &[ctx.bumps.cow_mint_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            FreezeAccount {
+                account: ctx.accounts.locked_cow_account.to_account_info(),
+                mint: ctx.accounts.cow_mint.to_account_info(),
+                authority: ctx.accounts.cow_mint_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        emit!(CowsLocked {
+            owner: ctx.accounts.user.key(),
+            tag: farm.tag,
+            token_account: ctx.accounts.locked_cow_account.key(),
+            cows: num_cows,
+            unlock_at,
+        });
+
+        msg!("Exported {} cows into locked account {} until {}. User cows remaining: {}", 
+             num_cows, ctx.accounts.locked_cow_account.key(), unlock_at, farm.cows);
+        Ok(())
+    }
+
+    /// Thaw a locked COW account once its unlock date has passed. Permissionless.
+    pub fn unlock_cows(ctx: Context<UnlockCows>, unlock_at: i64) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time >= unlock_at, ErrorCode::CowsStillLocked);
+
+        let config_key = ctx.accounts.config.key();
+        let seeds = &[
+            b"cow_mint_authority",
+            config_key.as_ref(),
+            &[ctx.bumps.cow_mint_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::thaw_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            ThawAccount {
+                account: ctx.accounts.locked_cow_account.to_account_info(),
+                mint: ctx.accounts.cow_mint.to_account_info(),
+                authority: ctx.accounts.cow_mint_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        msg!("Unlocked COW account {} for {}", ctx.accounts.locked_cow_account.key(), ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    pub fn import_cows(ctx: Context<ImportCows>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+        
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Initialize farm if needed
+        if open_farm(farm, ctx.accounts.user.key(), config, current_time)? {
+            msg!("Initialized new farm for user: {}", ctx.accounts.user.key());
+        } else {
+            // Update rewards before import
+            update_farm_rewards(farm, config, current_time)?;
+        }
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        check_barn_capacity(farm, config, num_cows)?;
+        msg!("Importing {} COW tokens to cows for user: {}", num_cows, ctx.accounts.user.key());
+
+        // Burn COW tokens from user
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.cow_mint.to_account_info(),
+                    from: ctx.accounts.user_cow_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            num_cows * 1_000_000, // COW tokens have 6 decimals
+        )?;
+
+        import_to_farm(farm, config, current_time, num_cows)
+    }
+
+    /// Open the COW vault: a share mint and a farm owned by the vault PDA
+    pub fn initialize_cow_vault(ctx: Context<InitializeCowVault>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let vault = &mut ctx.accounts.cow_vault;
+        vault.share_mint = ctx.accounts.share_mint.key();
+        vault.farm = ctx.accounts.vault_farm.key();
+        vault.bump = ctx.bumps.cow_vault;
+
+        let farm = &mut ctx.accounts.vault_farm;
+        farm.owner = vault.key();
+        farm.last_update_time = current_time;
+        farm.farm_created_at = current_time;
+        farm.last_interaction_time = current_time;
+
+        msg!("COW vault opened: shares {}, farm {}", vault.share_mint, vault.farm);
+        Ok(())
+    }
+
+    /// Open the COW staking pool and its token vault. Emission starts at
+    /// zero until `set_cow_staking_emission`.
+    pub fn initialize_cow_staking(ctx: Context<InitializeCowStaking>) -> Result<()> {
+        let pool = &mut ctx.accounts.staking_pool;
+        pool.token_vault = ctx.accounts.token_vault.key();
+        pool.updated_at = Clock::get()?.unix_timestamp;
+        pool.bump = ctx.bumps.staking_pool;
+
+        msg!("COW staking opened: vault {}", pool.token_vault);
+        Ok(())
+    }
+
+    /// Stake whole COW tokens to earn a share of the staking emission.
+    /// Rewards earned so far are kept for `claim_staking_rewards`.
+    pub fn stake_cow(ctx: Context<StakeCow>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+        require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+
+        let pool = &mut ctx.accounts.staking_pool;
+        let stake = &mut ctx.accounts.stake;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        accrue_staking(pool, current_time)?;
+        if stake.owner == Pubkey::default() {
+            stake.owner = ctx.accounts.user.key();
+        }
+        settle_stake(stake, pool)?;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_cow_token_account.to_account_info(),
+                    mint: ctx.accounts.cow_mint.to_account_info(),
+                    to: ctx.accounts.token_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            num_cows * 1_000_000, // COW tokens have 6 decimals
+            ctx.accounts.cow_mint.decimals,
+        )?;
+
+        stake.staked_cows = stake.staked_cows
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.total_staked = pool.total_staked
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(CowStaked {
+            owner: stake.owner,
+            num_cows,
+            staked_cows: stake.staked_cows,
+            total_staked: pool.total_staked,
+            timestamp: current_time,
+        });
+
+        msg!("Staked {} COW. Staked: {}, pool total: {}", num_cows, stake.staked_cows, pool.total_staked);
+        Ok(())
+    }
+
+    /// Take staked COW tokens back. Works while the game is paused since it
+    /// only returns the staker's own tokens.
+    pub fn unstake_cow(ctx: Context<UnstakeCow>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let pool = &mut ctx.accounts.staking_pool;
+        let stake = &mut ctx.accounts.stake;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(stake.staked_cows >= num_cows, ErrorCode::InsufficientStake);
+        accrue_staking(pool, current_time)?;
+        settle_stake(stake, pool)?;
+
+        stake.staked_cows -= num_cows;
+        pool.total_staked = pool.total_staked.saturating_sub(num_cows);
+
+        transfer_staked_cows(
+            &ctx.accounts.token_program,
+            &ctx.accounts.token_vault,
+            ctx.accounts.user_cow_token_account.to_account_info(),
+            pool,
+            &ctx.accounts.cow_mint,
+            &ctx.accounts.config.key(),
+            num_cows,
+        )?;
+
+        emit!(CowUnstaked {
+            owner: stake.owner,
+            num_cows,
+            staked_cows: stake.staked_cows,
+            total_staked: pool.total_staked,
+            timestamp: current_time,
+        });
+
+        msg!("Unstaked {} COW. Staked: {}, pool total: {}", num_cows, stake.staked_cows, pool.total_staked);
+        Ok(())
+    }
+
+    /// Pay out a stake's MILK rewards from the pool
+    pub fn claim_staking_rewards(ctx: Context<ClaimStakingRewards>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let pool = &mut ctx.accounts.staking_pool;
+        let stake = &mut ctx.accounts.stake;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        accrue_staking(pool, current_time)?;
+        settle_stake(stake, pool)?;
+
+        let amount = stake.pending_rewards;
+        require!(amount > 0, ErrorCode::NoStakingRewards);
+        require!(
+            payable_from_pool(config, &mut ctx.accounts.pool_token_account, amount)? == amount,
+            ErrorCode::InsufficientPoolBalance
+        );
+        stake.pending_rewards = 0;
+        stake.total_claimed = stake.total_claimed.saturating_add(amount);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            amount,
+        )?;
+        book_pool_outflow(config, amount)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(StakingRewardsClaimed {
+            owner: stake.owner,
+            amount,
+            timestamp: current_time,
+        });
+
+        msg!("Claimed {} MILK of staking rewards", amount / 1_000_000);
+        Ok(())
+    }
+
+    /// Adapter for yield aggregators: buy `num_cows` into the farm of
+    /// `authority` (typically a vault PDA signing through CPI) for at most
+    /// `max_total_cost` MILK. No referral, quote or goal accounts, so the
+    /// account list stays fixed across upgrades.
+    pub fn deposit_for(ctx: Context<DepositFor>, num_cows: u64, max_total_cost: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if !open_farm(farm, ctx.accounts.authority.key(), config, current_time)? {
+            update_farm_rewards(farm, config, current_time)?;
+        }
+        farm.last_interaction_time = current_time;
+        check_buy_limits(farm, config, num_cows, current_time)?;
+
+        let total_cost = calculate_purchase_cost(
+            config,
+            ctx.accounts.milk_price.as_deref(),
+            ctx.accounts.pyth_price_update.as_ref().map(|a| a.to_account_info()).as_ref(),
+            num_cows,
+            current_time,
+        )?;
+        let total_cost = phase_buy_price(config, total_cost)?;
+        require!(total_cost <= max_total_cost, ErrorCode::SlippageExceeded);
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.source_token_account.to_account_info(),
+                    mint: ctx.accounts.milk_mint.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            total_cost,
+            ctx.accounts.milk_mint.decimals,
+        )?;
+        let pool_inflow = net_of_transfer_fee(&ctx.accounts.milk_mint, total_cost)?;
+        book_pool_inflow(config, pool_inflow)?;
+        route_fee(config, FeeHookKind::BuyFee, pool_inflow)?;
+
+        let genetics = roll_genetics(
+            &ctx.accounts.slot_hashes,
+            &ctx.accounts.authority.key(),
+            config.global_cows_count.saturating_add(num_cows),
+        )?;
+        add_bought_cows(farm, config, num_cows, total_cost, genetics, current_time)?;
+
+        sync_reward_accumulator(config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        let new_reward_rate = config.reward_rate;
+
+        emit!(BuyEvent {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            num_cows,
+            price_per_cow: total_cost / num_cows,
+            total_cost,
+            global_cows: config.global_cows_count,
+            reward_rate: new_reward_rate,
+            timestamp: current_time,
+        });
+        emit!(AdapterDeposited {
+            farm: farm.key(),
+            authority: farm.owner,
+            num_cows,
+            total_cost,
+            cows: farm.cows,
+            timestamp: current_time,
+        });
+
+        msg!("Adapter deposit: {} cows for {} MILK. Farm total: {}", 
+             num_cows, total_cost / 1_000_000, farm.cows);
+        Ok(())
+    }
+
+    /// Adapter for yield aggregators: withdraw all of the farm's rewards to
+    /// `recipient_token_account`, aborting if less than `min_amount` would
+    /// arrive after the early-withdrawal penalty
+    pub fn harvest_for(ctx: Context<HarvestFor>, min_amount: u64) -> Result<()> {
+        let pool_balance = reloaded_pool_balance(&mut ctx.accounts.pool_token_account)?;
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let (withdrawal_amount, penalty_amount) = settle_withdrawal(
+            farm,
+            config,
+            ctx.accounts.penalty_grace.as_deref(),
+            current_time,
+            None,
+            pool_balance,
+        )?;
+        farm.last_interaction_time = current_time;
+        require!(withdrawal_amount >= min_amount, ErrorCode::SlippageExceeded);
+        require!(
+            config.escrow_threshold == 0 || withdrawal_amount <= config.escrow_threshold,
+            ErrorCode::WithdrawalRequiresEscrow
+        );
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.recipient_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            withdrawal_amount,
+        )?;
+        book_pool_outflow(config, withdrawal_amount)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        emit!(WithdrawEvent {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            amount: withdrawal_amount,
+            penalty: penalty_amount,
+            reward_rate: config.reward_rate,
+            memo: None,
+            timestamp: current_time,
+        });
+        emit!(AdapterHarvested {
+            farm: farm.key(),
+            authority: farm.owner,
+            recipient: ctx.accounts.recipient_token_account.key(),
+            amount: withdrawal_amount,
+            penalty: penalty_amount,
+            timestamp: current_time,
+        });
+
+        msg!("Adapter harvest: {} MILK ({} MILK penalty)", 
+             withdrawal_amount / 1_000_000, penalty_amount / 1_000_000);
+        Ok(())
+    }
+
+    /// Adapter for yield aggregators: sell `num_cows` back to the pool with
+    /// the proceeds paid to `recipient_token_account`, aborting below
+    /// `min_payout`. Rewards the cows produced stay on the farm.
+    pub fn withdraw_for(ctx: Context<WithdrawFor>, num_cows: u64, min_payout: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        require_phase_allows(config, PHASE_BLOCK_SELL)?;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+
+        let payout = calculate_sell_payout(&economic_params(config), config.global_cows_count, num_cows)?;
+        require!(payout >= min_payout, ErrorCode::SlippageExceeded);
+        require!(payout <= config.tvl, ErrorCode::InsufficientPoolBalance);
+
+        take_cows_from_herd(farm, num_cows)?;
+        config.global_cows_count -= num_cows;
+        config.farming_cows_count = config.farming_cows_count.saturating_sub(num_cows);
+
+        transfer_from_pool(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.recipient_token_account.to_account_info(),
+            &ctx.accounts.pool_authority,
+            &ctx.accounts.milk_mint,
+            &config.key(),
+            ctx.bumps.pool_authority,
+            payout,
+        )?;
+        book_pool_outflow(config, payout)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        let new_reward_rate = sync_reward_accumulator(config, current_time)?;
+
+        emit!(CowsSold {
+            farm: farm.key(),
+            owner: farm.owner,
+            tag: farm.tag,
+            num_cows,
+            payout,
+            global_cows: config.global_cows_count,
+            reward_rate: new_reward_rate,
+            timestamp: current_time,
+        });
+        emit!(AdapterWithdrawn {
+            farm: farm.key(),
+            authority: farm.owner,
+            recipient: ctx.accounts.recipient_token_account.key(),
+            num_cows,
+            payout,
+            cows: farm.cows,
+            timestamp: current_time,
+        });
+
+        msg!("Adapter withdrawal: {} cows for {} MILK. Farm total: {}", 
+             num_cows, payout / 1_000_000, farm.cows);
+        Ok(())
+    }
+
+    /// Deposit COW tokens into the vault for shares. The vault compounds
+    /// first, so new shares are priced against its reinvested herd.
+    pub fn deposit_to_cow_vault(ctx: Context<DepositToCowVault>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        let farm = &mut ctx.accounts.vault_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        compound_vault_farm(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let vault_cows = farm.cows
+            .checked_add(farm.pending_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let shares = vault_shares_for(num_cows, vault_cows, ctx.accounts.cow_vault.total_shares)?;
+        require!(shares > 0, ErrorCode::InvalidAmount);
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.cow_mint.to_account_info(),
+                    from: ctx.accounts.user_cow_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            num_cows * 1_000_000, // COW tokens have 6 decimals
+        )?;
+        import_to_farm(farm, config, current_time, num_cows)?;
+
+        mint_vault_shares(
+            &ctx.accounts.token_program,
+            &ctx.accounts.share_mint,
+            ctx.accounts.user_share_account.to_account_info(),
+            &ctx.accounts.cow_vault,
+            &config.key(),
+            shares,
+        )?;
+
+        let vault = &mut ctx.accounts.cow_vault;
+        vault.total_shares = vault.total_shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::MathOverflow)?;
+        vault.total_deposited_cows = vault.total_deposited_cows.saturating_add(num_cows);
+
+        emit!(CowVaultDeposited {
+            vault: vault.key(),
+            depositor: ctx.accounts.user.key(),
+            num_cows,
+            shares,
+            vault_cows: vault_cows + num_cows,
+            total_shares: vault.total_shares,
+            timestamp: current_time,
+        });
+
+        msg!("Deposited {} cows into the COW vault for {} shares", num_cows, shares);
+        Ok(())
+    }
+
+    /// Burn vault shares for their slice of the vault's herd, minted as COW
+    /// tokens. Cows still in import quarantine can't be redeemed until they
+    /// start producing.
+    pub fn redeem_cow_vault_shares(ctx: Context<RedeemCowVaultShares>, shares: u64) -> Result<()> {
+        require!(shares > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.vault_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        compound_vault_farm(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        let vault_cows = farm.cows
+            .checked_add(farm.pending_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let num_cows = vault_cows_for(shares, vault_cows, ctx.accounts.cow_vault.total_shares)?;
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    from: ctx.accounts.user_share_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        export_from_farm(farm, config, current_time, num_cows)?;
+        require_cow_backing(config, &ctx.accounts.cow_mint, num_cows)?;
+        mint_cow_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.cow_mint,
+            ctx.accounts.user_cow_token_account.to_account_info(),
+            &ctx.accounts.cow_mint_authority,
+            &config.key(),
+            ctx.bumps.cow_mint_authority,
+            num_cows,
+        )?;
+
+        let vault = &mut ctx.accounts.cow_vault;
+        vault.total_shares -= shares;
+        vault.total_redeemed_cows = vault.total_redeemed_cows.saturating_add(num_cows);
+
+        emit!(CowVaultRedeemed {
+            vault: vault.key(),
+            redeemer: ctx.accounts.user.key(),
+            num_cows,
+            shares,
+            vault_cows: vault_cows - num_cows,
+            total_shares: vault.total_shares,
+            timestamp: current_time,
+        });
+
+        msg!("Redeemed {} vault shares for {} cows", shares, num_cows);
+        Ok(())
+    }
+
+    /// Reinvest the vault farm's rewards into cows. Permissionless.
+    pub fn compound_cow_vault(ctx: Context<CompoundCowVault>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.vault_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let num_cows = compound_vault_farm(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        msg!("COW vault compounded {} cows, herd now {}", num_cows, farm.cows);
+        Ok(())
+    }
+
+    /// Set up the signer's farm for yield tokenization: its claim account and
+    /// claim token mint. Done once per farm.
+    pub fn open_yield_claim(ctx: Context<OpenYieldClaim>) -> Result<()> {
+        let yield_claim = &mut ctx.accounts.yield_claim;
+        yield_claim.farm = ctx.accounts.farm.key();
+        yield_claim.claim_mint = ctx.accounts.claim_mint.key();
+        yield_claim.bump = ctx.bumps.yield_claim;
+
+        msg!("Yield claim opened for farm {}, claim mint {}", yield_claim.farm, yield_claim.claim_mint);
+        Ok(())
+    }
+
+    /// Sell forward the next `duration` seconds of the signer's harvests:
+    /// mints YIELD_CLAIM_SUPPLY claim tokens to the signer, whose holders are
+    /// owed everything the farm earns for its owner until the period ends.
+    /// The cows stay with the farm but can't leave it until then. The
+    /// previous period's claim tokens must all have been redeemed.
+    pub fn tokenize_yield(ctx: Context<TokenizeYield>, duration: i64) -> Result<()> {
+        require!(duration > 0 && duration <= MAX_YIELD_CLAIM_DURATION, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        let farm = &mut ctx.accounts.farm;
+        let yield_claim = &mut ctx.accounts.yield_claim;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(farm.cows > 0, ErrorCode::InsufficientCows);
+        require!(
+            current_time >= farm.yield_claim_until && yield_claim.supply == 0,
+            ErrorCode::YieldAlreadyTokenized
+        );
+
+        // Everything accrued before the period belongs to the owner
+        update_farm_rewards(farm, config, current_time)?;
+        farm.last_interaction_time = current_time;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        farm.yield_claim_until = current_time
+            .checked_add(duration)
+            .ok_or(ErrorCode::MathOverflow)?;
+        yield_claim.starts_at = current_time;
+        yield_claim.expires_at = farm.yield_claim_until;
+        yield_claim.supply = YIELD_CLAIM_SUPPLY;
+        yield_claim.settled_yield = 0;
+        yield_claim.total_yield = 0;
+
+        mint_yield_claim_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.claim_mint,
+            ctx.accounts.user_claim_account.to_account_info(),
+            yield_claim,
+            YIELD_CLAIM_SUPPLY,
+        )?;
+
+        msg!("Tokenized yield of farm {} until {}: {} claim tokens", 
+             farm.owner, yield_claim.expires_at, YIELD_CLAIM_SUPPLY / 1_000_000);
+        Ok(())
+    }
+
+    /// Move the yield claim holders' accrued share of a farm into its claim.
+    /// Permissionless.
+    pub fn settle_yield_claim(ctx: Context<SettleYieldClaim>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        let settled = settle_yield_owed(farm, &mut ctx.accounts.yield_claim)?;
+
+        msg!("Settled {} MILK of farm {} yield into its claim", settled / 1_000_000, farm.owner);
+        Ok(())
+    }
+
+    /// Burn claim tokens for their share of the tokenized period's yield once
+    /// the period is over
+    pub fn redeem_yield_claim(ctx: Context<RedeemYieldClaim>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let yield_claim = &mut ctx.accounts.yield_claim;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(current_time >= yield_claim.expires_at, ErrorCode::YieldClaimNotExpired);
+        require!(amount <= yield_claim.supply, ErrorCode::InvalidAmount);
+
+        update_farm_rewards(farm, config, current_time)?;
+        settle_yield_owed(farm, yield_claim)?;
+
+        let payout = rounding::mul_div(yield_claim.settled_yield, amount, yield_claim.supply, rounding::USER_CREDIT)?;
+        let payout = payable_from_pool(config, &mut ctx.accounts.pool_token_account, payout)?;
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.claim_mint.to_account_info(),
+                    from: ctx.accounts.user_claim_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        if payout > 0 {
+            transfer_from_pool(
+                &ctx.accounts.token_program,
+                &ctx.accounts.pool_token_account,
+                ctx.accounts.user_token_account.to_account_info(),
+                &ctx.accounts.pool_authority,
+                &ctx.accounts.milk_mint,
+                &config.key(),
+                ctx.bumps.pool_authority,
+                payout,
+            )?;
+            book_pool_outflow(config, payout)?;
+        }
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        yield_claim.settled_yield -= payout;
+        yield_claim.supply -= amount;
+
+        msg!("Redeemed {} claim tokens of farm {} for {} MILK", amount, farm.owner, payout / 1_000_000);
+        Ok(())
+    }
+
+    /// List the signer's farm for a manager to run in exchange for a share of harvests
+    pub fn list_farm_rental(ctx: Context<ListFarmRental>, share_bps: u16, duration: i64) -> Result<()> {
+        require!(share_bps > 0 && share_bps <= MAX_RENTAL_SHARE_BPS, ErrorCode::InvalidParameter);
+        require!(duration > 0 && duration <= MAX_RENTAL_DURATION, ErrorCode::InvalidParameter);
+
+        let rental = &mut ctx.accounts.rental;
+        rental.owner = ctx.accounts.user.key();
+        rental.farm = ctx.accounts.farm.key();
+        rental.manager = Pubkey::default();
+        rental.share_bps = share_bps;
+        rental.duration = duration;
+        rental.started_at = 0;
+        rental.expires_at = 0;
+
+        msg!("Farm {} listed for rental: {} bps manager share for {} seconds", 
+             rental.farm, share_bps, duration);
+        Ok(())
+    }
+
+    /// Take over management of a listed farm; harvests are split from now on
+    pub fn accept_farm_rental(ctx: Context<AcceptFarmRental>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let rental = &mut ctx.accounts.rental;
+        let manager_farm = &mut ctx.accounts.manager_farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(rental.manager == Pubkey::default(), ErrorCode::RentalAlreadyAccepted);
+        require!(ctx.accounts.manager.key() != farm.owner, ErrorCode::InvalidParameter);
+
+        if open_farm(manager_farm, ctx.accounts.manager.key(), config, current_time)? {
+            msg!("Initialized new farm for manager: {}", ctx.accounts.manager.key());
+        }
+
+        // Everything accrued before acceptance belongs to the owner
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        rental.manager = ctx.accounts.manager.key();
+        rental.started_at = current_time;
+        rental.expires_at = current_time
+            .checked_add(rental.duration)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        farm.rental_share_bps = rental.share_bps;
+        farm.rental_expires_at = rental.expires_at;
+        farm.rental_owed = 0;
+
+        msg!("Manager {} accepted rental of farm {} until {}", 
+             rental.manager, rental.farm, rental.expires_at);
+        Ok(())
+    }
+
+    /// Credit the manager's accrued share of a rented farm. Permissionless.
+    pub fn settle_farm_rental(ctx: Context<SettleFarmRental>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        let settled = settle_rental(farm, &mut ctx.accounts.manager_farm)?;
+
+        msg!("Settled {} MILK rental share to manager {}", settled, ctx.accounts.rental.manager);
+        Ok(())
+    }
+
+    /// End a rental: the manager may walk away at any time, the owner may
+    /// cancel an unaccepted listing or close out an expired rental
+    pub fn terminate_farm_rental(ctx: Context<TerminateFarmRental>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let rental = &ctx.accounts.rental;
+        let signer = ctx.accounts.signer.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let accepted = rental.manager != Pubkey::default();
+        let allowed = if signer == rental.manager {
+            true
+        } else if signer == rental.owner {
+            !accepted || current_time >= rental.expires_at
+        } else {
+            false
+        };
+        require!(allowed, ErrorCode::Unauthorized);
+
+        if accepted {
+            let manager_farm = ctx.accounts.manager_farm
+                .as_mut()
+                .ok_or(ErrorCode::InvalidParameter)?;
+            require!(manager_farm.owner == rental.manager, ErrorCode::InvalidParameter);
+
+            update_farm_rewards(farm, config, current_time)?;
+            cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+            let settled = settle_rental(farm, manager_farm)?;
+            msg!("Settled {} MILK rental share to manager {}", settled, rental.manager);
+        }
+
+        farm.rental_share_bps = 0;
+        farm.rental_expires_at = 0;
+
+        msg!("Rental of farm {} terminated", rental.farm);
+        Ok(())
+    }
+
+    /// Compound a rented farm's rewards as its manager
+    pub fn managed_compound(ctx: Context<ManagedCompound>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(current_time < ctx.accounts.rental.expires_at, ErrorCode::RentalExpired);
+
+        msg!("Managed compound by {} for farm owner: {}", ctx.accounts.manager.key(), farm.owner);
+        check_barn_capacity(farm, config, num_cows)?;
+        compound_farm(farm, config, current_time, num_cows)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        Ok(())
+    }
+
+    /// Let `delegate` compound the signer's farm, up to `max_cows_per_day`,
+    /// until `expires_at`. Delegates cannot withdraw. Granting again
+    /// replaces the limits.
+    pub fn grant_delegation(
+        ctx: Context<GrantDelegation>,
+        delegate: Pubkey,
+        max_cows_per_day: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(delegate != ctx.accounts.user.key(), ErrorCode::InvalidParameter);
+        require!(max_cows_per_day > 0, ErrorCode::InvalidParameter);
+        require!(
+            expires_at > current_time && expires_at - current_time <= MAX_DELEGATION_DURATION,
+            ErrorCode::InvalidParameter
+        );
+
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.farm = ctx.accounts.farm.key();
+        delegation.delegate = delegate;
+        delegation.max_cows_per_day = max_cows_per_day;
+        delegation.expires_at = expires_at;
+
+        msg!("Delegated compounding of farm {} to {}: {} cows/day until {}", 
+             ctx.accounts.user.key(), delegate, max_cows_per_day, expires_at);
+        Ok(())
+    }
+
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+        msg!("Revoked delegation of farm {} to {}", ctx.accounts.user.key(), ctx.accounts.delegation.delegate);
+        Ok(())
+    }
+
+    /// Compound a farm as its delegate, within the delegation's daily allowance
+    pub fn delegated_compound(ctx: Context<DelegatedCompound>, num_cows: u64) -> Result<()> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let delegation = &mut ctx.accounts.delegation;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(current_time < delegation.expires_at, ErrorCode::DelegationExpired);
+
+        let today = day_index(config, current_time);
+        if delegation.day != today {
+            delegation.day = today;
+            delegation.cows_today = 0;
+        }
+        let cows_today = delegation.cows_today
+            .checked_add(num_cows)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(cows_today <= delegation.max_cows_per_day, ErrorCode::DelegationAllowanceExceeded);
+        delegation.cows_today = cows_today;
+        delegation.total_cows_compounded = delegation.total_cows_compounded.saturating_add(num_cows);
+
+        msg!("Delegated compound by {} for farm owner: {} ({} of {} cows today)", 
+             delegation.delegate, farm.owner, cows_today, delegation.max_cows_per_day);
+        check_barn_capacity(farm, config, num_cows)?;
+        compound_farm(farm, config, current_time, num_cows)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+        Ok(())
+    }
+
+    /// Choose whether buys are priced in MILK or in USD converted at the oracle price
+    pub fn set_pricing_mode(
+        ctx: Context<AdminConfig>,
+        mode: PricingMode,
+        cow_base_price_usd: u64,
+        price_oracle_authority: Pubkey,
+        max_price_age: i64,
+    ) -> Result<()> {
+        if mode != PricingMode::Milk {
+            require!(cow_base_price_usd > 0, ErrorCode::InvalidParameter);
+            require!(max_price_age > 0 && max_price_age <= MAX_PRICE_AGE_LIMIT, ErrorCode::InvalidParameter);
+        }
+        if mode == PricingMode::Usd {
+            require!(price_oracle_authority != Pubkey::default(), ErrorCode::InvalidParameter);
+        }
+
+        let config = &mut ctx.accounts.config;
+        if mode == PricingMode::Pyth {
+            require!(config.pyth_price_account != Pubkey::default(), ErrorCode::MissingPriceFeed);
+        }
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::PricingMode, ParamValue::PricingMode(config.pricing_mode), ParamValue::PricingMode(mode)),
+            (ConfigParam::CowBasePriceUsd, ParamValue::Unsigned(config.cow_base_price_usd), ParamValue::Unsigned(cow_base_price_usd)),
+            (ConfigParam::PriceOracleAuthority, ParamValue::Key(config.price_oracle_authority), ParamValue::Key(price_oracle_authority)),
+            (ConfigParam::MaxPriceAge, ParamValue::Signed(config.max_price_age), ParamValue::Signed(max_price_age)),
+        ];
+
+        config.pricing_mode = mode;
+        config.cow_base_price_usd = cow_base_price_usd;
+        config.price_oracle_authority = price_oracle_authority;
+        config.max_price_age = max_price_age;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Pricing mode set to {:?}, base price: {} micro-USD, oracle: {}", 
+             mode, cow_base_price_usd, price_oracle_authority);
+        Ok(())
+    }
+
+    /// Point Pyth pricing mode at a MILK/USD price update account and set the
+    /// widest confidence interval accepted, in basis points of the price
+    pub fn set_pyth_price_feed(
+        ctx: Context<AdminConfig>,
+        price_account: Pubkey,
+        max_conf_bps: u16,
+    ) -> Result<()> {
+        require!(price_account != Pubkey::default(), ErrorCode::InvalidParameter);
+        require!(max_conf_bps > 0 && max_conf_bps <= MAX_PRICE_CONF_BPS, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::PythPriceAccount, ParamValue::Key(config.pyth_price_account), ParamValue::Key(price_account)),
+            (ConfigParam::MaxPriceConfBps, ParamValue::Unsigned(config.max_price_conf_bps as u64), ParamValue::Unsigned(max_conf_bps as u64)),
+        ];
+
+        config.pyth_price_account = price_account;
+        config.max_price_conf_bps = max_conf_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Pyth MILK/USD feed: {}, max confidence {} bps", price_account, max_conf_bps);
+        Ok(())
+    }
+
+    /// Price and perks of the Premium Dairy subscription. A zero fee stops
+    /// new subscriptions; paid-up periods keep their perks at the current
+    /// settings.
+    pub fn set_premium_tier(
+        ctx: Context<AdminConfig>,
+        fee: u64,
+        period: i64,
+        buy_cap_bps: u16,
+        penalty_discount_bps: u16,
+        yield_bonus_bps: u16,
+    ) -> Result<()> {
+        if fee > 0 {
+            require!((MIN_PREMIUM_PERIOD..=MAX_PREMIUM_PERIOD).contains(&period), ErrorCode::InvalidParameter);
+        }
+        require!(buy_cap_bps <= MAX_PREMIUM_BUY_CAP_BPS, ErrorCode::InvalidParameter);
+        require!(penalty_discount_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+        require!(yield_bonus_bps <= MAX_PREMIUM_YIELD_BONUS_BPS, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::PremiumFee, ParamValue::Unsigned(config.premium_fee), ParamValue::Unsigned(fee)),
+            (ConfigParam::PremiumPeriod, ParamValue::Signed(config.premium_period), ParamValue::Signed(period)),
+            (ConfigParam::PremiumBuyCapBps, ParamValue::Unsigned(config.premium_buy_cap_bps as u64), ParamValue::Unsigned(buy_cap_bps as u64)),
+            (ConfigParam::PremiumPenaltyDiscountBps, ParamValue::Unsigned(config.premium_penalty_discount_bps as u64), ParamValue::Unsigned(penalty_discount_bps as u64)),
+            (ConfigParam::PremiumYieldBonusBps, ParamValue::Unsigned(config.premium_yield_bonus_bps as u64), ParamValue::Unsigned(yield_bonus_bps as u64)),
+        ];
+
+        config.premium_fee = fee;
+        config.premium_period = period;
+        config.premium_buy_cap_bps = buy_cap_bps;
+        config.premium_penalty_discount_bps = penalty_discount_bps;
+        config.premium_yield_bonus_bps = yield_bonus_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Premium Dairy: {} MILK per {}s, buy cap +{} bps, penalty -{} bps, yield +{} bps", 
+             fee / 1_000_000, period, buy_cap_bps, penalty_discount_bps, yield_bonus_bps);
+        Ok(())
+    }
+
+    /// Share of the curve value `emergency_withdraw` pays out, in basis
+    /// points. Zero turns the escape hatch off.
+    pub fn set_emergency_exit_bps(ctx: Context<AdminConfig>, exit_bps: u16) -> Result<()> {
+        require!(exit_bps <= MAX_EMERGENCY_EXIT_BPS, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let old_value = config.emergency_exit_bps;
+        config.emergency_exit_bps = exit_bps;
+
+        record_param_change(
+            config,
+            ConfigParam::EmergencyExitBps,
+            ParamValue::Unsigned(old_value as u64),
+            ParamValue::Unsigned(exit_bps as u64),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Emergency exit pays {} bps of curve value", exit_bps);
+        Ok(())
+    }
+
+    /// Route `bps` of one value flow to `destination`, a MILK token account,
+    /// so forks can re-route value without code changes. Zero bps turns the
+    /// hook off and releases anything earmarked but not yet swept back to
+    /// the pool.
+    pub fn set_fee_hook(
+        ctx: Context<AdminConfig>,
+        kind: FeeHookKind,
+        destination: Pubkey,
+        bps: u16,
+    ) -> Result<()> {
+        let max_bps = match kind {
+            FeeHookKind::BuyFee => MAX_BUY_FEE_HOOK_BPS,
+            _ => BPS_DENOMINATOR as u16,
+        };
+        require!(bps <= max_bps, ErrorCode::InvalidParameter);
+        require!(bps == 0 || destination != Pubkey::default(), ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let hook = &mut config.fee_hooks[kind as usize];
+        let changes = [
+            (ConfigParam::FeeHookDestination(kind), ParamValue::Key(hook.destination), ParamValue::Key(destination)),
+            (ConfigParam::FeeHookBps(kind), ParamValue::Unsigned(hook.bps as u64), ParamValue::Unsigned(bps as u64)),
+        ];
+
+        hook.destination = destination;
+        hook.bps = bps;
+        if bps == 0 {
+            config.fee_hook_pending[kind as usize] = 0;
+        }
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Fee hook {:?}: {} bps to {}", kind, bps, destination);
+        Ok(())
+    }
+
+    /// Publish the MILK/USD price used by USD pricing mode
+    pub fn update_milk_price(ctx: Context<UpdateMilkPrice>, price_micro_usd: u64) -> Result<()> {
+        require!(price_micro_usd > 0, ErrorCode::InvalidParameter);
+
+        let milk_price = &mut ctx.accounts.milk_price;
+        milk_price.price_micro_usd = price_micro_usd;
+        milk_price.updated_at = Clock::get()?.unix_timestamp;
+
+        msg!("MILK price updated: {} micro-USD", price_micro_usd);
+        Ok(())
+    }
+
+    pub fn set_efficiency_decay(
+        ctx: Context<AdminConfig>,
+        grace_seconds: i64,
+        decay_bps_per_day: u16,
+        floor_bps: u16,
+    ) -> Result<()> {
+        require!(grace_seconds >= 0, ErrorCode::InvalidParameter);
+        require!(decay_bps_per_day as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+        require!(floor_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::EfficiencyGrace, ParamValue::Signed(config.efficiency_grace_seconds), ParamValue::Signed(grace_seconds)),
+            (ConfigParam::EfficiencyDecayBpsPerDay, ParamValue::Unsigned(config.efficiency_decay_bps_per_day as u64), ParamValue::Unsigned(decay_bps_per_day as u64)),
+            (ConfigParam::EfficiencyFloorBps, ParamValue::Unsigned(config.efficiency_floor_bps as u64), ParamValue::Unsigned(floor_bps as u64)),
+        ];
+
+        config.efficiency_grace_seconds = grace_seconds;
+        config.efficiency_decay_bps_per_day = decay_bps_per_day;
+        config.efficiency_floor_bps = floor_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Efficiency decay: -{} bps/day after {}s idle, floor {} bps", 
+             decay_bps_per_day, grace_seconds, floor_bps);
+        Ok(())
+    }
+
+    /// Feeding: how often farms must call `feed_cows`, what it costs per
+    /// cow and the yield lost per period missed. An interval of 0 turns
+    /// feeding off; turning it on counts every farm as fed at that moment.
+    pub fn set_feed_params(
+        ctx: Context<AdminConfig>,
+        interval: i64,
+        cost_per_cow: u64,
+        decay_bps: u16,
+    ) -> Result<()> {
+        if interval != 0 {
+            require!(
+                (MIN_FEED_INTERVAL..=MAX_FEED_INTERVAL).contains(&interval),
+                ErrorCode::InvalidParameter
+            );
+            require!(
+                decay_bps >= MIN_FEED_DECAY_BPS && decay_bps as u64 <= BPS_DENOMINATOR,
+                ErrorCode::InvalidParameter
+            );
+        }
+        require!(cost_per_cow <= MAX_FEED_COST_PER_COW, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::FeedInterval, ParamValue::Signed(config.feed_interval), ParamValue::Signed(interval)),
+            (ConfigParam::FeedCostPerCow, ParamValue::Unsigned(config.feed_cost_per_cow), ParamValue::Unsigned(cost_per_cow)),
+            (ConfigParam::FeedDecayBps, ParamValue::Unsigned(config.feed_decay_bps as u64), ParamValue::Unsigned(decay_bps as u64)),
+        ];
+
+        if config.feed_interval == 0 && interval > 0 {
+            config.feed_started_at = Clock::get()?.unix_timestamp;
+        }
+        config.feed_interval = interval;
+        config.feed_cost_per_cow = cost_per_cow;
+        config.feed_decay_bps = decay_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Feeding: every {}s at {} MILK per cow, -{} bps per missed period", 
+             interval, cost_per_cow / 1_000_000, decay_bps);
+        Ok(())
+    }
+
+    /// Schedule a limited-time seasonal event: between `start` and `end`
+    /// the reward rate is multiplied by `reward_multiplier` bps and cow
+    /// purchases are `price_discount` bps cheaper. One event at a time; the
+    /// next can be scheduled once the last has ended.
+    pub fn create_event(
+        ctx: Context<CreateEvent>,
+        start: i64,
+        end: i64,
+        reward_multiplier: u16,
+        price_discount: u16,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            start >= current_time && end > start && end - start <= MAX_EVENT_DURATION,
+            ErrorCode::InvalidParameter
+        );
+        require!(
+            reward_multiplier as u64 >= BPS_DENOMINATOR && reward_multiplier <= MAX_EVENT_REWARD_BPS,
+            ErrorCode::InvalidParameter
+        );
+        require!(price_discount <= MAX_EVENT_PRICE_DISCOUNT_BPS, ErrorCode::InvalidParameter);
+        require!(config.event_ends_at <= current_time, ErrorCode::EventOverlap);
+
+        // Settle the accumulator under the old schedule before replacing it
+        sync_reward_accumulator(config, current_time)?;
+
+        let event = &mut ctx.accounts.event;
+        event.id = config.event_count;
+        event.starts_at = start;
+        event.ends_at = end;
+        event.reward_bps = reward_multiplier;
+        event.price_discount_bps = price_discount;
+        event.created_at = current_time;
+
+        config.event_count += 1;
+        config.event_starts_at = start;
+        config.event_ends_at = end;
+        config.event_reward_bps = reward_multiplier;
+        config.event_price_discount_bps = price_discount;
+
+        emit!(EventScheduled {
+            id: event.id,
+            starts_at: start,
+            ends_at: end,
+            reward_bps: reward_multiplier,
+            price_discount_bps: price_discount,
+            timestamp: current_time,
+        });
+
+        msg!("Event {} scheduled from {} to {}: {} bps rewards, {} bps off cows", 
+             event.id, start, end, reward_multiplier, price_discount);
+        Ok(())
+    }
+
+    /// End the latest event now, or drop it if it hasn't started yet
+    pub fn cancel_event(ctx: Context<CancelEvent>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(config.event_ends_at > current_time, ErrorCode::EventEnded);
+
+        // Accrue the event's boost up to now before cutting it short
+        sync_reward_accumulator(config, current_time)?;
+
+        let ends_at = current_time.max(config.event_starts_at);
+        config.event_ends_at = ends_at;
+        ctx.accounts.event.ends_at = ends_at;
+
+        emit!(EventCancelled {
+            id: ctx.accounts.event.id,
+            ends_at,
+            timestamp: current_time,
+        });
+
+        msg!("Event {} cancelled", ctx.accounts.event.id);
+        Ok(())
+    }
+
+    /// Breeding: the MILK fee and the odds of each outcome in bps. The odds
+    /// must sum to 100%, or all be 0 to turn breeding off.
+    pub fn set_breeding_params(
+        ctx: Context<AdminConfig>,
+        fee: u64,
+        odds: [u16; BREEDING_OUTCOMES],
+    ) -> Result<()> {
+        require!(fee <= MAX_BREEDING_FEE, ErrorCode::InvalidParameter);
+        let total: u64 = odds.iter().map(|o| *o as u64).sum();
+        require!(total == 0 || total == BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let mut changes = Vec::with_capacity(BREEDING_OUTCOMES + 1);
+        changes.push((
+            ConfigParam::BreedingFee,
+            ParamValue::Unsigned(config.breeding_fee),
+            ParamValue::Unsigned(fee),
+        ));
+        for outcome in [
+            BreedingOutcome::OneCalf,
+            BreedingOutcome::TwoCalves,
+            BreedingOutcome::ThreeCalves,
+            BreedingOutcome::RareCalf,
+        ] {
+            changes.push((
+                ConfigParam::BreedingOddsBps(outcome),
+                ParamValue::Unsigned(config.breeding_odds[outcome as usize] as u64),
+                ParamValue::Unsigned(odds[outcome as usize] as u64),
+            ));
+        }
+
+        config.breeding_fee = fee;
+        config.breeding_odds = odds;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Breeding: {} MILK fee, odds {:?} bps", fee / 1_000_000, odds);
+        Ok(())
+    }
+
+    pub fn set_withdrawal_escrow(ctx: Context<AdminConfig>, threshold: u64, window: i64) -> Result<()> {
+        if threshold > 0 {
+            require!(window > 0 && window <= MAX_ESCROW_WINDOW, ErrorCode::InvalidParameter);
+        }
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::EscrowThreshold, ParamValue::Unsigned(config.escrow_threshold), ParamValue::Unsigned(threshold)),
+            (ConfigParam::EscrowWindow, ParamValue::Signed(config.escrow_window), ParamValue::Signed(window)),
+        ];
+
+        config.escrow_threshold = threshold;
+        config.escrow_window = window;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Withdrawals above {} MILK now escrowed for {} seconds", threshold / 1_000_000, window);
+        Ok(())
+    }
+
+    pub fn set_pauser(ctx: Context<AdminConfig>, pauser: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_value = config.pauser;
+        config.pauser = pauser;
+
+        record_param_change(
+            config,
+            ConfigParam::Pauser,
+            ParamValue::Key(old_value),
+            ParamValue::Key(pauser),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Pauser set to {}", pauser);
+        Ok(())
+    }
+
+    /// Halt the game: buys, sells, imports, exports, compounding and
+    /// withdrawals all fail until `unpause`. Rewards keep accruing.
+    pub fn pause(ctx: Context<AdminConfig>) -> Result<()> {
+        set_paused(&mut ctx.accounts.config, true, ctx.accounts.admin.key())
+    }
+
+    /// Lift a pause, including one raised by the upgrade authority guard
+    pub fn unpause(ctx: Context<AdminConfig>) -> Result<()> {
+        set_paused(&mut ctx.accounts.config, false, ctx.accounts.admin.key())
+    }
+
+    pub fn set_buy_cooldown(ctx: Context<AdminConfig>, seconds: i64) -> Result<()> {
+        require!(
+            (0..=MAX_BUY_COOLDOWN).contains(&seconds),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_value = config.buy_cooldown_seconds;
+        config.buy_cooldown_seconds = seconds;
+
+        record_param_change(
+            config,
+            ConfigParam::BuyCooldown,
+            ParamValue::Signed(old_value),
+            ParamValue::Signed(seconds),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Buy cooldown set to {} seconds", seconds);
+        Ok(())
+    }
+
+    pub fn set_streak_bonus(ctx: Context<AdminConfig>, days_required: u8, bonus_bps: u16) -> Result<()> {
+        require!(days_required <= MAX_STREAK_DAYS, ErrorCode::InvalidParameter);
+        require!(bonus_bps <= MAX_STREAK_BONUS_BPS, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::StreakDaysRequired, ParamValue::Unsigned(config.streak_days_required as u64), ParamValue::Unsigned(days_required as u64)),
+            (ConfigParam::StreakBonusBps, ParamValue::Unsigned(config.streak_bonus_bps as u64), ParamValue::Unsigned(bonus_bps as u64)),
+        ];
+
+        config.streak_days_required = days_required;
+        config.streak_bonus_bps = bonus_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Compound streak bonus set to {} bps after {} consecutive days", bonus_bps, days_required);
+        Ok(())
+    }
+
+    /// Farms earn a penalty forgiveness pass for every `milestone_cows` cows
+    /// they compound, holding at most `max_passes` (0 milestone = off)
+    pub fn set_forgiveness_passes(ctx: Context<AdminConfig>, milestone_cows: u64, max_passes: u8) -> Result<()> {
+        require!(max_passes <= MAX_FORGIVENESS_PASSES, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::ForgivenessMilestoneCows, ParamValue::Unsigned(config.forgiveness_milestone_cows), ParamValue::Unsigned(milestone_cows)),
+            (ConfigParam::MaxForgivenessPasses, ParamValue::Unsigned(config.max_forgiveness_passes as u64), ParamValue::Unsigned(max_passes as u64)),
+        ];
+
+        config.forgiveness_milestone_cows = milestone_cows;
+        config.max_forgiveness_passes = max_passes;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Forgiveness passes: one per {} compounded cows, up to {} held", milestone_cows, max_passes);
+        Ok(())
+    }
+
+    /// Bound how fast economic parameters can move: each change may shift a
+    /// value by at most `max_step_bps` of its current value, and changes must
+    /// be `cooldown` seconds apart. The limits are themselves rate limited.
+    pub fn set_param_rate_limits(ctx: Context<AdminConfig>, max_step_bps: u16, cooldown: i64) -> Result<()> {
+        require!(max_step_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParameter);
+        require!((0..=MAX_PARAM_UPDATE_COOLDOWN).contains(&cooldown), ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::ParamMaxStepBps, ParamValue::Unsigned(config.param_max_step_bps as u64), ParamValue::Unsigned(max_step_bps as u64)),
+            (ConfigParam::ParamUpdateCooldown, ParamValue::Signed(config.param_update_cooldown), ParamValue::Signed(cooldown)),
+        ];
+
+        // Checked against the limits in force, so they can't be lifted in
+        // the same change they govern
+        record_param_changes(config, &changes, admin)?;
+
+        config.param_max_step_bps = max_step_bps;
+        config.param_update_cooldown = cooldown;
+
+        msg!("Parameter rate limits: {} bps per step, {}s between changes", max_step_bps, cooldown);
+        Ok(())
+    }
+
+    /// Record the program's live upgrade authority in Config along with the
+    /// timelock it operates under, so users can check on-chain who can
+    /// upgrade the program. With the guard on, anyone can pause the game if
+    /// the authority later changes. Recording again lifts a guard pause, but
+    /// not a pause set through the circuit breaker.
+    pub fn record_upgrade_authority(
+        ctx: Context<RecordUpgradeAuthority>,
+        timelock_seconds: i64,
+        guard_enabled: bool,
+    ) -> Result<()> {
+        require!((0..=MAX_UPGRADE_TIMELOCK).contains(&timelock_seconds), ErrorCode::InvalidTimelock);
+
+        let config = &mut ctx.accounts.config;
+        let authority = ctx.accounts.program_data.upgrade_authority_address.unwrap_or_default();
+        let old_value = config.upgrade_authority;
+
+        config.upgrade_authority = authority;
+        config.upgrade_authority_recorded_at = Clock::get()?.unix_timestamp;
+        config.upgrade_timelock_seconds = timelock_seconds;
+        config.upgrade_guard_enabled = guard_enabled;
+        if config.upgrade_guard_tripped {
+            if config.final_milking_started_at == 0 {
+                set_paused(config, false, ctx.accounts.admin.key())?;
+            }
+            config.upgrade_guard_tripped = false;
+        }
+
+        record_param_change(
+            config,
+            ConfigParam::UpgradeAuthority,
+            ParamValue::Key(old_value),
+            ParamValue::Key(authority),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Upgrade authority recorded: {} ({}s timelock, guard {})", 
+             authority, timelock_seconds, if guard_enabled { "on" } else { "off" });
+        Ok(())
+    }
+
+    /// Compare the live upgrade authority with the recorded one and pause the
+    /// game on a mismatch if the guard is on. Permissionless.
+    pub fn check_upgrade_authority(ctx: Context<CheckUpgradeAuthority>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let current_authority = ctx.accounts.program_data.upgrade_authority_address.unwrap_or_default();
+
+        if current_authority == config.upgrade_authority {
+            msg!("Upgrade authority matches the recorded {}", current_authority);
+            return Ok(());
+        }
+
+        // An existing pause stays with whoever set it, so recording the
+        // authority again won't lift it. There is no signer here, so the
+        // change is logged under the default key.
+        if config.upgrade_guard_enabled && !config.paused {
+            set_paused(config, true, Pubkey::default())?;
+            config.upgrade_guard_tripped = true;
+        }
+
+        emit!(UpgradeAuthorityMismatch {
+            recorded_authority: config.upgrade_authority,
+            current_authority,
+            paused: config.paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Upgrade authority changed from {} to {}. Game paused: {}", 
+             config.upgrade_authority, current_authority, config.paused);
+        Ok(())
+    }
+
+    /// Hand out test MILK from the faucet vault, once per wallet per day.
+    /// Only compiled into devnet builds.
+    #[cfg(feature = "devnet")]
+    pub fn faucet_milk(ctx: Context<FaucetMilk>) -> Result<()> {
+        let claim = &mut ctx.accounts.faucet_claim;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            claim.last_claim_time == 0 || current_time - claim.last_claim_time >= SECONDS_PER_DAY,
+            ErrorCode::FaucetCooldownActive
+        );
+
+        let amount = FAUCET_AMOUNT.min(ctx.accounts.faucet_vault.amount);
+        require!(amount > 0, ErrorCode::FaucetEmpty);
+
+        let config_key = ctx.accounts.config.key();
+        let seeds = &[
+            b"faucet_authority",
+            config_key.as_ref(),
+            &[ctx.bumps.faucet_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.faucet_vault.to_account_info(),
+                    mint: ctx.accounts.milk_mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.faucet_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.milk_mint.decimals,
+        )?;
+
+        claim.last_claim_time = current_time;
+        claim.total_claimed = claim.total_claimed.saturating_add(amount);
+
+        msg!("Faucet sent {} test MILK to {}", amount / 1_000_000, ctx.accounts.user.key());
+        Ok(())
+    }
+
+    /// Configure starter packs: free cows per new wallet (0 = off) and an
+    /// optional gatekeeper that must co-sign every claim
+    pub fn set_starter_pack(ctx: Context<AdminConfig>, cows: u64, gatekeeper: Pubkey) -> Result<()> {
+        require!(cows <= MAX_STARTER_PACK_COWS, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::StarterPackCows, ParamValue::Unsigned(config.starter_pack_cows), ParamValue::Unsigned(cows)),
+            (ConfigParam::StarterPackGatekeeper, ParamValue::Key(config.starter_pack_gatekeeper), ParamValue::Key(gatekeeper)),
+        ];
+
+        config.starter_pack_cows = cows;
+        config.starter_pack_gatekeeper = gatekeeper;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Starter pack: {} cows, gatekeeper {}", cows, gatekeeper);
+        Ok(())
+    }
+
+    /// Pause reward accrual whenever the pool falls below `threshold` (0 =
+    /// off), and set the rate, in MILK per cow per day, at which paused time
+    /// is credited back once the pool recovers
+    pub fn set_solvency_pause(ctx: Context<AdminConfig>, threshold: u64, catch_up_rate: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::SolvencyThreshold, ParamValue::Unsigned(config.solvency_threshold), ParamValue::Unsigned(threshold)),
+            (ConfigParam::CatchUpRate, ParamValue::Unsigned(config.catch_up_rate), ParamValue::Unsigned(catch_up_rate)),
+        ];
+
+        config.solvency_threshold = threshold;
+        config.catch_up_rate = catch_up_rate;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Solvency pause below {} MILK, catch-up at {} MILK/cow/day", 
+             threshold / 1_000_000, catch_up_rate / 1_000_000);
+        Ok(())
+    }
+
+    /// Exempt small farms from the early-withdrawal penalty: those with fewer
+    /// than `below_cows` cows, or withdrawing less than `below_rewards` MILK.
+    /// Either threshold can be 0 to disable it.
+    pub fn set_penalty_exemption(ctx: Context<AdminConfig>, below_cows: u64, below_rewards: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::PenaltyExemptBelowCows, ParamValue::Unsigned(config.penalty_exempt_below_cows), ParamValue::Unsigned(below_cows)),
+            (ConfigParam::PenaltyExemptBelowRewards, ParamValue::Unsigned(config.penalty_exempt_below_rewards), ParamValue::Unsigned(below_rewards)),
+        ];
+
+        config.penalty_exempt_below_cows = below_cows;
+        config.penalty_exempt_below_rewards = below_rewards;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Penalty exemption: below {} cows or {} MILK", below_cows, below_rewards / 1_000_000);
+        Ok(())
+    }
+
+    /// Vesting period for streamed withdrawals. Zero turns `withdraw_streamed`
+    /// off; existing streams keep their schedules.
+    pub fn set_stream_period(ctx: Context<AdminConfig>, period: i64) -> Result<()> {
+        require!(
+            period == 0 || (MIN_STREAM_PERIOD..=MAX_STREAM_PERIOD).contains(&period),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_value = config.stream_period;
+        config.stream_period = period;
+
+        record_param_change(
+            config,
+            ConfigParam::StreamPeriod,
+            ParamValue::Signed(old_value),
+            ParamValue::Signed(period),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Streamed withdrawals vest over {}s", period);
+        Ok(())
+    }
+
+    /// Open a cosmetic season, making its seasonal cosmetics unlockable and
+    /// closing the previous season's. Zero leaves only the evergreen ones.
+    pub fn set_cosmetic_season(ctx: Context<AdminConfig>, season: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_value = config.cosmetic_season;
+        config.cosmetic_season = season;
+
+        record_param_change(
+            config,
+            ConfigParam::CosmeticSeason,
+            ParamValue::Unsigned(old_value as u64),
+            ParamValue::Unsigned(season as u64),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Cosmetic season {} open", season);
+        Ok(())
+    }
+
+    /// Reward boost for locking rewards for each of the LOCK_DURATIONS
+    /// (7, 30 and 90 days). A zero turns that term off; running locks keep
+    /// the boost they started with.
+    pub fn set_lock_boosts(ctx: Context<AdminConfig>, boost_bps: [u16; LOCK_TIERS]) -> Result<()> {
+        require!(boost_bps.iter().all(|b| *b <= MAX_LOCK_BOOST_BPS), ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes: Vec<_> = (0..LOCK_TIERS)
+            .map(|tier| (
+                ConfigParam::LockBoostBps(tier as u8),
+                ParamValue::Unsigned(config.lock_boost_bps[tier] as u64),
+                ParamValue::Unsigned(boost_bps[tier] as u64),
+            ))
+            .collect();
+
+        config.lock_boost_bps = boost_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Reward lock boosts: 7d +{} bps, 30d +{} bps, 90d +{} bps", 
+             boost_bps[0], boost_bps[1], boost_bps[2]);
+        Ok(())
+    }
+
+    /// Price and yield multipliers of each cow rarity, and how many cows of
+    /// the tier below an upgrade consumes. Common stays at 1x. A tier is
+    /// never worth or earning more than the cows it consumes, so upgrading
+    /// can't mint value. A ratio of 0 turns upgrades off.
+    pub fn set_cow_tiers(ctx: Context<AdminConfig>, tiers: [CowTier; COW_RARITIES], upgrade_ratio: u8) -> Result<()> {
+        let base = BPS_DENOMINATOR as u16;
+        require!(
+            tiers[0] == CowTier { price_bps: base, reward_bps: base },
+            ErrorCode::InvalidParameter
+        );
+        require!(upgrade_ratio <= MAX_COW_UPGRADE_RATIO, ErrorCode::InvalidParameter);
+        for rarity in 1..COW_RARITIES {
+            let (tier, lower) = (tiers[rarity], tiers[rarity - 1]);
+            let max_price = (lower.price_bps as u64 * upgrade_ratio.max(1) as u64).min(MAX_COW_TIER_BPS as u64);
+            let max_reward = (lower.reward_bps as u64 * upgrade_ratio.max(1) as u64).min(MAX_COW_TIER_BPS as u64);
+            require!(
+                tier.price_bps >= lower.price_bps && tier.price_bps as u64 <= max_price,
+                ErrorCode::InvalidParameter
+            );
+            require!(
+                tier.reward_bps >= lower.reward_bps && tier.reward_bps as u64 <= max_reward,
+                ErrorCode::InvalidParameter
+            );
+        }
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let mut changes = Vec::with_capacity(2 * COW_RARITIES + 1);
+        for (rarity, kind) in [(1, CowRarity::Rare), (2, CowRarity::Epic)] {
+            changes.push((
+                ConfigParam::CowTierPriceBps(kind),
+                ParamValue::Unsigned(config.cow_tiers[rarity].price_bps as u64),
+                ParamValue::Unsigned(tiers[rarity].price_bps as u64),
+            ));
+            changes.push((
+                ConfigParam::CowTierRewardBps(kind),
+                ParamValue::Unsigned(config.cow_tiers[rarity].reward_bps as u64),
+                ParamValue::Unsigned(tiers[rarity].reward_bps as u64),
+            ));
+        }
+        changes.push((
+            ConfigParam::CowUpgradeRatio,
+            ParamValue::Unsigned(config.cow_upgrade_ratio as u64),
+            ParamValue::Unsigned(upgrade_ratio as u64),
+        ));
+
+        config.cow_tiers = tiers;
+        config.cow_upgrade_ratio = upgrade_ratio;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Cow tiers: Rare {}/{} bps, Epic {}/{} bps (price/yield), upgrade ratio {}", 
+             tiers[1].price_bps, tiers[1].reward_bps, tiers[2].price_bps, tiers[2].reward_bps, upgrade_ratio);
+        Ok(())
+    }
+
+    /// Barn capacity: cows a level-0 barn holds, extra cows per level and
+    /// the MILK burned for the first upgrade. A base capacity of 0 lifts the
+    /// cap. Farms already over a lowered cap keep their cows but can't add
+    /// more.
+    pub fn set_barn_params(
+        ctx: Context<AdminConfig>,
+        base_capacity: u64,
+        capacity_per_level: u64,
+        upgrade_cost: u64,
+    ) -> Result<()> {
+        require!(
+            base_capacity == 0 || (capacity_per_level > 0 && upgrade_cost > 0),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (
+                ConfigParam::BarnBaseCapacity,
+                ParamValue::Unsigned(config.barn_base_capacity),
+                ParamValue::Unsigned(base_capacity),
+            ),
+            (
+                ConfigParam::BarnCapacityPerLevel,
+                ParamValue::Unsigned(config.barn_capacity_per_level),
+                ParamValue::Unsigned(capacity_per_level),
+            ),
+            (
+                ConfigParam::BarnUpgradeCost,
+                ParamValue::Unsigned(config.barn_upgrade_cost),
+                ParamValue::Unsigned(upgrade_cost),
+            ),
+        ];
+
+        config.barn_base_capacity = base_capacity;
+        config.barn_capacity_per_level = capacity_per_level;
+        config.barn_upgrade_cost = upgrade_cost;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Barns: {} cows at level 0, +{} per level, first upgrade {} MILK", 
+             base_capacity, capacity_per_level, upgrade_cost / 1_000_000);
+        Ok(())
+    }
+
+    /// MILK emitted per day to COW stakers, shared pro rata. Emission up to
+    /// now is accrued at the old rate first.
+    pub fn set_cow_staking_emission(ctx: Context<SetCowStakingEmission>, emission_per_day: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let pool = &mut ctx.accounts.staking_pool;
+        accrue_staking(pool, Clock::get()?.unix_timestamp)?;
+
+        let old_value = pool.emission_per_day;
+        pool.emission_per_day = emission_per_day;
+
+        record_param_change(
+            config,
+            ConfigParam::CowStakingEmission,
+            ParamValue::Unsigned(old_value),
+            ParamValue::Unsigned(emission_per_day),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("COW staking emission: {} MILK per day", emission_per_day / 1_000_000);
+        Ok(())
+    }
+
+    /// Set the referrer's share of referred purchases and of referred
+    /// compounds. The compound share may not exceed the purchase share.
+    /// Configure the progressive tax on large withdrawals. Bracket `i` taxes
+    /// the part of a withdrawal above `thresholds[i]` (and below the next
+    /// threshold) at `rates_bps[i]`. Thresholds must increase and rates may
+    /// not fall from one bracket to the next; all-zero rates turn it off.
+    /// Replace the price and reward curve parameters. Every parameter is
+    /// bounds-checked and subject to the rate limits on economic changes.
+    pub fn update_config(ctx: Context<AdminConfig>, params: EconomicParams) -> Result<()> {
+        params.validate()?;
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let old = economic_params(config);
+        let changes = [
+            (ConfigParam::CowBasePrice, ParamValue::Unsigned(old.cow_base_price), ParamValue::Unsigned(params.cow_base_price)),
+            (ConfigParam::PricePivot, ParamValue::Unsigned(old.price_pivot), ParamValue::Unsigned(params.price_pivot)),
+            (ConfigParam::PriceSteepnessBps, ParamValue::Unsigned(old.price_steepness_bps), ParamValue::Unsigned(params.price_steepness_bps)),
+            (ConfigParam::RewardBase, ParamValue::Unsigned(old.reward_base), ParamValue::Unsigned(params.reward_base)),
+            (ConfigParam::RewardSensitivityBps, ParamValue::Unsigned(old.reward_sensitivity_bps), ParamValue::Unsigned(params.reward_sensitivity_bps)),
+            (ConfigParam::TvlNormalization, ParamValue::Unsigned(old.tvl_normalization), ParamValue::Unsigned(params.tvl_normalization)),
+            (ConfigParam::MinRewardPerDay, ParamValue::Unsigned(old.min_reward_per_day), ParamValue::Unsigned(params.min_reward_per_day)),
+            (ConfigParam::GreedMultiplierBps, ParamValue::Unsigned(old.greed_multiplier_bps), ParamValue::Unsigned(params.greed_multiplier_bps)),
+            (ConfigParam::GreedDecayPivot, ParamValue::Unsigned(old.greed_decay_pivot), ParamValue::Unsigned(params.greed_decay_pivot)),
+        ];
+
+        config.economic_params = params;
+        sync_reward_accumulator(config, Clock::get()?.unix_timestamp)?;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Economic params updated - price: {} at {} cows, α {} bps; reward base {}, min {} MILK/cow/day", 
+             params.cow_base_price, params.price_pivot, params.price_steepness_bps,
+             params.reward_base / 1_000_000, params.min_reward_per_day / 1_000_000);
+        Ok(())
+    }
+
+    pub fn set_withdrawal_tax(
+        ctx: Context<AdminConfig>,
+        thresholds: [u64; WITHDRAWAL_TAX_TIERS],
+        rates_bps: [u16; WITHDRAWAL_TAX_TIERS],
+    ) -> Result<()> {
+        require!(rates_bps.iter().all(|&bps| bps <= MAX_WITHDRAWAL_TAX_BPS), ErrorCode::InvalidParameter);
+        require!(rates_bps.windows(2).all(|w| w[0] <= w[1]), ErrorCode::InvalidParameter);
+        if rates_bps.iter().any(|&bps| bps > 0) {
+            require!(thresholds[0] > 0, ErrorCode::InvalidParameter);
+            require!(thresholds.windows(2).all(|w| w[0] < w[1]), ErrorCode::InvalidParameter);
+        }
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let mut changes = Vec::with_capacity(2 * WITHDRAWAL_TAX_TIERS);
+        for tier in 0..WITHDRAWAL_TAX_TIERS {
+            changes.push((
+                ConfigParam::WithdrawalTaxThreshold,
+                ParamValue::Unsigned(config.withdrawal_tax_thresholds[tier]),
+                ParamValue::Unsigned(thresholds[tier]),
+            ));
+            changes.push((
+                ConfigParam::WithdrawalTaxBps,
+                ParamValue::Unsigned(config.withdrawal_tax_bps[tier] as u64),
+                ParamValue::Unsigned(rates_bps[tier] as u64),
+            ));
+        }
+
+        config.withdrawal_tax_thresholds = thresholds;
+        config.withdrawal_tax_bps = rates_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Withdrawal tax brackets: {:?} MILK units at {:?} bps", thresholds, rates_bps);
+        Ok(())
+    }
+
+    pub fn set_referral_rates(ctx: Context<AdminConfig>, buy_bps: u16, compound_bps: u16) -> Result<()> {
+        require!(buy_bps <= MAX_REFERRAL_BPS, ErrorCode::InvalidParameter);
+        require!(compound_bps <= buy_bps, ErrorCode::InvalidParameter);
+
+        let config = &mut ctx.accounts.config;
+        let admin = ctx.accounts.admin.key();
+        let changes = [
+            (ConfigParam::ReferralBuyBps, ParamValue::Unsigned(config.referral_buy_bps as u64), ParamValue::Unsigned(buy_bps as u64)),
+            (ConfigParam::ReferralCompoundBps, ParamValue::Unsigned(config.referral_compound_bps as u64), ParamValue::Unsigned(compound_bps as u64)),
+        ];
+
+        config.referral_buy_bps = buy_bps;
+        config.referral_compound_bps = compound_bps;
+
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Referral shares: {} bps of buys, {} bps of compounds", buy_bps, compound_bps);
+        Ok(())
+    }
+
+    /// Start enforcing that COW supply never exceeds the tokenized cows
+    /// backing it. COW minted before tokenized cows were counted can't be
+    /// told apart, so the count is first raised to cover the live supply;
+    /// that is only sound once the program holds the mint authority, since
+    /// every token in circulation then came from an export.
+    pub fn enforce_cow_backing(ctx: Context<EnforceCowBacking>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let cow_mint = &ctx.accounts.cow_mint;
+
+        require!(!config.cow_backing_enforced, ErrorCode::CowBackingAlreadyEnforced);
+        require!(
+            cow_mint.mint_authority == Some(ctx.accounts.cow_mint_authority.key()).into(),
+            ErrorCode::MintAuthorityNotDelegated
+        );
+
+        let supply_cows = cow_mint.supply.div_ceil(1_000_000);
+        if supply_cows > config.tokenized_cows_count {
+            msg!("Tokenized cows raised from {} to {} to cover untracked COW supply", 
+                 config.tokenized_cows_count, supply_cows);
+            config.tokenized_cows_count = supply_cows;
+        }
+        config.cow_backing_enforced = true;
+
+        msg!("COW backing enforced: {} tokenized cows back {} COW supply", 
+             config.tokenized_cows_count, cow_mint.supply);
+        Ok(())
+    }
+
+    /// Bring the execute_migration kill date forward. It can only ever move
+    /// earlier, and configs created before it existed can set it at most
+    /// MIGRATION_WINDOW from now.
+    pub fn set_migration_deadline(ctx: Context<AdminConfig>, deadline: i64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let latest = if config.migration_deadline == 0 {
+            Clock::get()?.unix_timestamp + MIGRATION_WINDOW
+        } else {
+            config.migration_deadline
+        };
+        require!(deadline <= latest, ErrorCode::InvalidParameter);
+
+        let old_value = config.migration_deadline;
+        config.migration_deadline = deadline;
+
+        record_param_change(
+            config,
+            ConfigParam::MigrationDeadline,
+            ParamValue::Signed(old_value),
+            ParamValue::Signed(deadline),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Migration path closes at {}", deadline);
+        Ok(())
+    }
+
+    /// Set the timelock applied to migrations queued from now on
+    pub fn set_migration_delay(ctx: Context<AdminConfig>, seconds: i64) -> Result<()> {
+        require!(
+            (MIN_MIGRATION_DELAY..=MAX_MIGRATION_DELAY).contains(&seconds),
+            ErrorCode::InvalidTimelock
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_value = config.migration_delay;
+        config.migration_delay = seconds;
+
+        record_param_change(
+            config,
+            ConfigParam::MigrationDelay,
+            ParamValue::Signed(old_value),
+            ParamValue::Signed(seconds),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Migration delay set to {}s", seconds);
+        Ok(())
+    }
+
+    /// Replace the guardian set and the number of vetoes needed to cancel a
+    /// queued migration. An empty set with a zero threshold turns the veto
+    /// off. Locked while a migration is queued so the admin can't unseat
+    /// guardians during the timelock.
+    pub fn set_guardians(ctx: Context<AdminConfig>, guardians: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(guardians.len() <= MAX_GUARDIANS, ErrorCode::InvalidParameter);
+        require!(
+            guardians.iter().enumerate().all(|(i, g)| *g != Pubkey::default() && !guardians[..i].contains(g)),
+            ErrorCode::InvalidParameter
+        );
+        require!(
+            if guardians.is_empty() { threshold == 0 } else { (1..=guardians.len()).contains(&(threshold as usize)) },
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        require!(config.migration_executable_at == 0, ErrorCode::MigrationPending);
+
+        let mut seats = [Pubkey::default(); MAX_GUARDIANS];
+        seats[..guardians.len()].copy_from_slice(&guardians);
+
+        let mut changes = vec![(
+            ConfigParam::GuardianThreshold,
+            ParamValue::Unsigned(config.guardian_threshold as u64),
+            ParamValue::Unsigned(threshold as u64),
+        )];
+        changes.extend(
+            config.guardians
+                .iter()
+                .zip(seats.iter())
+                .filter(|(old, new)| old != new)
+                .map(|(old, new)| (ConfigParam::Guardian, ParamValue::Key(*old), ParamValue::Key(*new))),
+        );
+
+        config.guardians = seats;
+        config.guardian_threshold = threshold;
+        config.migration_vetoes = 0;
+
+        let admin = ctx.accounts.admin.key();
+        record_param_changes(config, &changes, admin)?;
+
+        msg!("Guardians set: {} of {} can veto a migration", threshold, guardians.len());
+        Ok(())
+    }
+
+    /// Waive the early-withdrawal penalty for everyone between `starts_at`
+    /// and `ends_at`, e.g. after an outage. Declaring again replaces the
+    /// window; an `ends_at` in the past ends it.
+    pub fn declare_penalty_grace(
+        ctx: Context<DeclarePenaltyGrace>,
+        starts_at: i64,
+        ends_at: i64,
+        reason: [u8; 32],
+    ) -> Result<()> {
+        require!(ends_at > starts_at, ErrorCode::InvalidParameter);
+        require!(ends_at - starts_at <= MAX_PENALTY_GRACE_DURATION, ErrorCode::InvalidParameter);
+
+        let grace = &mut ctx.accounts.penalty_grace;
+        let old_value = grace.ends_at;
+        grace.starts_at = starts_at;
+        grace.ends_at = ends_at;
+        grace.declared_at = Clock::get()?.unix_timestamp;
+        grace.reason = reason;
+
+        record_param_change(
+            &mut ctx.accounts.config,
+            ConfigParam::PenaltyGrace,
+            ParamValue::Signed(old_value),
+            ParamValue::Signed(ends_at),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Withdrawal penalties waived from {} to {}", starts_at, ends_at);
+        Ok(())
+    }
+
+    /// Start a partner-sponsored campaign emitting `bonus_pool`'s token at
+    /// `rate_per_day` per cow alongside MILK between `starts_at` and
+    /// `ends_at`. Farms opted in to boost events claim it in the same
+    /// withdrawal as their MILK. The previous campaign must have ended; its
+    /// unclaimed bonus is dropped.
+    pub fn start_bonus_campaign(
+        ctx: Context<StartBonusCampaign>,
+        rate_per_day: u64,
+        starts_at: i64,
+        ends_at: i64,
+    ) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(rate_per_day > 0, ErrorCode::InvalidParameter);
+        require!(
+            starts_at >= current_time && ends_at > starts_at && ends_at - starts_at <= MAX_BONUS_CAMPAIGN_DURATION,
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        require!(current_time >= config.bonus_ends_at, ErrorCode::BonusCampaignActive);
+
+        let bonus_pool = &ctx.accounts.bonus_pool;
+        config.bonus_campaign_id += 1;
+        config.bonus_mint = bonus_pool.mint;
+        config.bonus_pool = bonus_pool.key();
+        config.bonus_rate_per_day = rate_per_day;
+        config.bonus_starts_at = starts_at;
+        config.bonus_ends_at = ends_at;
+
+        let campaign_id = config.bonus_campaign_id;
+        record_param_change(
+            config,
+            ConfigParam::BonusCampaign,
+            ParamValue::Unsigned(campaign_id - 1),
+            ParamValue::Unsigned(campaign_id),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Bonus campaign {}: {} of {} per cow per day from {} to {}, funded with {}", 
+             config.bonus_campaign_id, rate_per_day, config.bonus_mint, starts_at, ends_at, bonus_pool.amount);
+        Ok(())
+    }
+
+    /// Start a community goal: once buys and compounds between `starts_at`
+    /// and `ends_at` add up to `target` MILK, every farm that spent at least
+    /// `min_contribution` of it can activate a `boost_bps` reward boost
+    /// lasting `boost_duration` from the moment the goal was reached. The
+    /// previous goal and any boost it unlocked must have ended.
+    pub fn start_community_goal(
+        ctx: Context<StartCommunityGoal>,
+        target: u64,
+        starts_at: i64,
+        ends_at: i64,
+        min_contribution: u64,
+        boost_bps: u16,
+        boost_duration: i64,
+    ) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(target > 0, ErrorCode::InvalidParameter);
+        require!(
+            starts_at >= current_time && ends_at > starts_at && ends_at - starts_at <= MAX_COMMUNITY_GOAL_DURATION,
+            ErrorCode::InvalidParameter
+        );
+        require!(boost_bps > 0 && boost_bps <= MAX_COMMUNITY_BOOST_BPS, ErrorCode::InvalidParameter);
+        require!(
+            boost_duration > 0 && boost_duration <= MAX_COMMUNITY_BOOST_DURATION,
+            ErrorCode::InvalidParameter
+        );
+
+        let goal = &mut ctx.accounts.community_goal;
+        let boost_ends_at = if goal.reached_at > 0 { goal.reached_at + goal.boost_duration } else { 0 };
+        require!(
+            current_time >= goal.ends_at && current_time >= boost_ends_at,
+            ErrorCode::CommunityGoalActive
+        );
+
+        goal.id += 1;
+        goal.target = target;
+        goal.progress = 0;
+        goal.starts_at = starts_at;
+        goal.ends_at = ends_at;
+        goal.min_contribution = min_contribution;
+        goal.boost_bps = boost_bps;
+        goal.boost_duration = boost_duration;
+        goal.reached_at = 0;
+
+        record_param_change(
+            &mut ctx.accounts.config,
+            ConfigParam::CommunityGoal,
+            ParamValue::Unsigned(goal.id - 1),
+            ParamValue::Unsigned(goal.id),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Community goal {}: {} MILK of buys and compounds from {} to {}, +{} bps for {} seconds to farms spending {} MILK", 
+             goal.id, target / 1_000_000, starts_at, ends_at, boost_bps, boost_duration, min_contribution / 1_000_000);
+        Ok(())
+    }
+
+    /// Unlock a reached community goal's boost for a farm that contributed
+    /// enough. Permissionless; rewards accrued before activation are settled
+    /// at the unboosted rate.
+    pub fn activate_community_boost(ctx: Context<ActivateCommunityBoost>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let farm = &mut ctx.accounts.farm;
+        let goal = &ctx.accounts.community_goal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(goal.reached_at > 0, ErrorCode::CommunityGoalNotReached);
+        require!(
+            farm.community_goal_id == goal.id && farm.community_goal_contribution >= goal.min_contribution,
+            ErrorCode::InsufficientGoalContribution
+        );
+        let boost_until = goal.reached_at + goal.boost_duration;
+        require!(current_time < boost_until, ErrorCode::CommunityBoostExpired);
+
+        update_farm_rewards(farm, config, current_time)?;
+        cache_pool_balance(config, &mut ctx.accounts.pool_token_account)?;
+
+        farm.community_boost_bps = goal.boost_bps;
+        farm.community_boost_until = boost_until;
+
+        msg!("Community goal {} boost of +{} bps active for farm {} until {}", 
+             goal.id, goal.boost_bps, farm.owner, boost_until);
+        Ok(())
+    }
+
+    pub fn set_import_quarantine(ctx: Context<AdminConfig>, seconds: i64) -> Result<()> {
+        require!(
+            (0..=MAX_IMPORT_QUARANTINE).contains(&seconds),
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_value = config.import_quarantine_seconds;
+        config.import_quarantine_seconds = seconds;
+
+        record_param_change(
+            config,
+            ConfigParam::ImportQuarantine,
+            ParamValue::Signed(old_value),
+            ParamValue::Signed(seconds),
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Import quarantine set to {} seconds", seconds);
+        Ok(())
+    }
+
+    // Read-only views. None of these take a signer or a writable account, so
+    // clients can simulate them for free (`.view()` in the TypeScript client)
+    // and the return type is published in the IDL.
+
+    pub fn get_global_stats(ctx: Context<GetGlobalStats>) -> Result<GlobalStats> {
+        let config = &ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        let (pool_balance, pool_balance_as_of) = match &ctx.accounts.pool_token_account {
+            Some(pool) => (pool.amount, current_time),
+            None => (config.last_known_pool_balance, config.pool_balance_cached_at),
+        };
+        
+        Ok(GlobalStats {
+            global_cows_count: config.global_cows_count,
+            pool_balance_milk: pool_balance,
+            pool_balance_as_of,
+            farming_cows_count: config.farming_cows_count,
+            tokenized_cows_count: config.tokenized_cows_count,
+            max_cows_per_transaction: max_cows_per_transaction(config, config.tvl, current_time)?,
+            tvl: config.tvl,
+            milk_mint_authority: config.milk_mint_authority,
+            milk_freeze_authority: config.milk_freeze_authority,
+            milk_mint_verified: config.milk_mint_verified,
+        })
+    }
+
+    /// A farm's live rewards and penalty status. Runs the settlement and
+    /// withdrawal logic on in-memory copies of the farm and config, so the
+    /// numbers match what the program would do now.
+    pub fn get_farm_stats(ctx: Context<GetFarmStats>) -> Result<FarmStats> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let mut config = (*ctx.accounts.config).clone();
+        let mut farm = ctx.accounts.farm.clone();
+
+        update_farm_rewards(&mut farm, &mut config, current_time)?;
+        let pending_rewards = farm.accumulated_rewards;
+
+        let mut ahead = farm.clone();
+        update_farm_rewards(&mut ahead, &mut config.clone(), current_time.saturating_add(SECONDS_PER_DAY))?;
+        let daily_rewards = ahead.accumulated_rewards.saturating_sub(pending_rewards);
+
+        let (withdrawable_now, penalty_now) = if pending_rewards == 0 || config.paused {
+            (0, 0)
+        } else {
+            settle_withdrawal(
+                &mut farm.clone(),
+                &mut config.clone(),
+                ctx.accounts.penalty_grace.as_deref(),
+                current_time,
+                None,
+                config.last_known_pool_balance,
+            )?
+        };
+
+        let last_withdraw_time = ctx.accounts.farm.last_withdraw_time;
+        Ok(FarmStats {
+            cows: farm.cows,
+            pending_cows: farm.pending_cows,
+            pending_rewards,
+            reward_rate: calculate_reward_rate(&config, config.global_cows_count, config.tvl, current_time)?,
+            daily_rewards,
+            seconds_since_last_withdraw: (last_withdraw_time != 0).then(|| current_time - last_withdraw_time),
+            penalty_free_at: if last_withdraw_time == 0 { 0 } else { last_withdraw_time + SECONDS_PER_DAY },
+            withdrawable_now,
+            penalty_now,
+        })
+    }
+
+    /// Quote a purchase of `num_cows` with the same pricing `buy_cows` uses,
+    /// so clients don't reimplement the curve. Pass the price feed the
+    /// pricing mode needs.
+    pub fn get_buy_quote(ctx: Context<GetBuyQuote>, num_cows: u64) -> Result<BuyQuote> {
+        require!(num_cows > 0, ErrorCode::InvalidAmount);
+
+        let config = &ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let total_cost = calculate_purchase_cost(
+            config,
+            ctx.accounts.milk_price.as_deref(),
+            ctx.accounts.pyth_price_update.as_ref().map(|a| a.to_account_info()).as_ref(),
+            num_cows,
+            current_time,
+        )?;
+        let total_cost = phase_buy_price(config, total_cost)?;
+        let price_per_cow = total_cost / num_cows;
+
+        let pool_inflow = net_of_transfer_fee(&ctx.accounts.milk_mint, total_cost)?;
+        let reward_rate_after = calculate_reward_rate(
+            config,
+            config.global_cows_count.saturating_add(num_cows),
+            config.tvl.saturating_add(pool_inflow),
+            current_time,
+        )?;
+
+        Ok(BuyQuote {
+            num_cows,
+            price_per_cow,
+            total_cost,
+            reward_rate: calculate_reward_rate(config, config.global_cows_count, config.tvl, current_time)?,
+            reward_rate_after,
+            max_cows_per_transaction: max_cows_per_transaction(config, config.tvl, current_time)?,
+        })
+    }
+
+    /// Stored state of up to MAX_FARMS_PER_BATCH farms passed as remaining
+    /// accounts, so dashboards can refresh many farms in one simulation.
+    /// Rewards are as of each farm's last update.
+    pub fn get_farms_stats_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetFarmsStatsBatch<'info>>,
+    ) -> Result<Vec<FarmSummary>> {
+        require!(ctx.remaining_accounts.len() <= MAX_FARMS_PER_BATCH, ErrorCode::TooManyFarms);
+
+        ctx.remaining_accounts
+            .iter()
+            .map(|info| {
+                let farm = Account::<FarmAccount>::try_from(info)?;
+                Ok(FarmSummary {
+                    cows: farm.cows,
+                    pending_cows: farm.pending_cows,
+                    accumulated_rewards: farm.accumulated_rewards,
+                    last_update_time: farm.last_update_time,
+                    cost_basis: farm.cost_basis,
+                })
+            })
+            .collect()
+    }
+
+    /// Dry-run a candidate parameter set against the live global state
+    pub fn preview_params(ctx: Context<GetGlobalStats>, params: EconomicParams) -> Result<ParamsPreview> {
+        params.validate()?;
+
+        let config = &ctx.accounts.config;
+
+        let preview = ParamsPreview {
+            cow_price: calculate_cow_price_with(&params, config.global_cows_count)?,
+            reward_rate: calculate_reward_rate_with(&params, config.global_cows_count, config.tvl)?,
+            current_cow_price: calculate_cow_price_with(&economic_params(config), config.global_cows_count)?,
+            current_reward_rate: calculate_reward_rate_with(&economic_params(config), config.global_cows_count, config.tvl)?,
+        };
+
+        msg!("Params preview - price: {} (now {}), rate: {} MILK/cow/day (now {})", 
+             preview.cow_price, preview.current_cow_price, 
+             preview.reward_rate / 1_000_000, preview.current_reward_rate / 1_000_000);
+        Ok(preview)
+    }
+
+    /// Recorded and live upgrade authority, with the declared timelock and guard state
+    pub fn get_upgrade_authority(ctx: Context<GetUpgradeAuthority>) -> Result<UpgradeAuthorityStatus> {
+        let config = &ctx.accounts.config;
+        let current_authority = ctx.accounts.program_data.upgrade_authority_address.unwrap_or_default();
+
+        Ok(UpgradeAuthorityStatus {
+            recorded_authority: config.upgrade_authority,
+            current_authority,
+            matches: current_authority == config.upgrade_authority,
+            recorded_at: config.upgrade_authority_recorded_at,
+            timelock_seconds: config.upgrade_timelock_seconds,
+            guard_enabled: config.upgrade_guard_enabled,
+            paused: config.paused,
+        })
+    }
+
+    /// COW supply against the exported cows backing it
+    pub fn get_backing(ctx: Context<GetBacking>) -> Result<CowBacking> {
+        let config = &ctx.accounts.config;
+        let cow_supply = ctx.accounts.cow_mint.supply;
+        let backed_supply = config.tokenized_cows_count.saturating_mul(1_000_000);
+
+        Ok(CowBacking {
+            cow_supply,
+            backed_supply,
+            tokenized_cows: config.tokenized_cows_count,
+            total_cows_exported: config.total_cows_exported,
+            total_cows_imported: config.total_cows_imported,
+            fully_backed: cow_supply <= backed_supply,
+            enforced: config.cow_backing_enforced,
+        })
+    }
+
+    /// Best, typical and worst-case earnings for a farm over the next
+    /// `horizon_seconds`: at its current rate, at the rate after
+    /// `extra_global_cows` more cows join the game, and at the minimum rate
+    pub fn project_rewards(ctx: Context<ProjectRewards>, horizon_seconds: i64, extra_global_cows: u64) -> Result<RewardProjection> {
+        require!(
+            horizon_seconds > 0 && horizon_seconds <= MAX_PROJECTION_HORIZON,
+            ErrorCode::InvalidParameter
+        );
+
+        let config = &ctx.accounts.config;
+        let farm = &ctx.accounts.farm;
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let current_rate = calculate_reward_rate(config, config.global_cows_count, config.tvl, current_time)?;
+        let diluted_rate = calculate_reward_rate(
+            config,
+            config.global_cows_count.saturating_add(extra_global_cows),
+            config.tvl,
+            current_time,
+        )?;
+
+        Ok(RewardProjection {
+            horizon_seconds,
+            cows: farm.cows,
+            current_rate,
+            current_projection: project_farm_rewards(farm, current_rate, horizon_seconds)?,
+            diluted_rate,
+            diluted_projection: project_farm_rewards(farm, diluted_rate, horizon_seconds)?,
+            min_rate: MIN_REWARD_PER_DAY,
+            min_projection: project_farm_rewards(farm, MIN_REWARD_PER_DAY, horizon_seconds)?,
+        })
+    }
+
+    /// Health of a loan position including interest accrued since the last
+    /// update, for keepers scanning for liquidations
+    pub fn get_position_health(ctx: Context<GetPositionHealth>) -> Result<PositionHealth> {
+        let config = &ctx.accounts.config;
+        let position = &ctx.accounts.position;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let debt = position.debt
+            .checked_add(pending_loan_interest(position, config, current_time)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let liquidation_debt = max_debt_at(config, position.collateral_cows, config.liquidation_threshold_bps)?;
+        let health_factor_bps = if debt == 0 {
+            u64::MAX
+        } else {
+            ((liquidation_debt as u128) * (BPS_DENOMINATOR as u128) / (debt as u128)).min(u64::MAX as u128) as u64
+        };
+
+        Ok(PositionHealth {
+            collateral_cows: position.collateral_cows,
+            collateral_value: collateral_value(config, position.collateral_cows)?,
+            debt,
+            max_debt: max_debt_at(config, position.collateral_cows, config.loan_ltv_bps)?,
+            liquidation_debt,
+            health_factor_bps,
+            liquidatable: debt > 0 && debt > liquidation_debt,
+        })
+    }
+}
@@ -0,0 +1,291 @@
+//! Bonding curve, reward curve and basis-point arithmetic.
+
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::ErrorCode;
+use crate::fixed;
+use crate::rounding::{self, USER_CREDIT, USER_DEBIT};
+use crate::pyth;
+use crate::state::{Config, MilkPrice, PricingMode};
+use crate::types::EconomicParams;
+
+/// Curve parameters in force. A Config that has never had them set uses
+/// the compile-time defaults.
+pub(crate) fn economic_params(config: &Config) -> EconomicParams {
+    if config.economic_params.price_pivot == 0 {
+        DEFAULT_ECONOMIC_PARAMS
+    } else {
+        config.economic_params
+    }
+}
+
+/// Whether the latest seasonal event is running at `current_time`
+pub(crate) fn event_active(config: &Config, current_time: i64) -> bool {
+    config.event_starts_at <= current_time && current_time < config.event_ends_at
+}
+
+/// Basis-point seconds of [from, to) at the event reward multiplier: a
+/// second outside the event counts BPS_DENOMINATOR, one inside it counts
+/// `event_reward_bps`
+pub(crate) fn event_weighted_bps_seconds(config: &Config, from: i64, to: i64) -> u128 {
+    let elapsed = (to - from).max(0) as u128;
+    let overlap = (to.min(config.event_ends_at) - from.max(config.event_starts_at)).max(0) as u128;
+    let boost = (config.event_reward_bps as u128).saturating_sub(BPS_DENOMINATOR as u128);
+    elapsed * BPS_DENOMINATOR as u128 + overlap * boost
+}
+
+/// A buy price with the running event's discount taken off
+fn event_price(config: &Config, price: u64, current_time: i64) -> Result<u64> {
+    if !event_active(config, current_time) || config.event_price_discount_bps == 0 {
+        return Ok(price);
+    }
+    rounding::mul_div(
+        price,
+        BPS_DENOMINATOR - config.event_price_discount_bps as u64,
+        BPS_DENOMINATOR,
+        USER_DEBIT,
+    )
+}
+
+/// Calculate dynamic cow price based on global cow count, less the
+/// discount of any event running at `current_time`
+/// P(c) = P₀ * (1 + (c / C_pivot)^α)
+pub(crate) fn calculate_cow_price(config: &Config, global_cows: u64, current_time: i64) -> Result<u64> {
+    event_price(config, calculate_cow_price_with(&economic_params(config), global_cows)?, current_time)
+}
+
+/// Cow price for an arbitrary parameter set
+pub(crate) fn calculate_cow_price_with(params: &EconomicParams, global_cows: u64) -> Result<u64> {
+    if global_cows == 0 {
+        return Ok(params.cow_base_price);
+    }
+
+    let ratio = fixed::div(global_cows as u128, params.price_pivot as u128)?;
+    let power_term = fixed::pow(ratio, fixed::from_bps(params.price_steepness_bps))?;
+    let multiplier = fixed::ONE
+        .checked_add(power_term)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let price = fixed::to_int(fixed::mul(fixed::from_int(params.cow_base_price), multiplier)?);
+
+    msg!("Cow price calculation: global_cows={}, multiplier_bps={}, price={}", 
+         global_cows, fixed::to_int(fixed::mul(multiplier, fixed::from_int(BPS_DENOMINATOR))?), price);
+
+    Ok(price)
+}
+
+/// Total curve cost of `num_cows` bought on top of `global_cows`, each cow
+/// priced at its own position on the curve rather than all at the first
+/// cow's price. Integrates P over [c, c + n]:
+/// ∫P = P₀ * (n + C_pivot / (α + 1) * (((c + n) / C_pivot)^(α+1) - (c / C_pivot)^(α+1)))
+/// Rounds up.
+pub(crate) fn calculate_cow_cost_with(params: &EconomicParams, global_cows: u64, num_cows: u64) -> Result<u64> {
+    let end = global_cows.checked_add(num_cows).ok_or(ErrorCode::MathOverflow)?;
+    let exponent = fixed::from_bps(params.price_steepness_bps + BPS_DENOMINATOR);
+    let antiderivative = |cows: u64| -> Result<u128> {
+        fixed::pow(fixed::div(cows as u128, params.price_pivot as u128)?, exponent)
+    };
+
+    let area = antiderivative(end)?.saturating_sub(antiderivative(global_cows)?);
+    let curve_cows = fixed::div(fixed::mul(area, fixed::from_int(params.price_pivot))?, exponent)?;
+    let priced_cows = fixed::from_int(num_cows)
+        .checked_add(curve_cows)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let cost = fixed::mul(fixed::from_int(params.cow_base_price), priced_cows)?;
+    Ok(fixed::to_int(cost) + u64::from(cost % fixed::ONE != 0))
+}
+
+/// Total cost of `num_cows` at their positions on the curve, less the
+/// discount of any event running at `current_time`
+pub(crate) fn calculate_cow_cost(config: &Config, global_cows: u64, num_cows: u64, current_time: i64) -> Result<u64> {
+    event_price(config, calculate_cow_cost_with(&economic_params(config), global_cows, num_cows)?, current_time)
+}
+
+/// Most whole cows `budget` buys on top of `global_cows` under integral
+/// pricing
+pub(crate) fn max_affordable_cows(config: &Config, global_cows: u64, budget: u64, current_time: i64) -> Result<u64> {
+    // Every cow costs at least the current price, so this bounds the search
+    let mut high = budget / calculate_cow_price(config, global_cows, current_time)?.max(1);
+    let mut low = 0;
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if calculate_cow_cost(config, global_cows, mid, current_time)? <= budget {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(low)
+}
+
+/// MILK paid for selling `num_cows` back to the pool. Cows are priced at
+/// the curve price for the herd left after the sale, less the sell discount,
+/// so a buy followed by a sell never returns more than it cost.
+pub(crate) fn calculate_sell_payout(params: &EconomicParams, global_cows: u64, num_cows: u64) -> Result<u64> {
+    curve_payout(params, global_cows, num_cows, SELL_PRICE_BPS)
+}
+
+/// MILK paid for cows burned through `emergency_withdraw`: the same curve
+/// value as a sale, scaled by the emergency exit share instead of the sell
+/// price
+pub(crate) fn calculate_emergency_payout(params: &EconomicParams, global_cows: u64, num_cows: u64, exit_bps: u16) -> Result<u64> {
+    curve_payout(params, global_cows, num_cows, exit_bps as u64)
+}
+
+fn curve_payout(params: &EconomicParams, global_cows: u64, num_cows: u64, share_bps: u64) -> Result<u64> {
+    let remaining = global_cows.checked_sub(num_cows).ok_or(ErrorCode::InsufficientCows)?;
+    let payout = (calculate_cow_price_with(params, remaining)? as u128)
+        .checked_mul(num_cows as u128)
+        .and_then(|v| v.checked_mul(share_bps as u128))
+        .ok_or(ErrorCode::MathOverflow)?;
+    rounding::div(payout, BPS_DENOMINATOR as u128, USER_CREDIT)
+}
+
+/// Vault shares minted for `num_cows` deposited into a vault holding
+/// `vault_cows` against `total_shares`. An empty vault issues one share per
+/// cow, in 6-decimal units like COW itself.
+pub(crate) fn vault_shares_for(num_cows: u64, vault_cows: u64, total_shares: u64) -> Result<u64> {
+    if total_shares == 0 || vault_cows == 0 {
+        return num_cows.checked_mul(1_000_000).ok_or(ErrorCode::MathOverflow.into());
+    }
+    rounding::mul_div(num_cows, total_shares, vault_cows, USER_CREDIT)
+}
+
+/// Whole cows `shares` redeem for. Rounds down, leaving the remainder with
+/// the remaining shareholders.
+pub(crate) fn vault_cows_for(shares: u64, vault_cows: u64, total_shares: u64) -> Result<u64> {
+    require!(shares <= total_shares, ErrorCode::InvalidAmount);
+    rounding::mul_div(shares, vault_cows, total_shares, USER_CREDIT)
+}
+
+/// Total cost of a fresh MILK purchase of `num_cows` under the configured
+/// pricing mode, each cow at its own position on the curve. In the USD
+/// modes the same curve is evaluated on a USD base price and converted to
+/// MILK at the published or Pyth oracle price.
+pub(crate) fn calculate_purchase_cost(
+    config: &Config,
+    milk_price: Option<&MilkPrice>,
+    pyth_price_update: Option<&AccountInfo>,
+    num_cows: u64,
+    current_time: i64,
+) -> Result<u64> {
+    let price_micro_usd = match config.pricing_mode {
+        PricingMode::Milk => return calculate_cow_cost(config, config.global_cows_count, num_cows, current_time),
+        PricingMode::Usd => {
+            let milk_price = milk_price.ok_or(ErrorCode::MissingPriceFeed)?;
+            require!(
+                current_time - milk_price.updated_at <= config.max_price_age,
+                ErrorCode::StalePriceFeed
+            );
+            milk_price.price_micro_usd
+        }
+        PricingMode::Pyth => {
+            let price_update = pyth_price_update.ok_or(ErrorCode::MissingPriceFeed)?;
+            pyth::milk_price_micro_usd(price_update, config, current_time)?
+        }
+    };
+
+    let params = EconomicParams {
+        cow_base_price: config.cow_base_price_usd,
+        ..economic_params(config)
+    };
+    let cost_usd = calculate_cow_cost_with(&params, config.global_cows_count, num_cows)?;
+    let cost_milk = event_price(
+        config,
+        rounding::mul_div(cost_usd, 1_000_000, price_micro_usd, USER_DEBIT)?,
+        current_time,
+    )?;
+
+    msg!("USD pricing: {} micro-USD at {} micro-USD/MILK = {} MILK units", 
+         cost_usd, price_micro_usd, cost_milk);
+    Ok(cost_milk)
+}
+
+/// Calculate dynamic reward rate per cow per day, times the multiplier of
+/// any event running at `current_time`
+/// R_cow = max(B / (1 + α_reward * (TVL/C) / S), R_min) * G(C)
+pub(crate) fn calculate_reward_rate(config: &Config, global_cows: u64, tvl: u64, current_time: i64) -> Result<u64> {
+    let rate = calculate_reward_rate_with(&economic_params(config), global_cows, tvl)?;
+    if !event_active(config, current_time) {
+        return Ok(rate);
+    }
+    rounding::mul_div(rate, config.event_reward_bps as u64, BPS_DENOMINATOR, USER_CREDIT)
+}
+
+/// Reward rate for an arbitrary parameter set
+pub(crate) fn calculate_reward_rate_with(params: &EconomicParams, global_cows: u64, tvl: u64) -> Result<u64> {
+    if global_cows == 0 {
+        return Ok(params.min_reward_per_day);
+    }
+
+    // (TVL/C) / S
+    let normalized_ratio = fixed::div(
+        tvl as u128,
+        (global_cows as u128) * (params.tvl_normalization as u128),
+    )?;
+
+    let denominator = fixed::ONE
+        .checked_add(fixed::mul(fixed::from_bps(params.reward_sensitivity_bps), normalized_ratio)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let base_reward = fixed::div(fixed::from_int(params.reward_base), denominator)?;
+
+    let greed_decay = fixed::exp_neg(fixed::div(global_cows as u128, params.greed_decay_pivot as u128)?)?;
+    let greed_multiplier = fixed::ONE
+        .checked_add(fixed::mul(fixed::from_bps(params.greed_multiplier_bps), greed_decay)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let reward_with_greed = fixed::to_int(fixed::mul(base_reward, greed_multiplier)?);
+    let reward_rate = reward_with_greed.max(params.min_reward_per_day);
+
+    msg!("Reward calculation: cows={}, tvl={}, base={}, greed_bps={}, final={}", 
+         global_cows, tvl, fixed::to_int(base_reward) / 1_000_000,
+         fixed::to_int(fixed::mul(greed_multiplier, fixed::from_int(BPS_DENOMINATOR))?), reward_rate / 1_000_000);
+
+    Ok(reward_rate)
+}
+
+/// Progressive tax on a single withdrawal. Each bracket taxes the part of
+/// the amount between its threshold and the next bracket's at its own rate.
+/// The brackets are summed before rounding, so the total rounds up once.
+pub(crate) fn calculate_withdrawal_tax(config: &Config, amount: u64) -> Result<u64> {
+    let mut tax = 0u128;
+    for tier in 0..WITHDRAWAL_TAX_TIERS {
+        let bps = config.withdrawal_tax_bps[tier];
+        if bps == 0 {
+            continue;
+        }
+        let upper = config.withdrawal_tax_thresholds.get(tier + 1).copied().unwrap_or(u64::MAX);
+        let taxed = amount.min(upper).saturating_sub(config.withdrawal_tax_thresholds[tier]);
+        tax += taxed as u128 * bps as u128;
+    }
+    rounding::div(tax, BPS_DENOMINATOR as u128, USER_DEBIT)
+}
+
+/// Scale an amount up by a bonus expressed in basis points
+pub(crate) fn apply_bonus_bps(amount: u64, bonus_bps: u16) -> Result<u64> {
+    let scaled = (amount as u128)
+        .checked_mul(BPS_DENOMINATOR as u128 + bonus_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    rounding::div(scaled, BPS_DENOMINATOR as u128, USER_CREDIT)
+}
+
+/// Scale an amount by a signed basis-point modifier
+pub(crate) fn apply_signed_bps(amount: u64, bps: i64) -> Result<u64> {
+    let factor = (BPS_DENOMINATOR as i128 + bps as i128).max(0) as u128;
+    let scaled = (amount as u128)
+        .checked_mul(factor)
+        .ok_or(ErrorCode::MathOverflow)?;
+    rounding::div(scaled, BPS_DENOMINATOR as u128, USER_CREDIT)
+}
+
+pub(crate) const DEFAULT_ECONOMIC_PARAMS: EconomicParams = EconomicParams {
+    cow_base_price: COW_BASE_PRICE,
+    price_pivot: PRICE_PIVOT,
+    price_steepness_bps: PRICE_STEEPNESS_BPS,
+    reward_base: REWARD_BASE,
+    reward_sensitivity_bps: REWARD_SENSITIVITY_BPS,
+    tvl_normalization: TVL_NORMALIZATION,
+    min_reward_per_day: MIN_REWARD_PER_DAY,
+    greed_multiplier_bps: GREED_MULTIPLIER_BPS,
+    greed_decay_pivot: GREED_DECAY_PIVOT,
+};
@@ -0,0 +1,62 @@
+//! Game phases (Presale, Launch, Steady, Sunset), each with its own rule
+//! table. Phases only move forward, through a timelocked transition that
+//! guardians can veto, and a phase's rules are frozen once it is current, so
+//! the rules in force can only change through an announced transition.
+//!
+//! All-zero rules leave the game unrestricted. Withdrawals and emergency
+//! exits are never blocked by a phase.
+
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::errors::ErrorCode;
+use crate::rounding::{self, USER_CREDIT, USER_DEBIT};
+use crate::state::{Config, GamePhase, PhaseRules};
+
+/// Rules of the phase the game is in
+pub(crate) fn current_rules(config: &Config) -> &PhaseRules {
+    &config.phase_rules[config.phase as usize]
+}
+
+/// The phase a transition from `phase` moves to, if any
+pub(crate) fn next_phase(phase: GamePhase) -> Option<GamePhase> {
+    match phase {
+        GamePhase::Presale => Some(GamePhase::Launch),
+        GamePhase::Launch => Some(GamePhase::Steady),
+        GamePhase::Steady => Some(GamePhase::Sunset),
+        GamePhase::Sunset => None,
+    }
+}
+
+/// Fail if the current phase blocks `action`, one of the PHASE_BLOCK_* bits
+pub(crate) fn require_phase_allows(config: &Config, action: u8) -> Result<()> {
+    require!(current_rules(config).blocked_actions & action == 0, ErrorCode::BlockedInPhase);
+    Ok(())
+}
+
+/// Per-transaction buy cap after the current phase's scaling
+pub(crate) fn phase_buy_cap(config: &Config, max_cows: u64) -> u64 {
+    match current_rules(config).buy_cap_bps {
+        0 => max_cows,
+        bps => ((max_cows as u128) * (bps as u128) / BPS_DENOMINATOR as u128).max(1) as u64,
+    }
+}
+
+/// Cow price with the current phase's buy surcharge, paid into the pool
+pub(crate) fn phase_buy_price(config: &Config, price: u64) -> Result<u64> {
+    rounding::mul_div(
+        price,
+        BPS_DENOMINATOR + current_rules(config).buy_fee_bps as u64,
+        BPS_DENOMINATOR,
+        USER_DEBIT,
+    )
+}
+
+/// Share of an early-withdrawal penalty the current phase waives
+pub(crate) fn phase_penalty_waiver(config: &Config, penalty: u64) -> Result<u64> {
+    rounding::mul_div(
+        penalty,
+        current_rules(config).penalty_waiver_bps as u64,
+        BPS_DENOMINATOR,
+        USER_CREDIT,
+    )
+}
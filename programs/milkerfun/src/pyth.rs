@@ -0,0 +1,86 @@
+//! MILK/USD prices read from a Pyth pull-oracle price update account.
+//!
+//! The account layout mirrors `PriceUpdateV2` from the Pyth Solana receiver
+//! so the program doesn't need the receiver SDK as a dependency.
+
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::errors::ErrorCode;
+use crate::state::Config;
+
+/// Pyth Solana receiver, owner of every price update account
+const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Anchor discriminator of `PriceUpdateV2`
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+#[derive(AnchorDeserialize)]
+enum VerificationLevel {
+    Partial { _num_signatures: u8 },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    _feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    _ema_price: i64,
+    _ema_conf: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    _posted_slot: u64,
+}
+
+/// USD price of 1 MILK with 6 decimals, from a fully verified Pyth update no
+/// older than `max_price_age` whose confidence interval is within
+/// `max_price_conf_bps` of the price. Rounds down, so conversions charge
+/// at least the USD price.
+pub(crate) fn milk_price_micro_usd(price_update: &AccountInfo, config: &Config, current_time: i64) -> Result<u64> {
+    require_keys_eq!(*price_update.owner, PYTH_RECEIVER_PROGRAM_ID, ErrorCode::InvalidPriceFeed);
+
+    let data = price_update.try_borrow_data()?;
+    require!(
+        data.len() > 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        ErrorCode::InvalidPriceFeed
+    );
+    let update = PriceUpdateV2::deserialize(&mut &data[8..])
+        .map_err(|_| ErrorCode::InvalidPriceFeed)?;
+    require!(
+        matches!(update.verification_level, VerificationLevel::Full),
+        ErrorCode::InvalidPriceFeed
+    );
+
+    let message = update.price_message;
+    require!(
+        current_time - message.publish_time <= config.max_price_age,
+        ErrorCode::StalePriceFeed
+    );
+    require!(message.price > 0, ErrorCode::InvalidPriceFeed);
+    let price = message.price as u128;
+    require!(
+        message.conf as u128 * BPS_DENOMINATOR as u128 <= price * config.max_price_conf_bps as u128,
+        ErrorCode::PriceFeedUncertain
+    );
+
+    let shift = message.exponent.checked_add(6).ok_or(ErrorCode::InvalidPriceFeed)?;
+    let scale = 10u128
+        .checked_pow(shift.unsigned_abs())
+        .ok_or(ErrorCode::InvalidPriceFeed)?;
+    let micro_usd = if shift >= 0 {
+        price.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?
+    } else {
+        price / scale
+    };
+    let micro_usd = u64::try_from(micro_usd).map_err(|_| ErrorCode::MathOverflow)?;
+    require!(micro_usd > 0, ErrorCode::InvalidPriceFeed);
+    Ok(micro_usd)
+}
@@ -0,0 +1,84 @@
+//! Owner-signed actions submitted by a relayer.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{get_instruction_relative, load_current_index_checked};
+use crate::constants::RELAY_MESSAGE_DOMAIN;
+use crate::errors::ErrorCode;
+use crate::state::FarmAccount;
+use crate::types::{RelayedAction, RelayedMessage};
+
+/// Bytes the farm owner signs to authorize a relayed action
+pub(crate) fn relayed_message_bytes(farm: &Pubkey, action: RelayedAction, nonce: u64, expiry: i64) -> Result<Vec<u8>> {
+    let message = RelayedMessage {
+        program_id: crate::ID,
+        farm: *farm,
+        action,
+        nonce,
+        expiry,
+    };
+    let mut bytes = RELAY_MESSAGE_DOMAIN.to_vec();
+    message.serialize(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Check that the instruction right before this one is an Ed25519 program
+/// verification of `expected_message` signed by `owner`. The signature,
+/// key and message must all live in that instruction's own data.
+pub(crate) fn verify_ed25519_instruction(instructions: &AccountInfo, owner: &Pubkey, expected_message: &[u8]) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, ErrorCode::InvalidRelaySignature);
+    let ix = get_instruction_relative(-1, instructions)?;
+    require!(ix.program_id == ed25519_program::ID, ErrorCode::InvalidRelaySignature);
+
+    // Layout: num_signatures (u8), padding (u8), then one 14-byte offsets
+    // struct per signature: signature, public key and message offsets,
+    // each paired with the index of the instruction holding the data
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidRelaySignature);
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_ix = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_ix = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix = read_u16(14);
+
+    require!(
+        signature_ix == u16::MAX && public_key_ix == u16::MAX && message_ix == u16::MAX,
+        ErrorCode::InvalidRelaySignature
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidRelaySignature)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidRelaySignature)?;
+
+    require!(public_key == owner.as_ref(), ErrorCode::InvalidRelaySignature);
+    require!(message == expected_message, ErrorCode::InvalidRelaySignature);
+    Ok(())
+}
+
+/// Validate and burn a relayed authorization for `farm`
+pub(crate) fn consume_relayed_authorization(
+    farm: &mut Account<FarmAccount>,
+    instructions: &AccountInfo,
+    action: RelayedAction,
+    nonce: u64,
+    expiry: i64,
+    current_time: i64,
+) -> Result<()> {
+    require!(current_time <= expiry, ErrorCode::RelayAuthorizationExpired);
+    require!(nonce == farm.relay_nonce, ErrorCode::InvalidRelayNonce);
+
+    let message = relayed_message_bytes(&farm.key(), action, nonce, expiry)?;
+    verify_ed25519_instruction(instructions, &farm.owner, &message)?;
+
+    farm.relay_nonce = farm.relay_nonce
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! Rounding policy for every division that moves value. Amounts credited or
+//! paid out to a user round down and amounts charged to a user round up, so
+//! the remainder of every division stays with the pool and rounding can
+//! never mint MILK. Changing the policy is an edit to the two constants
+//! below.
+//!
+//! The curves themselves round down inside `fixed`; this covers the
+//! divisions applied to the amounts they produce.
+
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Rounding {
+    Down,
+    Up,
+}
+
+/// Rewards, payouts, shares and borrowing capacity
+pub(crate) const USER_CREDIT: Rounding = Rounding::Down;
+
+/// Prices, taxes and interest
+pub(crate) const USER_DEBIT: Rounding = Rounding::Up;
+
+/// numerator / denominator under `rounding`, as a token amount
+pub(crate) fn div(numerator: u128, denominator: u128, rounding: Rounding) -> Result<u64> {
+    u64::try_from(div_wide(numerator, denominator, rounding)?).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// numerator / denominator under `rounding`, for scaled accumulators
+pub(crate) fn div_wide(numerator: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    require!(denominator > 0, ErrorCode::MathOverflow);
+    Ok(match rounding {
+        Rounding::Down => numerator / denominator,
+        Rounding::Up => numerator.div_ceil(denominator),
+    })
+}
+
+/// value * multiplier / divisor under `rounding`, without intermediate
+/// overflow
+pub(crate) fn mul_div(value: u64, multiplier: u64, divisor: u64, rounding: Rounding) -> Result<u64> {
+    div(value as u128 * multiplier as u128, divisor as u128, rounding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credits_round_down_and_debits_round_up() {
+        assert_eq!(mul_div(10, 1, 3, USER_CREDIT).unwrap(), 3);
+        assert_eq!(mul_div(10, 1, 3, USER_DEBIT).unwrap(), 4);
+        assert_eq!(mul_div(9, 1, 3, USER_CREDIT).unwrap(), 3);
+        assert_eq!(mul_div(9, 1, 3, USER_DEBIT).unwrap(), 3);
+        assert_eq!(mul_div(0, 7, 3, USER_DEBIT).unwrap(), 0);
+    }
+
+    #[test]
+    fn split_never_exceeds_the_whole() {
+        for amount in [1u64, 2, 3, 999, 1_000_001, u64::MAX] {
+            for bps in [1u64, 3_333, 5_000, 9_999] {
+                let credit = mul_div(amount, bps, 10_000, USER_CREDIT).unwrap();
+                let debit = mul_div(amount, 10_000 - bps, 10_000, USER_DEBIT).unwrap();
+                assert!(credit <= amount);
+                assert_eq!(credit + debit, amount);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_zero_divisor_and_overflow() {
+        assert!(mul_div(1, 1, 0, USER_CREDIT).is_err());
+        assert!(mul_div(u64::MAX, 2, 1, USER_CREDIT).is_err());
+        assert!(div(u128::MAX, 1, USER_DEBIT).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! COW token staking: staked COW earns a share of a fixed daily MILK
+//! emission from the pool, split pro rata by the cows staked.
+
+use anchor_lang::prelude::*;
+use crate::constants::{ACC_REWARD_PRECISION, SECONDS_PER_DAY};
+use crate::errors::ErrorCode;
+use crate::rounding::{self, USER_CREDIT};
+use crate::state::{CowStake, CowStakingPool};
+
+/// Advance the pool's per-cow accumulator to `current_time` at the current
+/// emission. Nothing is emitted while no cows are staked.
+pub(crate) fn accrue_staking(pool: &mut CowStakingPool, current_time: i64) -> Result<()> {
+    if current_time <= pool.updated_at {
+        return Ok(());
+    }
+
+    if pool.total_staked > 0 && pool.emission_per_day > 0 {
+        let elapsed = (current_time - pool.updated_at) as u128;
+        let emitted = rounding::div(
+            (pool.emission_per_day as u128)
+                .checked_mul(elapsed)
+                .ok_or(ErrorCode::MathOverflow)?,
+            SECONDS_PER_DAY as u128,
+            USER_CREDIT,
+        )?;
+        let per_cow = rounding::div_wide(
+            (emitted as u128)
+                .checked_mul(ACC_REWARD_PRECISION)
+                .ok_or(ErrorCode::MathOverflow)?,
+            pool.total_staked as u128,
+            USER_CREDIT,
+        )?;
+        pool.acc_reward_per_cow = pool.acc_reward_per_cow
+            .checked_add(per_cow)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.total_emitted = pool.total_emitted.saturating_add(emitted);
+    }
+    pool.updated_at = current_time;
+    Ok(())
+}
+
+/// Credit a stake with what its cows earned since it was last settled.
+/// The pool must be accrued first.
+pub(crate) fn settle_stake(stake: &mut CowStake, pool: &CowStakingPool) -> Result<()> {
+    let earned = rounding::div(
+        (stake.staked_cows as u128)
+            .checked_mul(pool.acc_reward_per_cow.saturating_sub(stake.reward_debt))
+            .ok_or(ErrorCode::MathOverflow)?,
+        ACC_REWARD_PRECISION,
+        USER_CREDIT,
+    )?;
+    stake.pending_rewards = stake.pending_rewards
+        .checked_add(earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake.reward_debt = pool.acc_reward_per_cow;
+    Ok(())
+}
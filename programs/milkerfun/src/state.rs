@@ -0,0 +1,702 @@
+//! Accounts owned by the program and the types stored in them.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::{BREEDING_OUTCOMES, COW_RARITIES, EPOCH_HISTORY_LEN, FEE_HOOK_COUNT, GAME_PHASES, HARVEST_LOG_LEN, LOCK_TIERS, MAX_AUTOMATION_PROGRAMS, MAX_GUARDIANS, WITHDRAWAL_TAX_TIERS};
+use crate::types::EconomicParams;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,                    // 32 bytes
+    pub milk_mint: Pubkey,               // 32 bytes  
+    pub cow_mint: Pubkey,                // 32 bytes
+    pub pool_token_account: Pubkey,      // 32 bytes
+    pub start_time: i64,                 // 8 bytes
+    pub global_cows_count: u64,          // 8 bytes
+    pub initial_tvl: u64,                // 8 bytes - for reference
+    pub import_quarantine_seconds: i64,  // 8 bytes - delay before imported cows produce (0 = off)
+    pub farming_cows_count: u64,         // 8 bytes - cows currently producing inside farms
+    pub tokenized_cows_count: u64,       // 8 bytes - cows exported as COW tokens and not yet imported
+    pub streak_days_required: u8,        // 1 byte - consecutive compound days for the streak bonus (K)
+    pub streak_bonus_bps: u16,           // 2 bytes - reward bonus while on a streak (0 = off)
+    pub automation_programs: [Pubkey; MAX_AUTOMATION_PROGRAMS], // 128 bytes - programs allowed to compound/poke opted-in farms
+    pub pricing_mode: PricingMode,       // 1 byte - currency cow purchases are priced in
+    pub cow_base_price_usd: u64,         // 8 bytes - P₀ in micro-USD for USD pricing mode
+    pub price_oracle_authority: Pubkey,  // 32 bytes - signer allowed to publish the MILK/USD price
+    pub max_price_age: i64,              // 8 bytes - oldest acceptable oracle price in seconds
+    pub buy_cooldown_seconds: i64,       // 8 bytes - minimum gap between buys per farm (0 = off)
+    pub param_change_count: u64,         // 8 bytes - sequence number of the last ParamChanged event
+    pub pauser: Pubkey,                  // 32 bytes - may flag escrowed withdrawals for review
+    pub escrow_threshold: u64,           // 8 bytes - withdrawals above this go through escrow (0 = off)
+    pub escrow_window: i64,              // 8 bytes - dispute window for escrowed withdrawals
+    pub efficiency_grace_seconds: i64,   // 8 bytes - idle time before milking efficiency starts decaying
+    pub efficiency_decay_bps_per_day: u16, // 2 bytes - efficiency lost per idle day (0 = off)
+    pub efficiency_floor_bps: u16,       // 2 bytes - efficiency never decays below this
+    pub loan_ltv_bps: u16,               // 2 bytes - max debt as a share of collateral value (0 = lending off)
+    pub liquidation_threshold_bps: u16,  // 2 bytes - debt/collateral ratio at which positions liquidate
+    pub loan_interest_bps_per_year: u16, // 2 bytes - simple interest on loan debt
+    pub collateral_cows_count: u64,      // 8 bytes - cows locked in loan positions
+    pub total_loan_principal: u64,       // 8 bytes - MILK lent out and not yet repaid or written off
+    pub liquidation_bonus_bps: u16,      // 2 bytes - keeper bonus as a share of cleared debt
+    pub penalty_destination: PenaltyDestination, // 1 byte
+    pub grants_pending: u64,             // 8 bytes - penalties owed to the grants vault, not yet swept
+    pub grant_proposal_count: u64,       // 8 bytes - next grant proposal id
+    pub grant_voting_period: i64,        // 8 bytes - seconds a grant proposal is open (0 = grants off)
+    pub grant_quorum_cows: u64,          // 8 bytes - minimum cows voting for a grant to pass
+    pub buy_volume_day: i64,             // 8 bytes - day index of the current volume bucket
+    pub buy_volume_current: u64,         // 8 bytes - cows bought so far that day
+    pub buy_volume_previous: u64,        // 8 bytes - cows bought the day before
+    pub whale_threshold_cows: u64,       // 8 bytes - herd size before the whale taper starts (0 = off)
+    pub whale_marginal_bps: u16,         // 2 bytes - each tier past the threshold earns this share of the last
+    pub reclaim_idle_seconds: i64,       // 8 bytes - idle time before unclaimed rewards can be reclaimed (0 = off)
+    pub reclaim_grace_seconds: i64,      // 8 bytes - notice between the warning and the sweep
+    pub total_reclaimed_rewards: u64,    // 8 bytes - unclaimed rewards returned to the pool
+    pub forgiveness_milestone_cows: u64, // 8 bytes - cows compounded per forgiveness pass earned (0 = off)
+    pub max_forgiveness_passes: u8,      // 1 byte - passes a farm can hold at once
+    pub param_max_step_bps: u16,         // 2 bytes - largest relative move per economic parameter change (0 = off)
+    pub param_update_cooldown: i64,      // 8 bytes - seconds between economic parameter changes
+    pub last_rate_limited_change: i64,   // 8 bytes
+    pub last_known_pool_balance: u64,    // 8 bytes - pool balance after the last pool-touching instruction
+    pub pool_balance_cached_at: i64,     // 8 bytes
+    pub upgrade_authority: Pubkey,       // 32 bytes - upgrade authority as last recorded by the admin (default = immutable)
+    pub upgrade_authority_recorded_at: i64, // 8 bytes
+    pub upgrade_timelock_seconds: i64,   // 8 bytes - declared delay enforced by the authority (e.g. a multisig timelock)
+    pub upgrade_guard_enabled: bool,     // 1 byte - pause the game if the live authority stops matching
+    pub paused: bool,                    // 1 byte - circuit breaker: blocks buys, sells, imports, exports, compounding, borrowing and withdrawals
+    pub starter_pack_cows: u64,          // 8 bytes - free cows per starter pack (0 = off)
+    pub starter_pack_gatekeeper: Pubkey, // 32 bytes - must co-sign claims when set (allowlist or identity check)
+    pub starter_packs_claimed: u64,      // 8 bytes
+    pub solvency_threshold: u64,         // 8 bytes - pool balance below which reward accrual pauses (0 = off)
+    pub accrual_paused_since: i64,       // 8 bytes - start of the current pause (0 = accruing)
+    pub accrual_paused_seconds: u64,     // 8 bytes - total length of all finished pauses
+    pub catch_up_rate: u64,              // 8 bytes - MILK per cow per day credited for paused time
+    pub penalty_exempt_below_cows: u64,  // 8 bytes - farms with fewer cows skip the early-withdrawal penalty (0 = off)
+    pub penalty_exempt_below_rewards: u64, // 8 bytes - withdrawals smaller than this skip the penalty (0 = off)
+    pub referral_buy_bps: u16,           // 2 bytes - referrer's share of a referred farm's purchases
+    pub referral_compound_bps: u16,      // 2 bytes - referrer's share of a referred farm's compounds (<= buy share)
+    pub total_cows_exported: u64,        // 8 bytes - lifetime cows exported as COW tokens
+    pub total_cows_imported: u64,        // 8 bytes - lifetime cows imported from COW tokens
+    pub cow_backing_enforced: bool,      // 1 byte - COW supply checked against tokenized cows on every mint
+    pub migration_deadline: i64,         // 8 bytes - execute_migration is disabled from this time on (0 = not yet set, disabled)
+    pub bonus_campaign_id: u64,          // 8 bytes - increments with every secondary-token campaign
+    pub bonus_mint: Pubkey,              // 32 bytes - token emitted alongside MILK by the current campaign
+    pub bonus_pool: Pubkey,              // 32 bytes - pool_authority-owned account funding the campaign
+    pub bonus_rate_per_day: u64,         // 8 bytes - bonus tokens per cow per day
+    pub bonus_starts_at: i64,            // 8 bytes
+    pub bonus_ends_at: i64,              // 8 bytes
+    pub withdrawal_tax_thresholds: [u64; WITHDRAWAL_TAX_TIERS], // 24 bytes - withdrawal size each tax bracket starts at
+    pub withdrawal_tax_bps: [u16; WITHDRAWAL_TAX_TIERS], // 6 bytes - marginal tax rate of each bracket (all 0 = off)
+    pub new_farmer_fund_pending: u64,    // 8 bytes - withdrawal tax owed to the marketing vault, not yet swept
+    pub total_withdrawal_tax: u64,       // 8 bytes - lifetime withdrawal tax collected
+    pub economic_params: EconomicParams, // 72 bytes - price and reward curve parameters (zeroed = compile-time defaults)
+    pub migration_delay: i64,            // 8 bytes - timelock between queue_migration and execute_migration
+    pub migration_executable_at: i64,    // 8 bytes - when the queued migration unlocks (0 = none queued)
+    pub migration_destination: Pubkey,   // 32 bytes - MILK account the queued migration pays out to
+    pub milk_mint_authority: Pubkey,     // 32 bytes - MILK mint authority at initialization (default = burned)
+    pub milk_freeze_authority: Pubkey,   // 32 bytes - MILK freeze authority at initialization (default = burned)
+    pub milk_mint_verified: bool,        // 1 byte - both matched the admin's declared expectation
+    pub tvl: u64,                        // 8 bytes - pool MILK as booked by the program's own transfers, drives all rate math
+    pub acc_reward_per_cow: u128,        // 16 bytes - MILK earned by one cow since launch, scaled by ACC_REWARD_PRECISION
+    pub reward_rate: u64,                // 8 bytes - curve MILK per cow per day before any event multiplier, re-priced on every sync
+    pub reward_rate_updated_at: i64,     // 8 bytes - when acc_reward_per_cow was last advanced
+    pub guardians: [Pubkey; MAX_GUARDIANS], // 160 bytes - keys that can veto a queued migration (default = empty seat)
+    pub guardian_threshold: u8,          // 1 byte - vetoes needed to cancel a queued migration (0 = no guardians)
+    pub migration_vetoes: u8,            // 1 byte - bitmask of guardian seats that vetoed the queued migration
+    pub pyth_price_account: Pubkey,      // 32 bytes - Pyth MILK/USD price update account for Pyth pricing mode
+    pub max_price_conf_bps: u16,         // 2 bytes - widest accepted Pyth confidence interval relative to the price
+    pub final_milking_started_at: i64,   // 8 bytes - wind-down start; the game stays paused from here on (0 = live)
+    pub final_milking_ends_at: i64,      // 8 bytes - end of the final distribution claim window
+    pub final_milking_pool: u64,         // 8 bytes - MILK distributed pro-rata to farmed cows
+    pub final_milking_cows: u64,         // 8 bytes - farmed cows at the start of the wind-down
+    pub final_milking_claimed: u64,      // 8 bytes - MILK claimed from final_milking_pool so far
+    pub premium_fee: u64,                // 8 bytes - MILK per Premium Dairy period, paid from rewards (0 = off)
+    pub premium_period: i64,             // 8 bytes - seconds one fee buys
+    pub premium_buy_cap_bps: u16,        // 2 bytes - raise of the per-transaction buy cap while premium
+    pub premium_penalty_discount_bps: u16, // 2 bytes - share of the early-withdrawal penalty waived while premium
+    pub premium_yield_bonus_bps: u16,    // 2 bytes - reward bonus while premium
+    pub total_premium_fees: u64,         // 8 bytes - lifetime Premium Dairy fees, kept in the pool
+    pub gift_campaign_cows_count: u64,   // 8 bytes - cows escrowed in gift campaigns and not yet claimed or swept
+    pub emergency_exit_bps: u16,         // 2 bytes - share of curve value paid by emergency_withdraw (0 = off)
+    pub fee_hooks: [FeeHook; FEE_HOOK_COUNT], // 136 bytes - re-routed value flows, indexed by FeeHookKind
+    pub fee_hook_pending: [u64; FEE_HOOK_COUNT], // 32 bytes - MILK owed to each hook's destination, not yet swept
+    pub stream_period: i64,              // 8 bytes - vesting period of streamed withdrawals (0 = off)
+    pub cosmetic_season: u16,            // 2 bytes - season whose seasonal cosmetics can be unlocked (0 = none)
+    pub lock_boost_bps: [u16; LOCK_TIERS], // 6 bytes - reward boost for locking rewards for each of LOCK_DURATIONS (0 = tier off)
+    pub cow_tiers: [CowTier; COW_RARITIES], // 12 bytes - price and yield multipliers per CowRarity (Common is always 1x)
+    pub cow_upgrade_ratio: u8,           // 1 byte - lower-tier cows combined into one upgraded cow (0 = upgrades off)
+    pub barn_base_capacity: u64,         // 8 bytes - cows a level-0 barn holds (0 = no barn cap)
+    pub barn_capacity_per_level: u64,    // 8 bytes - extra cows each barn level holds
+    pub barn_upgrade_cost: u64,          // 8 bytes - MILK burned for the first barn upgrade; level n+1 costs (n+1)x
+    pub phase: GamePhase,                // 1 byte - current game phase
+    pub phase_rules: [PhaseRules; GAME_PHASES], // 28 bytes - rule table per GamePhase
+    pub phase_transition_at: i64,        // 8 bytes - when the queued move to the next phase unlocks (0 = none queued)
+    pub phase_vetoes: u8,                // 1 byte - bitmask of guardian seats that vetoed the queued transition
+    pub feed_interval: i64,              // 8 bytes - farms must be fed this often to produce in full (0 = feeding off)
+    pub feed_cost_per_cow: u64,          // 8 bytes - MILK per producing cow paid into the pool by feed_cows
+    pub feed_decay_bps: u16,             // 2 bytes - yield lost per feeding period missed
+    pub feed_started_at: i64,            // 8 bytes - when feeding was turned on; farms count as fed then
+    pub breeding_fee: u64,               // 8 bytes - MILK paid into the pool per breeding
+    pub breeding_odds: [u16; BREEDING_OUTCOMES], // 8 bytes - bps chance of each BreedingOutcome (all 0 = breeding off)
+    pub event_count: u64,                // 8 bytes - seasonal events ever scheduled; next event id
+    pub event_starts_at: i64,            // 8 bytes - window of the latest scheduled event
+    pub event_ends_at: i64,              // 8 bytes
+    pub event_reward_bps: u16,           // 2 bytes - reward rate multiplier during the event (10000 = 1x)
+    pub event_price_discount_bps: u16,   // 2 bytes - off the cow price during the event
+    pub upgrade_guard_tripped: bool,     // 1 byte - the current pause was set by the upgrade guard, lifted by record_upgrade_authority
+    pub layout_version: u8,              // 1 byte - Config layout the account was last migrated to (0 = launch layout)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PricingMode {
+    /// Curve evaluated directly in MILK
+    Milk,
+    /// Curve evaluated in USD and converted to MILK at purchase time
+    Usd,
+    /// As `Usd`, converted at the Pyth MILK/USD price
+    Pyth,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PenaltyDestination {
+    /// Penalties stay in the reward pool
+    Pool,
+    /// Penalties accrue to the community grants vault
+    GrantsVault,
+}
+
+/// Value flows a fee hook can re-route, indexing `Config::fee_hooks`
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeHookKind {
+    /// Share of each purchase's cost, diverted from the pool
+    BuyFee,
+    /// Share of early-withdrawal penalties that would stay in the pool
+    WithdrawalPenalty,
+    /// Share of exported cows' curve value, charged to the farm's rewards
+    ExportFee,
+    /// Share of Premium Dairy fees
+    Treasury,
+}
+
+/// Stage of the game, indexing `Config::phase_rules`. Moves forward only.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GamePhase {
+    Presale,
+    Launch,
+    Steady,
+    Sunset,
+}
+
+/// What a game phase allows. Zero in every field leaves the game as is.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct PhaseRules {
+    /// PHASE_BLOCK_* bits of the actions the phase disables
+    pub blocked_actions: u8,
+    /// Scales the per-transaction buy cap (0 = unchanged)
+    pub buy_cap_bps: u16,
+    /// Surcharge on the cow price, paid into the pool
+    pub buy_fee_bps: u16,
+    /// Share of the early-withdrawal penalty waived
+    pub penalty_waiver_bps: u16,
+}
+
+/// Cow rarity, indexing `Config::cow_tiers`. Every cow starts Common;
+/// `upgrade_cows` combines lower-tier cows into the next tier.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CowRarity {
+    Common,
+    Rare,
+    Epic,
+}
+
+/// What a breeding produces, indexing `Config::breeding_odds`
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BreedingOutcome {
+    OneCalf,
+    TwoCalves,
+    ThreeCalves,
+    RareCalf,
+}
+
+/// Sale price and yield of one cow of a tier, relative to a Common cow
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct CowTier {
+    pub price_bps: u16,
+    pub reward_bps: u16,
+}
+
+/// Sends `bps` of one value flow to `destination`, a MILK token account.
+/// Off while `bps` is 0.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct FeeHook {
+    pub destination: Pubkey,
+    pub bps: u16,
+}
+
+/// Community grant payout put to a herd-weighted vote
+#[account]
+#[derive(InitSpace)]
+pub struct GrantProposal {
+    pub id: u64,                  // 8 bytes
+    pub proposer: Pubkey,         // 32 bytes
+    pub recipient: Pubkey,        // 32 bytes - MILK token account to pay
+    pub amount: u64,              // 8 bytes
+    #[max_len(128)]
+    pub description: String,      // 4 + 128 bytes
+    pub created_at: i64,          // 8 bytes
+    pub voting_ends_at: i64,      // 8 bytes
+    pub votes_for: u64,           // 8 bytes - cows
+    pub votes_against: u64,       // 8 bytes - cows
+    pub executed: bool,           // 1 byte
+}
+
+/// One wallet's vote on a grant proposal; its existence prevents double voting
+#[account]
+#[derive(InitSpace)]
+pub struct GrantVote {
+    pub voter: Pubkey,            // 32 bytes
+    pub proposal: Pubkey,         // 32 bytes
+    pub weight: u64,              // 8 bytes
+    pub approve: bool,            // 1 byte
+}
+
+/// Cows locked as collateral against MILK borrowed from the pool
+#[account]
+#[derive(InitSpace)]
+pub struct LoanPosition {
+    pub owner: Pubkey,            // 32 bytes
+    pub farm: Pubkey,             // 32 bytes
+    pub collateral_cows: u64,     // 8 bytes
+    pub debt: u64,                // 8 bytes - principal plus accrued interest
+    pub last_accrual_time: i64,   // 8 bytes
+    pub opened_at: i64,           // 8 bytes
+}
+
+/// Point-in-time summary of a farm for other programs to consume. Readers
+/// should check the account is owned by this program, sits at the expected
+/// PDA and has not expired.
+#[account]
+#[derive(InitSpace)]
+pub struct FarmAttestation {
+    pub farm: Pubkey,             // 32 bytes
+    pub owner: Pubkey,            // 32 bytes
+    pub cows: u64,                // 8 bytes
+    pub pending_cows: u64,        // 8 bytes - imported cows still in quarantine
+    pub pending_rewards: u64,     // 8 bytes - settled, unwithdrawn rewards
+    pub cost_basis: u64,          // 8 bytes
+    pub slot: u64,                // 8 bytes
+    pub attested_at: i64,         // 8 bytes
+    pub expires_at: i64,          // 8 bytes
+}
+
+/// A large withdrawal waiting out its dispute window. Funds stay in the pool
+/// until release.
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalEscrow {
+    pub farm: Pubkey,          // 32 bytes
+    pub owner: Pubkey,         // 32 bytes - receives the rent back on close
+    pub destination: Pubkey,   // 32 bytes - MILK token account to pay out to
+    pub amount: u64,           // 8 bytes - post-penalty payout
+    pub requested_at: i64,     // 8 bytes
+    pub release_at: i64,       // 8 bytes - end of the dispute window
+    pub flagged: bool,         // 1 byte - held for admin review
+    pub gross_amount: u64,     // 8 bytes - rewards taken off the farm, before penalty and tax
+    pub penalty: u64,          // 8 bytes
+    pub tax: u64,              // 8 bytes
+    pub grants_earmarked: u64, // 8 bytes - penalty set aside for the grants vault
+    pub fee_earmarked: u64,    // 8 bytes - penalty set aside for the withdrawal penalty fee hook
+    pub pass_redeemed: bool,   // 1 byte - a forgiveness pass waived the penalty
+    pub prior_withdraw_time: i64, // 8 bytes - the farm's withdrawal clock before the request
+}
+
+/// A limited-time seasonal event. The latest one is mirrored into Config,
+/// where the reward and price curves read it.
+#[account]
+#[derive(InitSpace)]
+pub struct GameEvent {
+    pub id: u64,                  // 8 bytes
+    pub starts_at: i64,           // 8 bytes
+    pub ends_at: i64,             // 8 bytes - moved up if cancelled early
+    pub reward_bps: u16,          // 2 bytes - reward rate multiplier (10000 = 1x)
+    pub price_discount_bps: u16,  // 2 bytes - off the cow price
+    pub created_at: i64,          // 8 bytes
+}
+
+/// Two cows waiting on VRF randomness to breed. The parents have already
+/// left the herd; their genetics and cost basis pass to the calves.
+#[account]
+#[derive(InitSpace)]
+pub struct BreedingRequest {
+    pub farm: Pubkey,          // 32 bytes
+    pub owner: Pubkey,         // 32 bytes - receives the rent back on close
+    pub seed: [u8; 32],        // 32 bytes - ORAO randomness request seed
+    pub genetics_bps_sum: i64, // 8 bytes - the parents' genetics
+    pub cost_basis: u64,       // 8 bytes - the parents' cost basis plus the fee
+    pub requested_at: i64,     // 8 bytes
+}
+
+/// Rewards a farm took through `withdraw_streamed`, released linearly from
+/// `starts_at` to `ends_at`. Topping up restarts the schedule for the
+/// unvested remainder plus the new amount; what had already vested is
+/// carried over.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingStream {
+    pub farm: Pubkey,          // 32 bytes
+    pub owner: Pubkey,         // 32 bytes - may claim vested MILK
+    pub amount: u64,           // 8 bytes - MILK in the current schedule
+    pub claimed: u64,          // 8 bytes - claimed from the current schedule
+    pub carried: u64,          // 8 bytes - vested under earlier schedules, not yet claimed
+    pub starts_at: i64,        // 8 bytes
+    pub ends_at: i64,          // 8 bytes - fully vested from here
+    pub total_claimed: u64,    // 8 bytes - lifetime MILK claimed
+}
+
+/// Per-wallet devnet faucet rate limit
+#[cfg(feature = "devnet")]
+#[account]
+#[derive(InitSpace)]
+pub struct FaucetClaim {
+    pub last_claim_time: i64,  // 8 bytes
+    pub total_claimed: u64,    // 8 bytes
+}
+
+/// Marks a wallet as having claimed its starter pack
+#[account]
+#[derive(InitSpace)]
+pub struct StarterPackClaim {
+    pub cows: u64,             // 8 bytes
+    pub claimed_at: i64,       // 8 bytes
+}
+
+/// MILK/USD price published by the configured oracle authority
+#[account]
+#[derive(InitSpace)]
+pub struct MilkPrice {
+    pub price_micro_usd: u64,  // 8 bytes - USD price of 1 MILK with 6 decimals
+    pub updated_at: i64,       // 8 bytes
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct FarmAccount {
+    pub owner: Pubkey,               // 32 bytes
+    pub cows: u64,                   // 8 bytes
+    pub last_update_time: i64,       // 8 bytes
+    pub accumulated_rewards: u64,    // 8 bytes
+    pub last_reward_rate: u64,       // 8 bytes - legacy rate snapshot from before the accumulator, settled by migrate_farm
+    pub last_withdraw_time: i64,     // 8 bytes - timestamp of last withdrawal
+    pub pending_cows: u64,           // 8 bytes - imported cows still in quarantine
+    pub pending_cows_ready_at: i64,  // 8 bytes - when pending cows start producing
+    pub compound_day_mask: u32,      // 4 bytes - bit i set if compounded i days before last_compound_day
+    pub last_compound_day: i64,      // 8 bytes - day index of the most recent compound
+    pub epoch_production: [EpochProduction; EPOCH_HISTORY_LEN], // 96 bytes - ring buffer of daily production
+    pub automation_opt_in: bool,     // 1 byte - whitelisted automation may compound/poke this farm
+    pub genetics_bps_sum: i64,       // 8 bytes - sum over purchased cows of their genetics modifier (bps)
+    pub rental_share_bps: u16,       // 2 bytes - manager share of harvests while rented (0 = not rented)
+    pub rental_expires_at: i64,      // 8 bytes - end of the current rental period
+    pub rental_owed: u64,            // 8 bytes - manager share accrued but not yet settled
+    pub last_buy_time: i64,          // 8 bytes - timestamp of last buy_cows
+    pub last_interaction_time: i64,  // 8 bytes - last owner-signed settle, restores milking efficiency
+    pub relay_nonce: u64,            // 8 bytes - next nonce accepted for a relayed action
+    pub cost_basis: u64,             // 8 bytes - MILK spent on the current herd (buys + compounds)
+    pub reclaim_warned_at: i64,      // 8 bytes - when the farm was warned of idle reclamation (0 = never)
+    pub lifetime_compounded_cows: u64, // 8 bytes - cows ever compounded, drives forgiveness pass milestones
+    pub forgiveness_passes: u8,      // 1 byte - unredeemed penalty forgiveness passes
+    pub forgiveness_pass_armed: bool, // 1 byte - next penalized withdrawal is forgiven
+    pub forgiveness_passes_redeemed: u32, // 4 bytes
+    pub paused_clock: u64,           // 8 bytes - global paused seconds as of the last update
+    pub paused_cow_seconds: u64,     // 8 bytes - production missed during accrual pauses, awaiting catch-up
+    pub referrer: Pubkey,            // 32 bytes - owner of the referring farm, fixed when the farm is created
+    pub referral_earnings: u64,      // 8 bytes - lifetime rewards credited from referred farms
+    pub withdrawal_count: u64,       // 8 bytes - withdrawals settled so far; sequence of the next receipt
+    pub bonus_campaign_id: u64,      // 8 bytes - campaign bonus_rewards were earned in
+    pub bonus_rewards: u64,          // 8 bytes - unclaimed secondary-token rewards
+    pub preferences: u8,             // 1 byte - FARM_PREF_* bits chosen by the owner
+    pub tag: [u8; 32],               // 32 bytes - custodian sub-account tag, set once (zero = untagged)
+    pub community_goal_id: u64,      // 8 bytes - community goal community_goal_contribution counts towards
+    pub community_goal_contribution: u64, // 8 bytes - MILK spent on buys and compounds during that goal
+    pub community_boost_bps: u16,    // 2 bytes - reward boost unlocked by a reached community goal
+    pub community_boost_until: i64,  // 8 bytes - end of the unlocked boost
+    pub yield_claim_until: i64,      // 8 bytes - owner rewards accrue to yield claim holders until here
+    pub yield_claim_owed: u64,       // 8 bytes - holders' share accrued but not yet settled into the claim
+    pub total_milk_spent: u64,       // 8 bytes - lifetime MILK spent on buys and compounds
+    pub total_milk_withdrawn: u64,   // 8 bytes - lifetime MILK paid out by withdrawals, after penalty and tax
+    pub total_penalties_paid: u64,   // 8 bytes - lifetime early-withdrawal penalties
+    pub farm_created_at: i64,        // 8 bytes - 0 for farms created before it was recorded
+    pub premium_until: i64,          // 8 bytes - end of the paid Premium Dairy period
+    pub locked_rewards: u64,         // 8 bytes - rewards set aside by lock_rewards, returned by unlock_rewards
+    pub rewards_locked_until: i64,   // 8 bytes - end of the lock and of its boost
+    pub lock_boost_bps: u16,         // 2 bytes - reward boost earned by the current lock
+    pub withholding_account: Pubkey, // 32 bytes - MILK account receiving the withheld share of withdrawals
+    pub withholding_bps: u16,        // 2 bytes - share of each withdrawal withheld, 0 = off
+    pub rare_cows: u64,              // 8 bytes - Rare cows among `cows`
+    pub epic_cows: u64,              // 8 bytes - Epic cows among `cows`
+    pub barn_level: u8,              // 1 byte - raises the farm's cow cap, bought with upgrade_barn
+    pub last_fed_at: i64,            // 8 bytes - last feed_cows, restarts the feeding period
+    pub tax_window_started_at: i64,  // 8 bytes - start of the 24h window the withdrawal tax brackets cover
+    pub tax_window_withdrawn: u64,   // 8 bytes - MILK taxed so far in that window
+    pub reward_debt: u128,           // 16 bytes - acc_reward_per_cow at last_update_time
+}
+
+/// A wallet's cosmetic progression. Bit `i` of `cosmetics` is set once the
+/// wallet unlocked cosmetic `i` of the catalog; nothing here affects the
+/// economy.
+#[account]
+#[derive(InitSpace)]
+pub struct FarmerProfile {
+    pub wallet: Pubkey,              // 32 bytes
+    pub cosmetics: u64,              // 8 bytes - unlocked cosmetics bitfield
+    pub last_unlocked_at: i64,       // 8 bytes
+}
+
+/// A wallet's registration as a referrer, with stats over the farms it
+/// referred
+#[account]
+#[derive(InitSpace)]
+pub struct Referrer {
+    pub owner: Pubkey,               // 32 bytes
+    pub referred_farms: u64,         // 8 bytes - farms created with this referrer
+    pub referred_volume: u64,        // 8 bytes - MILK spent by referred farms on buys and compounds
+    pub earnings: u64,               // 8 bytes - MILK credited to the referrer's farm
+    pub registered_at: i64,          // 8 bytes
+}
+
+/// Listing and live state of a whole-farm management rental
+#[account]
+#[derive(InitSpace)]
+pub struct FarmRental {
+    pub owner: Pubkey,      // 32 bytes
+    pub farm: Pubkey,       // 32 bytes
+    pub manager: Pubkey,    // 32 bytes - default until accepted
+    pub share_bps: u16,     // 2 bytes - manager share of harvests
+    pub duration: i64,      // 8 bytes - rental length once accepted
+    pub started_at: i64,    // 8 bytes
+    pub expires_at: i64,    // 8 bytes
+}
+
+/// Least-privilege grant letting a third party compound a farm. Delegates
+/// can never withdraw.
+#[account]
+#[derive(InitSpace)]
+pub struct Delegation {
+    pub farm: Pubkey,               // 32 bytes
+    pub delegate: Pubkey,           // 32 bytes
+    pub max_cows_per_day: u64,      // 8 bytes
+    pub expires_at: i64,            // 8 bytes
+    pub day: i64,                   // 8 bytes - day index that cows_today counts toward
+    pub cows_today: u64,            // 8 bytes
+    pub total_cows_compounded: u64, // 8 bytes
+}
+
+/// Small, frequently rewritten snapshot of the live price and rate, for
+/// integrators that would rather subscribe to one account than recompute
+/// from Config and the pool every slot
+#[account]
+#[derive(InitSpace)]
+pub struct Quote {
+    pub cow_price: u64,               // 8 bytes - MILK bonding-curve price of the next cow
+    pub reward_rate: u64,             // 8 bytes - MILK per cow per day
+    pub pool_balance: u64,            // 8 bytes
+    pub global_cows_count: u64,       // 8 bytes
+    pub slot: u64,                    // 8 bytes - slot the quote was written in
+    pub updated_at: i64,              // 8 bytes
+}
+
+/// Durable record of a single withdrawal, closable by the owner for rent
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalReceipt {
+    pub farm: Pubkey,                 // 32 bytes
+    pub owner: Pubkey,                // 32 bytes
+    pub sequence: u64,                // 8 bytes - the farm's withdrawal_count when settled
+    pub amount: u64,                  // 8 bytes
+    pub penalty: u64,                 // 8 bytes
+    pub timestamp: i64,               // 8 bytes
+    pub slot: u64,                    // 8 bytes
+}
+
+/// Public index of a wallet's accounts for portfolio trackers, with a
+/// snapshot of the position that anyone can refresh. Addresses are listed
+/// whether or not the account exists yet.
+#[account]
+#[derive(InitSpace)]
+pub struct Portfolio {
+    pub wallet: Pubkey,               // 32 bytes
+    pub farm: Pubkey,                 // 32 bytes
+    pub loan_position: Pubkey,        // 32 bytes
+    pub harvest_log: Pubkey,          // 32 bytes
+    pub escrow: Pubkey,               // 32 bytes
+    pub rental: Pubkey,               // 32 bytes
+    pub cows: u64,                    // 8 bytes
+    pub pending_cows: u64,            // 8 bytes
+    pub accumulated_rewards: u64,     // 8 bytes - as of the farm's last settlement
+    pub bonus_rewards: u64,           // 8 bytes
+    pub referral_earnings: u64,       // 8 bytes
+    pub collateral_cows: u64,         // 8 bytes
+    pub debt: u64,                    // 8 bytes
+    pub refreshed_at: i64,            // 8 bytes
+    pub refreshed_slot: u64,          // 8 bytes
+}
+
+/// Immutable copy of a farm and the global state it was priced against,
+/// taken by the owner at a moment of their choosing
+#[account]
+#[derive(InitSpace)]
+pub struct FarmCheckpoint {
+    pub farm: Pubkey,                 // 32 bytes
+    pub checkpoint_id: u64,           // 8 bytes - owner-chosen, part of the seeds
+    pub slot: u64,                    // 8 bytes
+    pub timestamp: i64,               // 8 bytes
+    pub state: FarmAccount,           // complete farm state, settled to timestamp
+    pub global_cows_count: u64,       // 8 bytes
+    pub farming_cows_count: u64,      // 8 bytes
+    pub tokenized_cows_count: u64,    // 8 bytes
+    pub pool_balance: u64,            // 8 bytes
+    pub cow_price: u64,               // 8 bytes
+    pub reward_rate: u64,             // 8 bytes - MILK per cow per day
+    pub param_change_count: u64,      // 8 bytes - ties the checkpoint to the ParamChanged history
+    pub economic_params: EconomicParams, // 72 bytes
+}
+
+/// Program-wide waiver of the early-withdrawal penalty, declared by the
+/// admin after an incident kept users from their 24h windows
+#[account]
+#[derive(InitSpace)]
+pub struct PenaltyGrace {
+    pub starts_at: i64,               // 8 bytes
+    pub ends_at: i64,                 // 8 bytes - penalties apply again from here
+    pub declared_at: i64,             // 8 bytes
+    pub reason: [u8; 32],             // 32 bytes - hash of the incident report
+}
+
+/// Co-op goal for the whole community: MILK spent on buys and compounds
+/// between `starts_at` and `ends_at`. Reaching `target` unlocks a temporary
+/// reward boost for every farm that contributed at least `min_contribution`.
+#[account]
+#[derive(InitSpace)]
+pub struct CommunityGoal {
+    pub id: u64,                      // 8 bytes - increments with each new goal
+    pub target: u64,                  // 8 bytes - MILK the community has to spend
+    pub progress: u64,                // 8 bytes
+    pub starts_at: i64,               // 8 bytes
+    pub ends_at: i64,                 // 8 bytes - contributions count until here
+    pub min_contribution: u64,        // 8 bytes - MILK a farm must spend to share the boost
+    pub boost_bps: u16,               // 2 bytes
+    pub boost_duration: i64,          // 8 bytes - how long the boost runs once the goal is reached
+    pub reached_at: i64,              // 8 bytes - 0 until the target is hit
+}
+
+/// A farm's tokenized yield: holders of `claim_mint` are owed everything the
+/// farm's owner earns between `starts_at` and `expires_at`, and redeem it
+/// pro rata once the period is over. The cows stay with the farm.
+#[account]
+#[derive(InitSpace)]
+pub struct YieldClaim {
+    pub farm: Pubkey,                 // 32 bytes
+    pub claim_mint: Pubkey,           // 32 bytes - 6 decimals, minted to the owner on tokenization
+    pub starts_at: i64,               // 8 bytes
+    pub expires_at: i64,              // 8 bytes - end of the tokenized period
+    pub supply: u64,                  // 8 bytes - claim tokens not yet redeemed
+    pub settled_yield: u64,           // 8 bytes - MILK settled from the farm and not yet redeemed
+    pub total_yield: u64,             // 8 bytes - MILK settled over the current period
+    pub bump: u8,                     // 1 byte
+}
+
+/// Pooled COW vault: runs one farm for its shareholders and reinvests that
+/// farm's rewards into more cows, so each share redeems for a growing
+/// number of cows
+#[account]
+#[derive(InitSpace)]
+pub struct CowVault {
+    pub share_mint: Pubkey,           // 32 bytes
+    pub farm: Pubkey,                 // 32 bytes - farm owned by this vault
+    pub total_shares: u64,            // 8 bytes - outstanding shares, 6 decimals
+    pub total_deposited_cows: u64,    // 8 bytes
+    pub total_redeemed_cows: u64,     // 8 bytes
+    pub bump: u8,                     // 1 byte
+}
+
+/// Cows a sponsor set aside for an allowlist of wallets. Each listed wallet
+/// can claim `cows_per_claim` once before `expires_at`; whatever is left
+/// after that goes back to the sponsor's farm.
+#[account]
+#[derive(InitSpace)]
+pub struct GiftCampaign {
+    pub sponsor: Pubkey,              // 32 bytes - funded the campaign; unclaimed cows return to their farm
+    pub id: u64,                      // 8 bytes - chosen by the sponsor, unique per sponsor
+    pub allowlist_root: [u8; 32],     // 32 bytes - merkle root over GiftCampaign::leaf of each listed wallet
+    pub cows_per_claim: u64,          // 8 bytes
+    pub cows_remaining: u64,          // 8 bytes - escrowed cows not yet claimed
+    pub claims: u64,                  // 8 bytes
+    pub created_at: i64,              // 8 bytes
+    pub expires_at: i64,              // 8 bytes - claims close and the sweep opens here
+}
+
+impl GiftCampaign {
+    /// Allowlist leaf for `wallet`, bound to the campaign so one list can't
+    /// be replayed against another.
+    pub fn leaf(campaign: &Pubkey, wallet: &Pubkey) -> [u8; 32] {
+        hashv(&[b"gift_campaign_leaf", campaign.as_ref(), wallet.as_ref()]).to_bytes()
+    }
+}
+
+/// Marks a wallet's claim on a gift campaign; existing is what prevents a
+/// second claim
+#[account]
+#[derive(InitSpace)]
+pub struct GiftClaim {
+    pub campaign: Pubkey,             // 32 bytes
+    pub wallet: Pubkey,               // 32 bytes
+    pub cows: u64,                    // 8 bytes
+    pub claimed_at: i64,              // 8 bytes
+}
+
+/// COW staking pool: staked COW tokens sit in `token_vault` and share
+/// `emission_per_day` MILK from the pool pro rata
+#[account]
+#[derive(InitSpace)]
+pub struct CowStakingPool {
+    pub token_vault: Pubkey,          // 32 bytes - COW token account owned by this PDA
+    pub emission_per_day: u64,        // 8 bytes - MILK emitted per day across all stakers (0 = off)
+    pub total_staked: u64,            // 8 bytes - whole cows staked
+    pub acc_reward_per_cow: u128,     // 16 bytes - MILK per staked cow since opening, scaled by ACC_REWARD_PRECISION
+    pub updated_at: i64,              // 8 bytes - when acc_reward_per_cow was last advanced
+    pub total_emitted: u64,           // 8 bytes - lifetime MILK emitted to stakers
+    pub bump: u8,                     // 1 byte
+}
+
+/// A wallet's staked COW and unclaimed staking rewards
+#[account]
+#[derive(InitSpace)]
+pub struct CowStake {
+    pub owner: Pubkey,                // 32 bytes
+    pub staked_cows: u64,             // 8 bytes
+    pub reward_debt: u128,            // 16 bytes - pool acc_reward_per_cow at the last settlement
+    pub pending_rewards: u64,         // 8 bytes - MILK earned and not yet claimed
+    pub total_claimed: u64,           // 8 bytes
+}
+
+/// Opt-in on-chain history of a farm's most recent withdrawals
+#[account]
+#[derive(InitSpace)]
+pub struct HarvestLog {
+    pub farm: Pubkey,                 // 32 bytes
+    pub total_harvests: u64,          // 8 bytes - harvests ever recorded; next slot is total % HARVEST_LOG_LEN
+    pub entries: [HarvestEntry; HARVEST_LOG_LEN], // 384 bytes - ring buffer, oldest overwritten first
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default)]
+pub struct HarvestEntry {
+    pub amount: u64,     // paid out (or escrowed) after penalty
+    pub penalty: u64,
+    pub timestamp: i64,
+}
+
+/// MILK produced by a farm during one daily epoch (day index since start)
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default)]
+pub struct EpochProduction {
+    pub epoch: u32,
+    pub produced: u64,
+}
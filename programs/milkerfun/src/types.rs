@@ -0,0 +1,361 @@
+//! Instruction arguments and view return types.
+
+use std::ops::RangeInclusive;
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::ErrorCode;
+use crate::state::{BreedingOutcome, CowRarity, FeeHookKind, GamePhase, PenaltyDestination, PricingMode};
+
+/// Curve parameters for cow pricing and reward emission. Fractional
+/// coefficients are in basis points so the curves stay integer-only.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EconomicParams {
+    pub cow_base_price: u64,         // P₀
+    pub price_pivot: u64,            // C_pivot
+    pub price_steepness_bps: u64,    // α
+    pub reward_base: u64,            // B
+    pub reward_sensitivity_bps: u64, // α_reward
+    pub tvl_normalization: u64,      // S
+    pub min_reward_per_day: u64,     // R_min
+    pub greed_multiplier_bps: u64,   // β
+    pub greed_decay_pivot: u64,      // C₀
+}
+
+impl EconomicParams {
+    /// Reject parameter sets the curve code can't evaluate sensibly
+    pub fn validate(&self) -> Result<()> {
+        require!(self.cow_base_price > 0 && self.cow_base_price <= MAX_COW_BASE_PRICE, ErrorCode::InvalidParameter);
+        require!(self.price_pivot > 0 && self.price_pivot <= MAX_CURVE_PIVOT, ErrorCode::InvalidParameter);
+        require!(self.price_steepness_bps <= MAX_PRICE_STEEPNESS_BPS, ErrorCode::InvalidParameter);
+        require!(self.reward_base > 0 && self.reward_base <= MAX_REWARD_BASE, ErrorCode::InvalidParameter);
+        require!(self.reward_sensitivity_bps <= MAX_REWARD_SENSITIVITY_BPS, ErrorCode::InvalidParameter);
+        require!(self.tvl_normalization >= MIN_TVL_NORMALIZATION, ErrorCode::InvalidParameter);
+        require!(self.min_reward_per_day <= self.reward_base, ErrorCode::InvalidParameter);
+        require!(self.greed_multiplier_bps <= MAX_GREED_MULTIPLIER_BPS, ErrorCode::InvalidParameter);
+        require!(self.greed_decay_pivot > 0 && self.greed_decay_pivot <= MAX_CURVE_PIVOT, ErrorCode::InvalidParameter);
+        Ok(())
+    }
+}
+
+/// Action a farm owner can authorize a relayer to submit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RelayedAction {
+    Compound { num_cows: u64 },
+    Withdraw,
+}
+
+/// Where `harvest_as_cows` delivers the cows bought with a farm's rewards
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HarvestTarget {
+    Farm,
+    Tokens,
+}
+
+/// Off-chain message signed by the farm owner, prefixed with RELAY_MESSAGE_DOMAIN
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RelayedMessage {
+    pub program_id: Pubkey,
+    pub farm: Pubkey,
+    pub action: RelayedAction,
+    pub nonce: u64,
+    pub expiry: i64,
+}
+
+/// Admin-tunable Config parameters, as reported in ParamChanged events
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConfigParam {
+    ImportQuarantine,
+    StreakDaysRequired,
+    StreakBonusBps,
+    AutomationProgram,
+    PricingMode,
+    CowBasePriceUsd,
+    PriceOracleAuthority,
+    MaxPriceAge,
+    BuyCooldown,
+    Pauser,
+    EscrowThreshold,
+    EscrowWindow,
+    EfficiencyGrace,
+    EfficiencyDecayBpsPerDay,
+    EfficiencyFloorBps,
+    LoanLtvBps,
+    LiquidationThresholdBps,
+    LoanInterestBps,
+    LiquidationBonusBps,
+    PenaltyDestination,
+    GrantVotingPeriod,
+    GrantQuorumCows,
+    WhaleThresholdCows,
+    WhaleMarginalBps,
+    ReclaimIdleSeconds,
+    ReclaimGraceSeconds,
+    ForgivenessMilestoneCows,
+    MaxForgivenessPasses,
+    ParamMaxStepBps,
+    ParamUpdateCooldown,
+    UpgradeAuthority,
+    StarterPackCows,
+    StarterPackGatekeeper,
+    SolvencyThreshold,
+    CatchUpRate,
+    PenaltyExemptBelowCows,
+    PenaltyExemptBelowRewards,
+    ReferralBuyBps,
+    ReferralCompoundBps,
+    MigrationDeadline,
+    BonusCampaign,
+    WithdrawalTaxThreshold,
+    WithdrawalTaxBps,
+    CowBasePrice,
+    PricePivot,
+    PriceSteepnessBps,
+    RewardBase,
+    RewardSensitivityBps,
+    TvlNormalization,
+    MinRewardPerDay,
+    GreedMultiplierBps,
+    GreedDecayPivot,
+    Paused,
+    PenaltyGrace,
+    MigrationDelay,
+    CommunityGoal,
+    Guardian,
+    GuardianThreshold,
+    PythPriceAccount,
+    MaxPriceConfBps,
+    PremiumFee,
+    PremiumPeriod,
+    PremiumBuyCapBps,
+    PremiumPenaltyDiscountBps,
+    PremiumYieldBonusBps,
+    EmergencyExitBps,
+    FeeHookDestination(FeeHookKind),
+    FeeHookBps(FeeHookKind),
+    StreamPeriod,
+    CosmeticSeason,
+    LockBoostBps(u8),
+    CowStakingEmission,
+    CowTierPriceBps(CowRarity),
+    CowTierRewardBps(CowRarity),
+    CowUpgradeRatio,
+    BarnBaseCapacity,
+    BarnCapacityPerLevel,
+    BarnUpgradeCost,
+    PhaseBlockedActions(GamePhase),
+    PhaseBuyCapBps(GamePhase),
+    PhaseBuyFeeBps(GamePhase),
+    PhasePenaltyWaiverBps(GamePhase),
+    FeedInterval,
+    FeedCostPerCow,
+    FeedDecayBps,
+    BreedingFee,
+    BreedingOddsBps(BreedingOutcome),
+}
+
+impl ConfigParam {
+    /// Economic parameters whose changes are bounded by the step limit and
+    /// cooldown. Keys, modes and safety switches can move freely.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(
+            self,
+            ConfigParam::StreakBonusBps
+                | ConfigParam::PremiumYieldBonusBps
+                | ConfigParam::LockBoostBps(_)
+                | ConfigParam::CowStakingEmission
+                | ConfigParam::CowTierPriceBps(_)
+                | ConfigParam::CowTierRewardBps(_)
+                | ConfigParam::CowUpgradeRatio
+                | ConfigParam::BarnBaseCapacity
+                | ConfigParam::BarnCapacityPerLevel
+                | ConfigParam::BarnUpgradeCost
+                | ConfigParam::FeedInterval
+                | ConfigParam::FeedCostPerCow
+                | ConfigParam::FeedDecayBps
+                | ConfigParam::BreedingFee
+                | ConfigParam::BreedingOddsBps(_)
+                | ConfigParam::CowBasePriceUsd
+                | ConfigParam::BuyCooldown
+                | ConfigParam::EfficiencyGrace
+                | ConfigParam::EfficiencyDecayBpsPerDay
+                | ConfigParam::EfficiencyFloorBps
+                | ConfigParam::LoanLtvBps
+                | ConfigParam::LiquidationThresholdBps
+                | ConfigParam::LoanInterestBps
+                | ConfigParam::LiquidationBonusBps
+                | ConfigParam::WhaleThresholdCows
+                | ConfigParam::WhaleMarginalBps
+                | ConfigParam::ForgivenessMilestoneCows
+                | ConfigParam::SolvencyThreshold
+                | ConfigParam::CatchUpRate
+                | ConfigParam::PenaltyExemptBelowCows
+                | ConfigParam::PenaltyExemptBelowRewards
+                | ConfigParam::ParamMaxStepBps
+                | ConfigParam::ParamUpdateCooldown
+                | ConfigParam::WithdrawalTaxThreshold
+                | ConfigParam::WithdrawalTaxBps
+                | ConfigParam::CowBasePrice
+                | ConfigParam::PricePivot
+                | ConfigParam::PriceSteepnessBps
+                | ConfigParam::RewardBase
+                | ConfigParam::RewardSensitivityBps
+                | ConfigParam::TvlNormalization
+                | ConfigParam::MinRewardPerDay
+                | ConfigParam::GreedMultiplierBps
+                | ConfigParam::GreedDecayPivot
+        )
+    }
+
+    /// Rate-limited parameters whose zero value turns their feature off.
+    /// These may always be switched off; see `switch_on_bounds` for
+    /// switching them back on.
+    pub fn is_switch(&self) -> bool {
+        self.switch_on_bounds().is_some()
+    }
+
+    /// Values a switch may be turned on at from zero, conservative in
+    /// whichever direction hurts users or the pool. Later changes are
+    /// bounded by the step limit from there.
+    pub fn switch_on_bounds(&self) -> Option<RangeInclusive<u64>> {
+        Some(match self {
+            ConfigParam::StreakBonusBps | ConfigParam::LockBoostBps(_) => 1..=500,
+            ConfigParam::CowStakingEmission => 1..=1_000_000_000, // 1,000 MILK per day
+            ConfigParam::CowUpgradeRatio => 2..=MAX_COW_UPGRADE_RATIO as u64,
+            ConfigParam::BarnBaseCapacity | ConfigParam::WhaleThresholdCows => 10_000..=u64::MAX,
+            ConfigParam::FeedInterval => 7 * SECONDS_PER_DAY as u64..=MAX_FEED_INTERVAL as u64,
+            ConfigParam::BuyCooldown => 1..=3_600,
+            ConfigParam::EfficiencyDecayBpsPerDay => 1..=100,
+            ConfigParam::LoanLtvBps => 1..=2_500,
+            ConfigParam::ForgivenessMilestoneCows => 100..=u64::MAX,
+            ConfigParam::SolvencyThreshold => 1..=INITIAL_TVL / 10,
+            ConfigParam::PenaltyExemptBelowCows => 1..=10,
+            ConfigParam::PenaltyExemptBelowRewards => 1..=100_000_000, // 100 MILK
+            ConfigParam::WithdrawalTaxBps => 1..=500,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ParamValue {
+    Signed(i64),
+    Unsigned(u64),
+    Key(Pubkey),
+    PricingMode(PricingMode),
+    PenaltyDestination(PenaltyDestination),
+}
+
+/// Authorities the admin declares the MILK mint must have at
+/// initialization. `None` means the authority must be burned.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MintAuthorityExpectation {
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GlobalStats {
+    pub global_cows_count: u64,
+    pub pool_balance_milk: u64,
+    pub pool_balance_as_of: i64,
+    pub farming_cows_count: u64,
+    pub tokenized_cows_count: u64,
+    pub max_cows_per_transaction: u64,
+    pub tvl: u64,                       // Pool MILK as booked by the program, used for all rate math
+    pub milk_mint_authority: Pubkey,    // Default when burned
+    pub milk_freeze_authority: Pubkey,  // Default when burned
+    pub milk_mint_verified: bool,
+}
+
+/// One farm's entry in get_farms_stats_batch, in the order the farms were passed
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FarmSummary {
+    pub cows: u64,
+    pub pending_cows: u64,              // Imported cows still in quarantine
+    pub accumulated_rewards: u64,       // As of last_update_time
+    pub last_update_time: i64,
+    pub cost_basis: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpgradeAuthorityStatus {
+    pub recorded_authority: Pubkey,
+    pub current_authority: Pubkey,      // Default when the program is immutable
+    pub matches: bool,
+    pub recorded_at: i64,
+    pub timelock_seconds: i64,
+    pub guard_enabled: bool,
+    pub paused: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CowBacking {
+    pub cow_supply: u64,                // Raw COW token supply (6 decimals)
+    pub backed_supply: u64,             // tokenized_cows * 1 COW
+    pub tokenized_cows: u64,
+    pub total_cows_exported: u64,
+    pub total_cows_imported: u64,
+    pub fully_backed: bool,
+    pub enforced: bool,
+}
+
+/// Projected earnings for a farm over a horizon under three rate
+/// assumptions. Projections apply the farm's genetics but no streak bonus,
+/// efficiency decay or whale taper.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RewardProjection {
+    pub horizon_seconds: i64,
+    pub cows: u64,
+    pub current_rate: u64,              // The rate the farm accrues at now
+    pub current_projection: u64,
+    pub diluted_rate: u64,              // After the hypothetical global herd increase
+    pub diluted_projection: u64,
+    pub min_rate: u64,                  // The floor the curve never goes below
+    pub min_projection: u64,
+}
+
+/// A farm settled to now in memory, by the same accrual and withdrawal
+/// logic the program runs, without writing anything
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FarmStats {
+    pub cows: u64,
+    pub pending_cows: u64,              // Imported cows still in quarantine
+    pub pending_rewards: u64,           // Accrued up to now, before any penalty
+    pub reward_rate: u64,               // Live MILK per cow per day, event multiplier included
+    pub daily_rewards: u64,             // What the farm would accrue over the next day if nothing changed
+    pub seconds_since_last_withdraw: Option<i64>, // None before the first withdrawal
+    pub penalty_free_at: i64,           // When withdrawing stops incurring the early penalty
+    pub withdrawable_now: u64,          // Payout of withdrawing everything now
+    pub penalty_now: u64,               // Penalty that withdrawal would pay
+}
+
+/// What buying `num_cows` right now would cost, priced exactly as
+/// `buy_cows` prices it, and the reward rate once the purchase lands
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BuyQuote {
+    pub num_cows: u64,
+    pub price_per_cow: u64,             // Average over the cows bought, phase fee and event discount included
+    pub total_cost: u64,
+    pub reward_rate: u64,               // The rate before the purchase
+    pub reward_rate_after: u64,         // With the cows and the pool's share of the payment added
+    pub max_cows_per_transaction: u64,  // Before any premium bonus or phase cap
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ParamsPreview {
+    pub cow_price: u64,
+    pub reward_rate: u64,
+    pub current_cow_price: u64,
+    pub current_reward_rate: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PositionHealth {
+    pub collateral_cows: u64,
+    pub collateral_value: u64,
+    pub debt: u64,
+    pub max_debt: u64,
+    pub liquidation_debt: u64,
+    pub health_factor_bps: u64, // 10000 = exactly at the liquidation threshold
+    pub liquidatable: bool,
+}
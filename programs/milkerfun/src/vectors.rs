@@ -0,0 +1,128 @@
+//! Canonical test vectors for the economy math under the default curve
+//! parameters. Enabled with the `client` feature. Off-chain implementations
+//! of the bonding and reward curves should reproduce every value in
+//! `vectors/economy.json` exactly; the file is regenerated with
+//! `cargo run --example export_vectors --features client`.
+
+use crate::constants::INITIAL_TVL;
+use crate::math::{calculate_cow_price_with, calculate_reward_rate_with, calculate_sell_payout, DEFAULT_ECONOMIC_PARAMS};
+
+/// Herd sizes straddling the price pivot and greed decay pivot
+pub const VECTOR_COWS: [u64; 12] = [0, 1, 10, 100, 500, 1_000, 1_500, 2_500, 5_000, 10_000, 50_000, 1_000_000];
+
+/// Pool balances from empty to ten times the initial TVL
+pub const VECTOR_TVLS: [u64; 7] = [
+    0,
+    1_000_000_000,
+    100_000_000_000,
+    1_000_000_000_000,
+    10_000_000_000_000,
+    INITIAL_TVL,
+    10 * INITIAL_TVL,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceVector {
+    pub global_cows: u64,
+    pub cow_price: u64,
+    /// Payout for selling one cow, absent when there is none to sell
+    pub sell_one_payout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RewardVector {
+    pub global_cows: u64,
+    pub tvl: u64,
+    pub reward_rate: u64,
+}
+
+pub fn price_vectors() -> Vec<PriceVector> {
+    VECTOR_COWS
+        .iter()
+        .map(|&global_cows| PriceVector {
+            global_cows,
+            cow_price: calculate_cow_price_with(&DEFAULT_ECONOMIC_PARAMS, global_cows).unwrap(),
+            sell_one_payout: (global_cows > 0).then(|| calculate_sell_payout(&DEFAULT_ECONOMIC_PARAMS, global_cows, 1).unwrap()),
+        })
+        .collect()
+}
+
+pub fn reward_vectors() -> Vec<RewardVector> {
+    VECTOR_COWS
+        .iter()
+        .flat_map(|&global_cows| {
+            VECTOR_TVLS.iter().map(move |&tvl| RewardVector {
+                global_cows,
+                tvl,
+                reward_rate: calculate_reward_rate_with(&DEFAULT_ECONOMIC_PARAMS, global_cows, tvl).unwrap(),
+            })
+        })
+        .collect()
+}
+
+/// Render the vectors as JSON. Amounts are strings so that JavaScript
+/// readers don't lose precision above 2^53.
+pub fn economy_vectors_json() -> String {
+    let prices: Vec<String> = price_vectors()
+        .iter()
+        .map(|v| {
+            let sell = v.sell_one_payout.map_or("null".to_string(), |p| format!("\"{}\"", p));
+            format!(
+                "    {{ \"global_cows\": \"{}\", \"cow_price\": \"{}\", \"sell_one_payout\": {} }}",
+                v.global_cows, v.cow_price, sell
+            )
+        })
+        .collect();
+    let rewards: Vec<String> = reward_vectors()
+        .iter()
+        .map(|v| {
+            format!(
+                "    {{ \"global_cows\": \"{}\", \"tvl\": \"{}\", \"reward_rate\": \"{}\" }}",
+                v.global_cows, v.tvl, v.reward_rate
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"version\": 1,\n  \"cow_price\": [\n{}\n  ],\n  \"reward_rate\": [\n{}\n  ]\n}}\n",
+        prices.join(",\n"),
+        rewards.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn committed_vectors_match_math() {
+        assert_eq!(
+            economy_vectors_json(),
+            include_str!("../vectors/economy.json"),
+            "economy math changed; regenerate with `cargo run --example export_vectors --features client`"
+        );
+    }
+
+    #[test]
+    fn price_grows_with_herd() {
+        let prices = price_vectors();
+        assert!(prices.windows(2).all(|w| w[0].cow_price <= w[1].cow_price));
+        assert_eq!(prices[0].cow_price, crate::constants::COW_BASE_PRICE);
+    }
+
+    #[test]
+    fn selling_never_beats_buying() {
+        for v in price_vectors() {
+            if let Some(payout) = v.sell_one_payout {
+                assert!(payout < calculate_cow_price_with(&DEFAULT_ECONOMIC_PARAMS, v.global_cows - 1).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn reward_rate_respects_floor() {
+        for v in reward_vectors() {
+            assert!(v.reward_rate >= crate::constants::MIN_REWARD_PER_DAY);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Verifiable randomness read from an ORAO VRF randomness request account.
+//!
+//! The account layout mirrors ORAO's `Randomness` so the program doesn't
+//! need the VRF SDK as a dependency. Clients send ORAO's `request`
+//! instruction with a fresh seed in the same transaction as the instruction
+//! that commits to it; ORAO fulfills it a few slots later.
+
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// ORAO VRF program, owner of every randomness request account
+const ORAO_VRF_PROGRAM_ID: Pubkey = pubkey!("VRFzZoJdhFWL8rkvu87LpKM3RbcVezpMEc6X5GVDr7y");
+
+/// Anchor discriminator of `Randomness`
+const RANDOMNESS_DISCRIMINATOR: [u8; 8] = [188, 96, 216, 248, 93, 94, 49, 112];
+
+#[derive(AnchorDeserialize)]
+struct Randomness {
+    seed: [u8; 32],
+    randomness: [u8; 64],
+    // Followed by the individual fulfillment authorities' responses
+}
+
+/// The randomness of the ORAO request for `seed`, or None while it is
+/// still waiting on fulfillment.
+pub(crate) fn fulfilled_randomness(request: &AccountInfo, seed: &[u8; 32]) -> Result<Option<[u8; 64]>> {
+    require_keys_eq!(*request.owner, ORAO_VRF_PROGRAM_ID, ErrorCode::InvalidRandomness);
+
+    let data = request.try_borrow_data()?;
+    require!(
+        data.len() > 8 && data[..8] == RANDOMNESS_DISCRIMINATOR,
+        ErrorCode::InvalidRandomness
+    );
+    let randomness = Randomness::deserialize(&mut &data[8..])
+        .map_err(|_| ErrorCode::InvalidRandomness)?;
+    require!(randomness.seed == *seed, ErrorCode::InvalidRandomness);
+
+    if randomness.randomness == [0u8; 64] {
+        return Ok(None);
+    }
+    Ok(Some(randomness.randomness))
+}
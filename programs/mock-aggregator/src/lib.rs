@@ -0,0 +1,156 @@
+//! Test-only yield vault that wraps a milkerfun farm through the adapter
+//! instructions. The vault PDA owns the farm and the MILK it deposits; a
+//! real aggregator would add share accounting on top.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use milkerfun::cpi::accounts::{DepositFor, HarvestFor, WithdrawFor};
+use milkerfun::program::Milkerfun;
+
+declare_id!("CtfsuoryYLwGyvE6GVwa1XHHgjM3cfFucq8aTvJSBbp9");
+
+const VAULT_SEED: &[u8] = b"vault";
+
+#[program]
+pub mod mock_aggregator {
+    use super::*;
+
+    /// Buy cows for the vault's position with MILK held by the vault
+    pub fn deposit(ctx: Context<VaultDeposit>, num_cows: u64, max_total_cost: u64) -> Result<()> {
+        let bump = [ctx.bumps.vault];
+        let seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &bump]];
+        let m = &ctx.accounts.milkerfun;
+        milkerfun::cpi::deposit_for(
+            CpiContext::new_with_signer(
+                m.milkerfun_program.to_account_info(),
+                DepositFor {
+                    config: m.config.to_account_info(),
+                    farm: m.farm.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                    payer: ctx.accounts.payer.to_account_info(),
+                    source_token_account: ctx.accounts.vault_token_account.to_account_info(),
+                    pool_token_account: m.pool_token_account.to_account_info(),
+                    slot_hashes: ctx.accounts.slot_hashes.to_account_info(),
+                    milk_price: None,
+                    pyth_price_update: None,
+                    milk_mint: m.milk_mint.to_account_info(),
+                    token_program: m.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                seeds,
+            ),
+            num_cows,
+            max_total_cost,
+        )
+    }
+
+    /// Harvest the position's rewards back into the vault
+    pub fn harvest(ctx: Context<VaultAction>, min_amount: u64) -> Result<()> {
+        let bump = [ctx.bumps.vault];
+        let seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &bump]];
+        let m = &ctx.accounts.milkerfun;
+        milkerfun::cpi::harvest_for(
+            CpiContext::new_with_signer(
+                m.milkerfun_program.to_account_info(),
+                HarvestFor {
+                    config: m.config.to_account_info(),
+                    farm: m.farm.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                    recipient_token_account: ctx.accounts.vault_token_account.to_account_info(),
+                    pool_token_account: m.pool_token_account.to_account_info(),
+                    pool_authority: ctx.accounts.pool_authority.to_account_info(),
+                    penalty_grace: None,
+                    milk_mint: m.milk_mint.to_account_info(),
+                    token_program: m.token_program.to_account_info(),
+                },
+                seeds,
+            ),
+            min_amount,
+        )
+    }
+
+    /// Sell cows out of the position back into the vault
+    pub fn withdraw(ctx: Context<VaultAction>, num_cows: u64, min_payout: u64) -> Result<()> {
+        let bump = [ctx.bumps.vault];
+        let seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &bump]];
+        let m = &ctx.accounts.milkerfun;
+        milkerfun::cpi::withdraw_for(
+            CpiContext::new_with_signer(
+                m.milkerfun_program.to_account_info(),
+                WithdrawFor {
+                    config: m.config.to_account_info(),
+                    farm: m.farm.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                    recipient_token_account: ctx.accounts.vault_token_account.to_account_info(),
+                    pool_token_account: m.pool_token_account.to_account_info(),
+                    pool_authority: ctx.accounts.pool_authority.to_account_info(),
+                    milk_mint: m.milk_mint.to_account_info(),
+                    token_program: m.token_program.to_account_info(),
+                },
+                seeds,
+            ),
+            num_cows,
+            min_payout,
+        )
+    }
+}
+
+/// Accounts milkerfun validates itself
+#[derive(Accounts)]
+pub struct MilkerfunAccounts<'info> {
+    /// CHECK: checked by milkerfun
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: checked by milkerfun
+    #[account(mut)]
+    pub farm: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub milk_mint: InterfaceAccount<'info, Mint>,
+
+    pub milkerfun_program: Program<'info, Milkerfun>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct VaultDeposit<'info> {
+    /// CHECK: PDA signer for the vault's position, holds no data
+    #[account(seeds = [VAULT_SEED], bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::authority = vault,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: SlotHashes sysvar, checked by milkerfun
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub milkerfun: MilkerfunAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VaultAction<'info> {
+    /// CHECK: PDA signer for the vault's position, holds no data
+    #[account(seeds = [VAULT_SEED], bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::authority = vault,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: checked by milkerfun
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub milkerfun: MilkerfunAccounts<'info>,
+}
@@ -44,6 +44,9 @@ async function main() {
   // Get MILK and COW mints based on cluster
   let milkMint: PublicKey;
   let cowMint: PublicKey;
+  // Strict mode: null skips the check, otherwise the MILK mint's authorities
+  // must match exactly (null authority = burned)
+  let milkAuthorityExpectation: { mintAuthority: PublicKey | null; freezeAuthority: PublicKey | null } | null = null;
   
   if (provider.connection.rpcEndpoint.includes('devnet')) {
     milkMint = MILK_MINT_DEVNET;
@@ -53,6 +56,7 @@ async function main() {
   } else if (provider.connection.rpcEndpoint.includes('mainnet')) {
     milkMint = MILK_MINT_MAINNET;
     cowMint = COW_MINT_MAINNET;
+    milkAuthorityExpectation = { mintAuthority: null, freezeAuthority: null };
     console.log("Using MILK token on mainnet");
     console.log("Using COW token on mainnet");
   } else {
@@ -201,7 +205,7 @@ async function main() {
   console.log("Initializing config with verified pool token account and COW mint...");
   try {
     tx = await program.methods
-      .initializeConfig()
+      .initializeConfig(milkAuthorityExpectation)
       .accountsPartial({
         milkMint: milkMint,
         cowMint: cowMint,
@@ -56,7 +56,11 @@ async function main() {
     const poolBalanceMilk = Number(poolBalanceRaw) / 1_000_000;
 
     console.log(`🌍 Total Cows in Ecosystem: ${totalCows}`);
+    console.log(`🐄 Farming Cows (productive): ${globalStats.farmingCowsCount.toString()}`);
+    console.log(`🪙 Tokenized Cows (circulating COW): ${globalStats.tokenizedCowsCount.toString()}`);
+    console.log(`🛒 Max Cows per Buy: ${globalStats.maxCowsPerTransaction.toString()}`);
     console.log(`💰 Total Value Locked (TVL): ${poolBalanceMilk.toLocaleString()} MILK`);
+    console.log(`🕒 Pool balance as of: ${new Date(globalStats.poolBalanceAsOf.toNumber() * 1000).toISOString()}`);
     
     // Format TVL in different units for readability
     if (poolBalanceMilk >= 1_000_000) {
@@ -9,6 +9,8 @@ import * as os from "os";
 /**
  * Admin script for V3 migration (emergency withdrawal)
  * ADMIN ONLY - Withdraws all MILK from pool for protocol upgrade
+ *
+ * Run once to queue the migration, then again after the timelock to execute it
  */
 async function main() {
   // Set up provider manually
@@ -93,19 +95,40 @@ async function main() {
     // Confirm migration
     console.log("\n⚠️  WARNING: V3 MIGRATION");
 
-    
-    // In a real scenario, you might want to add a confirmation prompt here
-    // For automation, we'll proceed directly
+    const executableAt = config.migrationExecutableAt.toNumber();
+    if (executableAt === 0) {
+      console.log("\n🔄 Queueing V3 migration...");
+
+      const queueTx = await program.methods
+        .queueMigration()
+        .accountsPartial({
+          config: configPda,
+          admin: wallet.publicKey,
+          destinationTokenAccount: adminTokenAccount,
+          poolTokenAccount: config.poolTokenAccount,
+        })
+        .rpc();
+
+      const queued = await program.account.config.fetch(configPda);
+      console.log("✅ Migration queued:", queueTx);
+      console.log("⏳ Executable from:", new Date(queued.migrationExecutableAt.toNumber() * 1000).toISOString());
+      console.log("Run this script again after the timelock to execute it");
+      return;
+    }
+
+    if (Date.now() / 1000 < executableAt) {
+      console.log("⏳ Migration queued, executable from:", new Date(executableAt * 1000).toISOString());
+      return;
+    }
 
     console.log("\n🔄 Executing V3 migration...");
 
-    // Execute v3_migrating transaction
     const tx = await program.methods
-      .v3Migrating()
+      .executeMigration()
       .accountsPartial({
         config: configPda,
         admin: wallet.publicKey,
-        adminTokenAccount: adminTokenAccount,
+        destinationTokenAccount: config.migrationDestination,
         poolTokenAccount: config.poolTokenAccount,
         poolAuthority: poolAuthorityPda,
       })
@@ -162,6 +162,8 @@ async function testBuyCows() {
     // Test buying 1 cow
     const numCows = 1;
     const totalCost = currentCowPrice * numCows;
+    // Allow the price to move 5% before the transaction lands
+    const maxPricePerCow = new anchor.BN(Math.ceil(currentCowPrice * 1.05 * 1_000_000));
 
     if (userBalance < totalCost) {
       console.log(`❌ Insufficient balance. Need ${totalCost} MILK but have ${userBalance} MILK`);
@@ -192,7 +194,7 @@ async function testBuyCows() {
     let tx;
     try {
       const txBuilder = program.methods
-        .buyCows(new anchor.BN(numCows))
+        .buyCows(new anchor.BN(numCows), maxPricePerCow)
         .accountsPartial({
           config: configPda,
           farm: farmPda,
@@ -200,6 +202,7 @@ async function testBuyCows() {
           userTokenAccount: userTokenAccount,
           poolTokenAccount: poolTokenAccount,
           poolAuthority: poolAuthorityPda,
+          milkMint: config.milkMint,
         });
 
       console.log("🔄 Sending transaction...");
@@ -102,8 +102,8 @@ async function testCompoundCows() {
     const currentTime = Math.floor(Date.now() / 1000);
     const timeSinceUpdate = currentTime - farm.lastUpdateTime.toNumber();
     
-    // Use stored reward rate or calculate current
-    const storedRate = farm.lastRewardRate ? farm.lastRewardRate.toNumber() : 10_000_000; // 10 MILK/day default
+    // Use the live global reward rate
+    const storedRate = config.rewardRate ? config.rewardRate.toNumber() : 10_000_000; // 10 MILK/day default
     const currentRate = storedRate;
     
     const pendingRewards = (farm.cows.toNumber() * currentRate * timeSinceUpdate) / 86400; // per day to per second
@@ -243,7 +243,8 @@ async function testCompoundCows() {
       let newEarningRate = (farmAfter.cows.toNumber() * currentRate) / (86400 * 1_000_000); // per day to per second
       console.log(`📈 New earning rate: ${newEarningRate.toFixed(6)} MILK/second`);
       console.log(`📈 New earning rate: ${(newEarningRate * 86400).toFixed(4)} MILK/day`);
-      console.log(`📈 New stored rate: ${farmAfter.lastRewardRate ? farmAfter.lastRewardRate.toNumber() / 1_000_000 : 'Not set'} MILK/cow/day`);
+      const configAfter = await program.account.config.fetch(configPda, 'confirmed');
+      console.log(`📈 New global rate: ${configAfter.rewardRate.toNumber() / 1_000_000} MILK/cow/day`);
 
       // Transaction verification
       const actualCowIncrease = farmAfter.cows.toNumber() - farm.cows.toNumber();
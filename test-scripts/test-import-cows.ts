@@ -227,7 +227,7 @@ async function testImportCows() {
 
       // Show earning potential
       if (farmAfter.cows.toNumber() > 0) {
-        const currentRewardRate = farmAfter.lastRewardRate ? farmAfter.lastRewardRate.toNumber() / 1_000_000 : 10;
+        const currentRewardRate = configAfter.rewardRate ? configAfter.rewardRate.toNumber() / 1_000_000 : 10;
         const dailyEarnings = farmAfter.cows.toNumber() * currentRewardRate;
         console.log(`\n💰 Earning Potential:`);
         console.log(`📈 Current reward rate: ${currentRewardRate} MILK/cow/day`);
@@ -103,8 +103,8 @@ async function testWithdrawMilk() {
     const currentTime = Math.floor(Date.now() / 1000);
     const timeSinceUpdate = currentTime - farm.lastUpdateTime.toNumber();
     
-    // Use stored reward rate
-    const storedRate = farm.lastRewardRate ? farm.lastRewardRate.toNumber() : 10_000_000; // 10 MILK/day default
+    // Use the live global reward rate
+    const storedRate = config.rewardRate ? config.rewardRate.toNumber() : 10_000_000; // 10 MILK/day default
     
     const pendingRewards = (farm.cows.toNumber() * storedRate * timeSinceUpdate) / (86400 * 1_000_000); // per day to per second
     const totalRewards = (farm.accumulatedRewards.toNumber() / 1_000_000) + pendingRewards;
@@ -175,13 +175,15 @@ async function testWithdrawMilk() {
     }
 
     console.log(`\n💸 Attempting to withdraw ${totalRewards.toFixed(6)} MILK...`);
+    // Rounded down so the amount never exceeds what the farm settles to
+    const withdrawAmount = new anchor.BN(Math.floor(totalRewards * 1_000_000));
 
     // Execute withdraw_milk transaction
     let tx;
     try {
       console.log("🔄 Building transaction...");
       const txBuilder = program.methods
-        .withdrawMilk()
+        .withdrawMilk(withdrawAmount)
         .accountsPartial({
           config: configPda,
           farm: farmPda,
@@ -189,6 +191,7 @@ async function testWithdrawMilk() {
           userTokenAccount: userTokenAccount,
           poolTokenAccount: poolTokenAccount,
           poolAuthority: poolAuthorityPda,
+          milkMint: config.milkMint,
         });
 
       console.log("🔄 Sending transaction...");
@@ -0,0 +1,169 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Milkerfun } from "../target/types/milkerfun";
+import { MockAggregator } from "../target/types/mock_aggregator";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { PublicKey, SYSVAR_SLOT_HASHES_PUBKEY } from "@solana/web3.js";
+import { expect } from "chai";
+
+/**
+ * Drives a milkerfun position through the adapter instructions from a mock
+ * aggregator program, the way a yield vault would via CPI.
+ */
+describe("vault adapter", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const milkerfun = anchor.workspace.Milkerfun as Program<Milkerfun>;
+  const aggregator = anchor.workspace.MockAggregator as Program<MockAggregator>;
+
+  const [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], milkerfun.programId);
+  const [poolAuthority] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pool_authority"), config.toBuffer()],
+    milkerfun.programId
+  );
+  const [cowMintAuthority] = PublicKey.findProgramAddressSync(
+    [Buffer.from("cow_mint_authority"), config.toBuffer()],
+    milkerfun.programId
+  );
+  const [vault] = PublicKey.findProgramAddressSync([Buffer.from("vault")], aggregator.programId);
+  const [farm] = PublicKey.findProgramAddressSync(
+    [Buffer.from("farm"), vault.toBuffer()],
+    milkerfun.programId
+  );
+
+  let milkMint: PublicKey;
+  let poolTokenAccount: PublicKey;
+  let vaultTokenAccount: PublicKey;
+
+  const milkerfunAccounts = () => ({
+    config,
+    farm,
+    poolTokenAccount,
+    milkMint,
+    milkerfunProgram: milkerfun.programId,
+    tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+  });
+
+  const vaultBalance = async () =>
+    (await getAccount(provider.connection, vaultTokenAccount)).amount;
+
+  before(async () => {
+    milkMint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    const cowMint = await createMint(provider.connection, payer, cowMintAuthority, cowMintAuthority, 6);
+
+    poolTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, milkMint, poolAuthority, true)
+    ).address;
+    vaultTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, milkMint, vault, true)
+    ).address;
+
+    await mintTo(provider.connection, payer, milkMint, poolTokenAccount, payer, 100_000_000_000_000n);
+    await mintTo(provider.connection, payer, milkMint, vaultTokenAccount, payer, 1_000_000_000_000n);
+
+    await milkerfun.methods
+      .initializeConfig(null)
+      .accountsPartial({
+        milkMint,
+        cowMint,
+        cowMintAuthority,
+        poolTokenAccount,
+        admin: payer.publicKey,
+      })
+      .rpc();
+  });
+
+  it("deposits into the vault's farm", async () => {
+    const before = await vaultBalance();
+
+    await aggregator.methods
+      .deposit(new anchor.BN(2), new anchor.BN(1_000_000_000_000))
+      .accountsPartial({
+        vaultTokenAccount,
+        payer: payer.publicKey,
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        milkerfun: milkerfunAccounts(),
+      })
+      .rpc();
+
+    const position = await milkerfun.account.farmAccount.fetch(farm);
+    expect(position.owner.toBase58()).to.equal(vault.toBase58());
+    expect(position.cows.toNumber()).to.equal(2);
+    expect((await vaultBalance()) < before).to.be.true;
+  });
+
+  it("rejects a deposit above the cost limit", async () => {
+    try {
+      await aggregator.methods
+        .deposit(new anchor.BN(1), new anchor.BN(1))
+        .accountsPartial({
+          vaultTokenAccount,
+          payer: payer.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          milkerfun: milkerfunAccounts(),
+        })
+        .rpc();
+      expect.fail("deposit should have failed");
+    } catch (err) {
+      expect(String(err)).to.contain("SlippageExceeded");
+    }
+  });
+
+  it("harvests rewards back into the vault", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    const before = await vaultBalance();
+
+    await aggregator.methods
+      .harvest(new anchor.BN(1))
+      .accountsPartial({
+        vaultTokenAccount,
+        poolAuthority,
+        milkerfun: milkerfunAccounts(),
+      })
+      .rpc();
+
+    expect((await vaultBalance()) > before).to.be.true;
+    const position = await milkerfun.account.farmAccount.fetch(farm);
+    expect(position.accumulatedRewards.toNumber()).to.equal(0);
+  });
+
+  it("rejects a harvest below the minimum", async () => {
+    try {
+      await aggregator.methods
+        .harvest(new anchor.BN("18446744073709551615"))
+        .accountsPartial({
+          vaultTokenAccount,
+          poolAuthority,
+          milkerfun: milkerfunAccounts(),
+        })
+        .rpc();
+      expect.fail("harvest should have failed");
+    } catch (err) {
+      expect(String(err)).to.contain("SlippageExceeded");
+    }
+  });
+
+  it("withdraws cows back into the vault", async () => {
+    const before = await vaultBalance();
+
+    await aggregator.methods
+      .withdraw(new anchor.BN(1), new anchor.BN(1))
+      .accountsPartial({
+        vaultTokenAccount,
+        poolAuthority,
+        milkerfun: milkerfunAccounts(),
+      })
+      .rpc();
+
+    const position = await milkerfun.account.farmAccount.fetch(farm);
+    expect(position.cows.toNumber()).to.equal(1);
+    expect((await vaultBalance()) > before).to.be.true;
+  });
+});